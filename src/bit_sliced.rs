@@ -0,0 +1,355 @@
+use crate::BitVec;
+use std::convert::TryFrom;
+
+
+/// A bit-sliced index over a column of unsigned integers, with one bit vector
+/// for each bit position of the values marking the rows where that bit is set.
+///
+/// The predicates follow O'Neil and Quass: a comparison with a constant walks
+/// the slices from the most significant one down, narrowing the rows that are
+/// still equal to the constant so far, so it takes one pass of bit vector
+/// operations per slice no matter how many distinct values the column holds.
+/// Like [`BitmapIndex`], the predicates return the matching rows as bit
+/// vectors with one bit per row.
+///
+/// [`BitmapIndex`]: crate::BitmapIndex
+///
+/// ## Example
+///
+/// ```
+/// # use bitset::{
+/// #     BitSlicedIndex,
+/// # };
+/// #
+/// let prices: BitSlicedIndex = vec![30, 5, 12, 30, 18].into_iter().collect();
+///
+/// assert_eq!(prices.lt(12).iter().collect::<Vec<_>>(), vec![1]);
+/// assert_eq!(prices.between(10, 20).iter().collect::<Vec<_>>(), vec![2, 4]);
+/// assert_eq!(prices.sum(&prices.ge(18)), 78);
+/// assert_eq!(prices.top_k(2).iter().collect::<Vec<_>>(), vec![0, 3]);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BitSlicedIndex {
+    /// The rows where each bit of the value is set, from the least significant
+    /// bit up. There are only as many slices as the widest value needs.
+    slices: Vec<BitVec>,
+    rows: usize,
+}
+
+impl BitSlicedIndex {
+    /// Construct a new index over an empty column.
+    pub fn new() -> BitSlicedIndex {
+        BitSlicedIndex {
+            slices: Vec::new(),
+            rows: 0,
+        }
+    }
+
+    /// Return the number of rows of the column.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.rows
+    }
+
+    /// Determine whether the column has no rows.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.rows == 0
+    }
+
+    /// Return the number of bit slices, which is the width of the widest value
+    /// in the column.
+    #[inline]
+    pub fn slice_count(&self) -> usize {
+        self.slices.len()
+    }
+
+    /// Append a row holding `value` to the column, returning the row.
+    pub fn push(&mut self, value: u64) -> usize {
+        let width = 64 - value.leading_zeros() as usize;
+        while self.slices.len() < width {
+            self.slices.push(BitVec::zeros(self.rows));
+        }
+        for (bit, slice) in self.slices.iter_mut().enumerate() {
+            slice.push((value >> bit) & 1 != 0);
+        }
+        self.rows += 1;
+
+        self.rows - 1
+    }
+
+    /// Return the value of row `row`, or `None` if the row is out of range.
+    pub fn get(&self, row: usize) -> Option<u64> {
+        if row >= self.rows {
+            return None;
+        }
+        let value = self.slices.iter()
+            .enumerate()
+            .filter(|(_, slice)| slice.get(row) == Some(true))
+            .fold(0, |value, (bit, _)| value | (1 << bit));
+
+        Some(value)
+    }
+
+    /// Split the rows into those holding a value less than, equal to, and
+    /// greater than `value`.
+    fn compare(&self, value: u64) -> (BitVec, BitVec, BitVec) {
+        let all = BitVec::ones(self.rows);
+        if self.slices.len() < 64 && value >> self.slices.len() != 0 {
+            // The constant is wider than every value.
+            return (all, BitVec::zeros(self.rows), BitVec::zeros(self.rows));
+        }
+        let mut less = BitVec::zeros(self.rows);
+        let mut greater = BitVec::zeros(self.rows);
+        let mut equal = all;
+        for (bit, slice) in self.slices.iter().enumerate().rev() {
+            if (value >> bit) & 1 != 0 {
+                less |= &equal & &!slice;
+                equal &= slice;
+            } else {
+                greater |= &equal & slice;
+                equal &= &!slice;
+            }
+        }
+
+        (less, equal, greater)
+    }
+
+    /// Find the rows holding a value less than `value`.
+    #[inline]
+    pub fn lt(&self, value: u64) -> BitVec {
+        self.compare(value).0
+    }
+
+    /// Find the rows holding a value less than or equal to `value`.
+    #[inline]
+    pub fn le(&self, value: u64) -> BitVec {
+        let (less, equal, _) = self.compare(value);
+
+        less | equal
+    }
+
+    /// Find the rows holding `value`.
+    #[inline]
+    pub fn eq(&self, value: u64) -> BitVec {
+        self.compare(value).1
+    }
+
+    /// Find the rows holding a value greater than `value`.
+    #[inline]
+    pub fn gt(&self, value: u64) -> BitVec {
+        self.compare(value).2
+    }
+
+    /// Find the rows holding a value greater than or equal to `value`.
+    #[inline]
+    pub fn ge(&self, value: u64) -> BitVec {
+        let (_, equal, greater) = self.compare(value);
+
+        equal | greater
+    }
+
+    /// Find the rows holding a value in `low..=high`.
+    #[inline]
+    pub fn between(&self, low: u64, high: u64) -> BitVec {
+        self.ge(low) & self.le(high)
+    }
+
+    /// Compute the sum of the values of the rows set in `rows`, by weighting
+    /// the number of those rows in each slice by the place value of the slice.
+    pub fn sum(&self, rows: &BitVec) -> u128 {
+        self.slices.iter()
+            .enumerate()
+            .map(|(bit, slice)| (slice.and_count(rows) as u128) << bit)
+            .sum()
+    }
+
+    /// Find the `k` rows holding the largest values, preferring the earlier
+    /// rows among equal values.
+    ///
+    /// Every row is returned if the column has at most `k` rows.
+    pub fn top_k(&self, k: usize) -> BitVec {
+        if k >= self.rows {
+            return BitVec::ones(self.rows);
+        }
+        // The rows known to be among the largest, and the rows whose values
+        // are tied with the boundary so far.
+        let mut greater = BitVec::zeros(self.rows);
+        let mut tied = BitVec::ones(self.rows);
+        for slice in self.slices.iter().rev() {
+            let candidates = &greater | &(&tied & slice);
+            let count = candidates.count();
+            if count > k {
+                tied &= slice;
+            } else if count < k {
+                greater = candidates;
+                tied &= &!slice;
+            } else {
+                return candidates;
+            }
+        }
+        // Fill up the result with the earliest of the tied rows.
+        let missing = k - greater.count();
+        for row in tied.iter().take(missing) {
+            greater.set(row, true);
+        }
+
+        greater
+    }
+}
+
+impl Extend<u64> for BitSlicedIndex {
+    fn extend<I: IntoIterator<Item = u64>>(&mut self, iter: I) {
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
+
+impl std::iter::FromIterator<u64> for BitSlicedIndex {
+    fn from_iter<I: IntoIterator<Item = u64>>(iter: I) -> Self {
+        let mut index = BitSlicedIndex::new();
+        index.extend(iter);
+
+        index
+    }
+}
+
+
+/// A range-encoded bitmap index over a column of small unsigned integers in
+/// `0..=max_value`, with one bit vector for each value `v` marking the rows
+/// holding a value of at most `v`.
+///
+/// Any range predicate reads at most two bit vectors, which makes it faster
+/// than a [`BitSlicedIndex`] for columns with few distinct values, at the cost
+/// of one bit vector per value instead of one per bit.
+///
+/// ## Example
+///
+/// ```
+/// # use bitset::{
+/// #     RangeEncodedIndex,
+/// # };
+/// #
+/// let mut ratings = RangeEncodedIndex::new(5).unwrap();
+/// for rating in vec![4, 1, 5, 3, 4] {
+///     ratings.push(rating).unwrap();
+/// }
+///
+/// assert_eq!(ratings.ge(4).iter().collect::<Vec<_>>(), vec![0, 2, 4]);
+/// assert_eq!(ratings.between(2, 3).iter().collect::<Vec<_>>(), vec![3]);
+/// assert_eq!(ratings.push(6), None);
+/// assert!(RangeEncodedIndex::new(u64::MAX).is_none());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RangeEncodedIndex {
+    /// The rows holding a value of at most each value below the largest one,
+    /// which would mark every row.
+    bitmaps: Vec<BitVec>,
+    max_value: u64,
+    rows: usize,
+}
+
+impl RangeEncodedIndex {
+    /// The largest `max_value` of an index. The index keeps a bit vector for
+    /// each value, so a column with more distinct values than this calls for a
+    /// [`BitSlicedIndex`] instead.
+    pub const MAX_VALUE_LIMIT: u64 = 65_535;
+
+    /// Construct a new index over an empty column of values in
+    /// `0..=max_value`.
+    ///
+    /// The function returns `None` if `max_value` exceeds
+    /// [`RangeEncodedIndex::MAX_VALUE_LIMIT`].
+    pub fn new(max_value: u64) -> Option<RangeEncodedIndex> {
+        if max_value > RangeEncodedIndex::MAX_VALUE_LIMIT {
+            return None;
+        }
+        let bitmaps = usize::try_from(max_value).ok()?;
+
+        Some(RangeEncodedIndex {
+            bitmaps: vec![BitVec::new(); bitmaps],
+            max_value,
+            rows: 0,
+        })
+    }
+
+    /// Return the largest value the column can hold.
+    #[inline]
+    pub fn max_value(&self) -> u64 {
+        self.max_value
+    }
+
+    /// Return the number of rows of the column.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.rows
+    }
+
+    /// Determine whether the column has no rows.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.rows == 0
+    }
+
+    /// Append a row holding `value` to the column, returning the row.
+    ///
+    /// The function returns `None`, and leaves the column unchanged, if the
+    /// value exceeds the largest value of the column.
+    pub fn push(&mut self, value: u64) -> Option<usize> {
+        if value > self.max_value {
+            return None;
+        }
+        for (bound, bitmap) in self.bitmaps.iter_mut().enumerate() {
+            bitmap.push(value <= bound as u64);
+        }
+        self.rows += 1;
+
+        Some(self.rows - 1)
+    }
+
+    /// Find the rows holding a value less than or equal to `value`.
+    pub fn le(&self, value: u64) -> BitVec {
+        if value >= self.max_value {
+            BitVec::ones(self.rows)
+        } else {
+            self.bitmaps[value as usize].clone()
+        }
+    }
+
+    /// Find the rows holding a value less than `value`.
+    pub fn lt(&self, value: u64) -> BitVec {
+        match value.checked_sub(1) {
+            Some(bound) => self.le(bound),
+            None => BitVec::zeros(self.rows),
+        }
+    }
+
+    /// Find the rows holding `value`.
+    pub fn eq(&self, value: u64) -> BitVec {
+        if value > self.max_value {
+            return BitVec::zeros(self.rows);
+        }
+        self.le(value) & !self.lt(value)
+    }
+
+    /// Find the rows holding a value greater than `value`.
+    #[inline]
+    pub fn gt(&self, value: u64) -> BitVec {
+        !self.le(value)
+    }
+
+    /// Find the rows holding a value greater than or equal to `value`.
+    #[inline]
+    pub fn ge(&self, value: u64) -> BitVec {
+        !self.lt(value)
+    }
+
+    /// Find the rows holding a value in `low..=high`.
+    pub fn between(&self, low: u64, high: u64) -> BitVec {
+        if low > high {
+            return BitVec::zeros(self.rows);
+        }
+        self.le(high) & !self.lt(low)
+    }
+}
@@ -0,0 +1,167 @@
+use crate::BitVec;
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::collections::hash_map;
+use std::hash::Hash;
+
+
+/// An equality-encoded bitmap index over a column of values, with one bit
+/// vector for each distinct value marking the rows that hold it.
+///
+/// The predicates return the matching rows as a bit vector with one bit per
+/// row, so predicates over several indexes of the same table combine with the
+/// logic operators of [`BitVec`]. Rows are numbered from zero in the order in
+/// which they are appended.
+///
+/// ## Example
+///
+/// ```
+/// # use bitset::{
+/// #     BitmapIndex,
+/// # };
+/// #
+/// let cities: BitmapIndex<&str> = vec!["Oslo", "Lima", "Oslo", "Pune"].into_iter().collect();
+/// let tiers: BitmapIndex<u8> = vec![1, 2, 2, 1].into_iter().collect();
+///
+/// // The rows where the city is Oslo or Pune and the tier is not 2.
+/// let rows = &cities.in_set(&["Oslo", "Pune"]) & &tiers.not_eq(&2);
+///
+/// assert_eq!(rows.iter().collect::<Vec<_>>(), vec![0, 3]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct BitmapIndex<T> where T: Hash + Eq {
+    /// The rows holding each value. The bits past the last row holding a value
+    /// are left out, and read as `false`.
+    bitmaps: HashMap<T, BitVec>,
+    rows: usize,
+}
+
+impl<T> BitmapIndex<T> where T: Hash + Eq {
+    /// Construct a new index over an empty column.
+    pub fn new() -> BitmapIndex<T> {
+        BitmapIndex {
+            bitmaps: HashMap::new(),
+            rows: 0,
+        }
+    }
+
+    /// Return the number of rows of the column.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.rows
+    }
+
+    /// Determine whether the column has no rows.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.rows == 0
+    }
+
+    /// Return the number of distinct values in the column.
+    #[inline]
+    pub fn cardinality(&self) -> usize {
+        self.bitmaps.len()
+    }
+
+    /// Construct an iterator over the distinct values in the column, in
+    /// arbitrary order.
+    #[inline]
+    pub fn values(&self) -> hash_map::Keys<'_, T, BitVec> {
+        self.bitmaps.keys()
+    }
+
+    /// Append a row holding `value` to the column, returning the row.
+    pub fn push(&mut self, value: T) -> usize {
+        let row = self.rows;
+        let bitmap = self.bitmaps.entry(value).or_default();
+        bitmap.resize(row, false);
+        bitmap.push(true);
+        self.rows += 1;
+
+        row
+    }
+
+    /// Count up the number of rows holding `value`.
+    pub fn count<Q>(&self, value: &Q) -> usize where T: Borrow<Q>, Q: Hash + Eq + ?Sized {
+        self.bitmaps.get(value).map_or(0, |bitmap| bitmap.count())
+    }
+
+    /// Find the rows holding `value`.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use bitset::{
+    /// #     BitmapIndex,
+    /// # };
+    /// #
+    /// let mut index = BitmapIndex::new();
+    /// for value in vec![3, 1, 3, 2, 3] {
+    ///     index.push(value);
+    /// }
+    ///
+    /// assert_eq!(index.eq(&3).iter().collect::<Vec<_>>(), vec![0, 2, 4]);
+    /// assert_eq!(index.not_eq(&3).iter().collect::<Vec<_>>(), vec![1, 3]);
+    /// assert_eq!(index.in_set(&[1, 2]).iter().collect::<Vec<_>>(), vec![1, 3]);
+    /// assert!(index.eq(&7).none());
+    /// ```
+    pub fn eq<Q>(&self, value: &Q) -> BitVec where T: Borrow<Q>, Q: Hash + Eq + ?Sized {
+        let mut rows = self.bitmaps.get(value).cloned().unwrap_or_default();
+        rows.resize(self.rows, false);
+
+        rows
+    }
+
+    /// Find the rows not holding `value`.
+    pub fn not_eq<Q>(&self, value: &Q) -> BitVec where T: Borrow<Q>, Q: Hash + Eq + ?Sized {
+        !self.eq(value)
+    }
+
+    /// Find the rows holding any of the values.
+    pub fn in_set<'a, Q, I>(&self, values: I) -> BitVec
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized + 'a,
+        I: IntoIterator<Item = &'a Q>
+    {
+        let mut rows = BitVec::zeros(self.rows);
+        for bitmap in values.into_iter().filter_map(|value| self.bitmaps.get(value)) {
+            rows |= bitmap;
+        }
+
+        rows
+    }
+
+    /// Find the rows holding none of the values.
+    pub fn not_in_set<'a, Q, I>(&self, values: I) -> BitVec
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized + 'a,
+        I: IntoIterator<Item = &'a Q>
+    {
+        !self.in_set(values)
+    }
+}
+
+impl<T> Default for BitmapIndex<T> where T: Hash + Eq {
+    fn default() -> BitmapIndex<T> {
+        BitmapIndex::new()
+    }
+}
+
+impl<T> Extend<T> for BitmapIndex<T> where T: Hash + Eq {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
+
+impl<T> std::iter::FromIterator<T> for BitmapIndex<T> where T: Hash + Eq {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut index = BitmapIndex::new();
+        index.extend(iter);
+
+        index
+    }
+}
@@ -0,0 +1,376 @@
+use crate::{
+    BitSet,
+    BitVec,
+};
+use crate::transpose::transpose_128;
+use std::fmt;
+use std::ops;
+
+
+/// A dense matrix of bits with `rows × cols` entries, where each row is stored
+/// as a bit vector.
+///
+/// Bit matrices model boolean relations: the entry at row `i` and column `j`
+/// is `true` when `i` is related to `j`. Matrix multiplication composes
+/// relations, and the element-wise logic operators take the intersection,
+/// union, and symmetric difference of relations of the same shape.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BitMatrix {
+    pub(crate) rows: usize,
+    pub(crate) cols: usize,
+    pub(crate) data: Vec<BitVec>,
+}
+
+impl BitMatrix {
+    /// Construct a new matrix with `rows` rows and `cols` columns with all
+    /// entries set to `false`.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use bitset::{
+    /// #     BitMatrix,
+    /// # };
+    /// #
+    /// let matrix = BitMatrix::new(3, 200);
+    ///
+    /// assert_eq!(matrix.rows(), 3);
+    /// assert_eq!(matrix.cols(), 200);
+    /// assert_eq!(matrix.get(2, 199), Some(false));
+    /// ```
+    pub fn new(rows: usize, cols: usize) -> BitMatrix {
+        BitMatrix {
+            rows,
+            cols,
+            data: vec![BitVec::zeros(cols); rows],
+        }
+    }
+
+    /// Construct the `size × size` identity matrix.
+    pub fn identity(size: usize) -> BitMatrix {
+        let mut matrix = BitMatrix::new(size, size);
+        for i in 0..size {
+            matrix.set(i, i, true);
+        }
+
+        matrix
+    }
+
+    /// Construct a matrix from its rows.
+    ///
+    /// The function returns `None` if the rows do not all have the length
+    /// `cols`.
+    pub fn from_rows(rows: Vec<BitVec>, cols: usize) -> Option<BitMatrix> {
+        if rows.iter().all(|row| row.len() == cols) {
+            Some(BitMatrix {
+                rows: rows.len(),
+                cols,
+                data: rows,
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Return the number of rows in the matrix.
+    #[inline]
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Return the number of columns in the matrix.
+    #[inline]
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// Determine whether the matrix has as many rows as columns.
+    #[inline]
+    pub fn is_square(&self) -> bool {
+        self.rows == self.cols
+    }
+
+    /// Get the entry at row `row` and column `col`.
+    ///
+    /// The function returns `None` if the position is outside the matrix.
+    #[inline]
+    pub fn get(&self, row: usize, col: usize) -> Option<bool> {
+        self.data.get(row)?.get(col)
+    }
+
+    /// Set the entry at row `row` and column `col` to `value`.
+    ///
+    /// The function returns `None` if the position is outside the matrix.
+    #[inline]
+    pub fn set(&mut self, row: usize, col: usize, value: bool) -> Option<()> {
+        self.data.get_mut(row)?.set(col, value)
+    }
+
+    /// Return the row at index `row`.
+    ///
+    /// The function returns `None` if `row` is outside the matrix.
+    #[inline]
+    pub fn row(&self, row: usize) -> Option<&BitVec> {
+        self.data.get(row)
+    }
+
+    /// Construct an iterator over the rows of the matrix.
+    #[inline]
+    pub fn iter_rows(&self) -> std::slice::Iter<'_, BitVec> {
+        self.data.iter()
+    }
+
+    /// Copy the column at index `col` into a bit vector.
+    ///
+    /// The function returns `None` if `col` is outside the matrix.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use bitset::{
+    /// #     BitMatrix,
+    /// # };
+    /// #
+    /// let mut matrix = BitMatrix::new(3, 3);
+    /// matrix.set(0, 1, true);
+    /// matrix.set(2, 1, true);
+    /// let column = matrix.column(1).unwrap();
+    ///
+    /// assert_eq!(column.iter().collect::<Vec<_>>(), vec![0, 2]);
+    /// ```
+    pub fn column(&self, col: usize) -> Option<BitVec> {
+        if col < self.cols {
+            Some(self.data.iter().map(|row| row.test(col)).collect())
+        } else {
+            None
+        }
+    }
+
+    /// Count up the number of entries of the matrix that are set to `true`.
+    #[inline]
+    pub fn count(&self) -> usize {
+        self.data.iter().map(|row| row.count()).sum()
+    }
+
+    /// Construct the transpose of the matrix, whose entry at row `j` and column
+    /// `i` is the entry of this matrix at row `i` and column `j`.
+    ///
+    /// The matrix is transposed in `128 × 128` tiles, one block of each row at
+    /// a time, with [`transpose_128`].
+    ///
+    /// [`transpose_128`]: crate::transpose_128
+    pub fn transpose(&self) -> BitMatrix {
+        let mut result = BitMatrix::new(self.cols, self.rows);
+        let mut tile = [BitSet::new(); 128];
+        for row_start in (0..self.rows).step_by(128) {
+            for col_start in (0..self.cols).step_by(128) {
+                for (i, entry) in tile.iter_mut().enumerate() {
+                    *entry = self.data.get(row_start + i)
+                        .map_or(BitSet::new(), |row| row.blocks[col_start / 128]);
+                }
+                transpose_128(&mut tile);
+                // The bits past the length of each row are zero, so the rows
+                // of the tile past the number of columns are zero as well.
+                for (j, entry) in tile.iter().enumerate() {
+                    if let Some(row) = result.data.get_mut(col_start + j) {
+                        row.blocks[row_start / 128] = *entry;
+                    }
+                }
+            }
+        }
+
+        result
+    }
+
+    /// Compute the boolean matrix product of this matrix with `other`.
+    ///
+    /// The entry at row `i` and column `j` of the product is the logical OR
+    /// over `k` of the logical AND of entry `(i, k)` of this matrix and entry
+    /// `(k, j)` of `other`. The function returns `None` if the number of
+    /// columns of this matrix differs from the number of rows of `other`.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use bitset::{
+    /// #     BitMatrix,
+    /// # };
+    /// #
+    /// // The relation 0 -> 1 -> 2.
+    /// let mut matrix = BitMatrix::new(3, 3);
+    /// matrix.set(0, 1, true);
+    /// matrix.set(1, 2, true);
+    /// let product = matrix.multiply(&matrix).unwrap();
+    ///
+    /// assert_eq!(product.get(0, 2), Some(true));
+    /// assert_eq!(product.count(), 1);
+    /// ```
+    pub fn multiply(&self, other: &BitMatrix) -> Option<BitMatrix> {
+        if self.cols != other.rows {
+            return None;
+        }
+
+        let mut result = BitMatrix::new(self.rows, other.cols);
+        for (target, row) in result.data.iter_mut().zip(self.data.iter()) {
+            for k in row.iter() {
+                *target |= &other.data[k];
+            }
+        }
+
+        Some(result)
+    }
+
+    /// Compute the entry-wise logical AND of this matrix and `other`, which is
+    /// the intersection of the two relations.
+    ///
+    /// The function returns `None` if the matrices have different shapes.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use bitset::{
+    /// #     BitMatrix,
+    /// # };
+    /// #
+    /// let mut left = BitMatrix::new(2, 2);
+    /// left.set(0, 0, true);
+    /// left.set(1, 1, true);
+    /// let right = BitMatrix::identity(2);
+    ///
+    /// assert_eq!(left.and(&right), Some(right.clone()));
+    /// assert_eq!(left.xor(&right).map(|matrix| matrix.count()), Some(0));
+    /// assert_eq!(left.or(&BitMatrix::new(2, 3)), None);
+    /// ```
+    #[inline]
+    pub fn and(&self, other: &BitMatrix) -> Option<BitMatrix> {
+        self.zip_with(other, |left, right| left & right)
+    }
+
+    /// Compute the entry-wise logical OR of this matrix and `other`, which is
+    /// the union of the two relations.
+    ///
+    /// The function returns `None` if the matrices have different shapes.
+    #[inline]
+    pub fn or(&self, other: &BitMatrix) -> Option<BitMatrix> {
+        self.zip_with(other, |left, right| left | right)
+    }
+
+    /// Compute the entry-wise logical XOR of this matrix and `other`, which is
+    /// the symmetric difference of the two relations.
+    ///
+    /// The function returns `None` if the matrices have different shapes.
+    #[inline]
+    pub fn xor(&self, other: &BitMatrix) -> Option<BitMatrix> {
+        self.zip_with(other, |left, right| left ^ right)
+    }
+
+    /// Combine two matrices of the same shape entry by entry, returning `None`
+    /// if their shapes differ.
+    fn zip_with<F>(&self, other: &BitMatrix, op: F) -> Option<BitMatrix> where F: Fn(&BitVec, &BitVec) -> BitVec {
+        if (self.rows, self.cols) != (other.rows, other.cols) {
+            return None;
+        }
+        let data = self.data.iter()
+            .zip(other.data.iter())
+            .map(|(left, right)| op(left, right))
+            .collect();
+
+        Some(BitMatrix {
+            rows: self.rows,
+            cols: self.cols,
+            data,
+        })
+    }
+}
+
+impl fmt::Display for BitMatrix {
+    /// Format the matrix as a grid of ones and zeros with one line per row,
+    /// starting from column zero.
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        for (i, row) in self.data.iter().enumerate() {
+            if i > 0 {
+                writeln!(formatter)?;
+            }
+            write!(formatter, "{}", row)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> ops::Mul<&'a BitMatrix> for &BitMatrix {
+    type Output = BitMatrix;
+
+    /// Compute the boolean matrix product of two matrices.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the number of columns of the left operand differs from the
+    /// number of rows of the right operand.
+    #[inline]
+    fn mul(self, other: &'a BitMatrix) -> Self::Output {
+        self.multiply(other)
+            .expect("bit matrix dimensions do not match for multiplication")
+    }
+}
+
+impl<'a> ops::BitAnd<&'a BitMatrix> for &BitMatrix {
+    type Output = BitMatrix;
+
+    /// Compute the entry-wise logical AND of two matrices.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the matrices have different shapes. Use
+    /// [`BitMatrix::and`] to handle mismatched shapes instead.
+    #[inline]
+    fn bitand(self, other: &'a BitMatrix) -> Self::Output {
+        self.and(other)
+            .expect("bit matrices must have the same shape")
+    }
+}
+
+impl<'a> ops::BitOr<&'a BitMatrix> for &BitMatrix {
+    type Output = BitMatrix;
+
+    /// Compute the entry-wise logical OR of two matrices.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the matrices have different shapes. Use
+    /// [`BitMatrix::or`] to handle mismatched shapes instead.
+    #[inline]
+    fn bitor(self, other: &'a BitMatrix) -> Self::Output {
+        self.or(other)
+            .expect("bit matrices must have the same shape")
+    }
+}
+
+impl<'a> ops::BitXor<&'a BitMatrix> for &BitMatrix {
+    type Output = BitMatrix;
+
+    /// Compute the entry-wise logical XOR of two matrices.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the matrices have different shapes. Use
+    /// [`BitMatrix::xor`] to handle mismatched shapes instead.
+    #[inline]
+    fn bitxor(self, other: &'a BitMatrix) -> Self::Output {
+        self.xor(other)
+            .expect("bit matrices must have the same shape")
+    }
+}
+
+impl ops::Not for &BitMatrix {
+    type Output = BitMatrix;
+
+    #[inline]
+    fn not(self) -> Self::Output {
+        BitMatrix {
+            rows: self.rows,
+            cols: self.cols,
+            data: self.data.iter().map(|row| !row).collect(),
+        }
+    }
+}
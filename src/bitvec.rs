@@ -0,0 +1,586 @@
+use crate::BitSet;
+use std::fmt;
+use std::ops;
+
+
+/// The number of bits in each block of a bit vector.
+const BLOCK_BITS: usize = 128;
+
+
+/// A growable sequence of bits stored as a vector of 128-bit bit sets.
+///
+/// Unlike a `BitSet`, a bit vector has a length that can be chosen at runtime
+/// and changed later. Bit vectors can be combined with the same logic operators
+/// as bit sets. When the operands of a binary operator have different lengths,
+/// the shorter one is treated as if it were padded with `false` bits, and the
+/// result has the length of the longer one.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct BitVec {
+    pub(crate) blocks: Vec<BitSet>,
+    pub(crate) length: usize,
+}
+
+impl BitVec {
+    /// Construct a new empty bit vector.
+    #[inline]
+    pub const fn new() -> BitVec {
+        BitVec {
+            blocks: Vec::new(),
+            length: 0,
+        }
+    }
+
+    /// Construct a new bit vector of `length` bits all set to `false`.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use bitset::{
+    /// #     BitVec,
+    /// # };
+    /// #
+    /// let bitvec = BitVec::zeros(300);
+    ///
+    /// assert_eq!(bitvec.len(), 300);
+    /// assert!(bitvec.none());
+    /// ```
+    #[inline]
+    pub fn zeros(length: usize) -> BitVec {
+        BitVec {
+            blocks: vec![BitSet::new(); block_count(length)],
+            length,
+        }
+    }
+
+    /// Construct a new bit vector of `length` bits all set to `true`.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use bitset::{
+    /// #     BitVec,
+    /// # };
+    /// #
+    /// let bitvec = BitVec::ones(300);
+    ///
+    /// assert_eq!(bitvec.count(), 300);
+    /// assert!(bitvec.all());
+    /// ```
+    #[inline]
+    pub fn ones(length: usize) -> BitVec {
+        let mut bitvec = BitVec::zeros(length);
+        bitvec.set_all();
+
+        bitvec
+    }
+
+    /// Construct a bit vector of length `length` from a slice of blocks.
+    ///
+    /// Bit `i` of the bit vector is bit `i % 128` of block `i / 128`. Any
+    /// bits of the blocks at positions `length` or greater are discarded,
+    /// and missing blocks are treated as empty.
+    pub fn from_blocks(blocks: &[BitSet], length: usize) -> BitVec {
+        let mut bitvec = BitVec::zeros(length);
+        for (target, source) in bitvec.blocks.iter_mut().zip(blocks.iter()) {
+            *target = *source;
+        }
+        bitvec.clear_unused_bits();
+
+        bitvec
+    }
+
+    /// Return the number of bits in the bit vector.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    /// Determine whether the bit vector has a length of zero.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    /// Return the blocks storing the bits of the bit vector.
+    ///
+    /// The bits of the last block at positions past the length of the bit vector
+    /// are always `false`.
+    #[inline]
+    pub fn blocks(&self) -> &[BitSet] {
+        &self.blocks
+    }
+
+    /// Test whether the bit in the input position is set.
+    ///
+    /// If the position `position` exceeds the length of the bit vector,
+    /// the function returns `false`.
+    #[inline]
+    pub fn test(&self, position: usize) -> bool {
+        position < self.length && self.blocks[position / BLOCK_BITS].test(position % BLOCK_BITS)
+    }
+
+    /// Get the current value of the bit at position `position` in the bit
+    /// vector.
+    ///
+    /// The function returns `None` if `position` is outside the length of the
+    /// bit vector.
+    #[inline]
+    pub fn get(&self, position: usize) -> Option<bool> {
+        if position < self.length {
+            Some(self.test(position))
+        } else {
+            None
+        }
+    }
+
+    /// Set the bit as position `position` to the value `value`.
+    ///
+    /// The function returns `None` if `position` is outside the length of the
+    /// bit vector.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use bitset::{
+    /// #     BitVec,
+    /// # };
+    /// #
+    /// let mut bitvec = BitVec::zeros(200);
+    ///
+    /// assert_eq!(bitvec.set(150, true), Some(()));
+    /// assert_eq!(bitvec.get(150), Some(true));
+    /// assert_eq!(bitvec.set(200, true), None);
+    /// ```
+    pub fn set(&mut self, position: usize, value: bool) -> Option<()> {
+        if position < self.length {
+            self.blocks[position / BLOCK_BITS].set(position % BLOCK_BITS, value)
+        } else {
+            None
+        }
+    }
+
+    /// Flip the bit at position `position` in the bit vector.
+    ///
+    /// The function returns `None` if `position` is outside the length of the
+    /// bit vector.
+    pub fn flip(&mut self, position: usize) -> Option<()> {
+        if position < self.length {
+            self.blocks[position / BLOCK_BITS].flip(position % BLOCK_BITS)
+        } else {
+            None
+        }
+    }
+
+    /// Append a bit with the value `value` to the end of the bit vector.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use bitset::{
+    /// #     BitVec,
+    /// # };
+    /// #
+    /// let mut bitvec = BitVec::new();
+    /// bitvec.push(true);
+    /// bitvec.push(false);
+    ///
+    /// assert_eq!(bitvec.len(), 2);
+    /// assert_eq!(bitvec.get(0), Some(true));
+    /// assert_eq!(bitvec.get(1), Some(false));
+    /// ```
+    pub fn push(&mut self, value: bool) {
+        if self.length == self.blocks.len() * BLOCK_BITS {
+            self.blocks.push(BitSet::new());
+        }
+        self.length += 1;
+        self.set(self.length - 1, value);
+    }
+
+    /// Change the length of the bit vector to `length`.
+    ///
+    /// If the bit vector grows, the new bits are set to `value`. If it shrinks,
+    /// the bits past the new length are discarded.
+    pub fn resize(&mut self, length: usize, value: bool) {
+        let old_length = self.length;
+        self.blocks.resize(block_count(length), BitSet::new());
+        self.length = length;
+        if value {
+            for position in old_length..length {
+                self.set(position, true);
+            }
+        }
+        self.clear_unused_bits();
+    }
+
+    /// Count up the number of bits in the bit vector that are set to true.
+    #[inline]
+    pub fn count(&self) -> usize {
+        self.blocks.iter().map(|block| block.count()).sum()
+    }
+
+    /// Test whether all the bits in the bit vector are set to true.
+    ///
+    /// An empty bit vector satisfies this vacuously.
+    #[inline]
+    pub fn all(&self) -> bool {
+        self.count() == self.length
+    }
+
+    /// Test whether any of the bits in the bit vector are set to true.
+    #[inline]
+    pub fn any(&self) -> bool {
+        self.blocks.iter().any(|block| block.any())
+    }
+
+    /// Test whether none of the bits in the bit vector are set to true.
+    #[inline]
+    pub fn none(&self) -> bool {
+        !self.any()
+    }
+
+    /// Set all the bits in the bit vector to `true`.
+    pub fn set_all(&mut self) {
+        for block in self.blocks.iter_mut() {
+            block.set_all();
+        }
+        self.clear_unused_bits();
+    }
+
+    /// Set all the bits in the bit vector to `false`.
+    pub fn reset_all(&mut self) {
+        for block in self.blocks.iter_mut() {
+            block.reset_all();
+        }
+    }
+
+    /// Flip all the bits in the bit vector.
+    pub fn flip_all(&mut self) {
+        for block in self.blocks.iter_mut() {
+            block.flip_all();
+        }
+        self.clear_unused_bits();
+    }
+
+    /// Construct an iterator over the positions of the bits set to `true`, in
+    /// increasing order.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use bitset::{
+    /// #     BitVec,
+    /// # };
+    /// #
+    /// let mut bitvec = BitVec::zeros(300);
+    /// bitvec.set(3, true);
+    /// bitvec.set(299, true);
+    /// let positions: Vec<usize> = bitvec.iter().collect();
+    ///
+    /// assert_eq!(positions, vec![3, 299]);
+    /// ```
+    #[inline]
+    pub fn iter(&self) -> BitVecIter<'_> {
+        BitVecIter {
+            blocks: &self.blocks,
+            block: 0,
+            current: self.blocks.first().copied().unwrap_or_default().iter(),
+        }
+    }
+
+    /// Reset the bits of the last block past the length of the bit vector.
+    fn clear_unused_bits(&mut self) {
+        let used = self.length % BLOCK_BITS;
+        if used != 0 {
+            if let Some(last) = self.blocks.last_mut() {
+                last.data &= (1_u128 << used) - 1;
+            }
+        }
+    }
+
+    /// Combine two bit vectors block by block, padding the shorter one with
+    /// `false` bits.
+    fn zip_with<F>(&self, other: &BitVec, op: F) -> BitVec where F: Fn(u128, u128) -> u128 {
+        let length = usize::max(self.length, other.length);
+        let mut result = BitVec::zeros(length);
+        for (i, block) in result.blocks.iter_mut().enumerate() {
+            let left = self.blocks.get(i).map_or(0, |block| block.data);
+            let right = other.blocks.get(i).map_or(0, |block| block.data);
+            block.data = op(left, right);
+        }
+        result.clear_unused_bits();
+
+        result
+    }
+
+    /// Move every bit `amount` positions toward the end of the bit vector,
+    /// discarding the bits moved past its length.
+    fn shift_up(&mut self, amount: usize) {
+        if amount >= self.length {
+            self.reset_all();
+            return;
+        }
+        let words = amount / BLOCK_BITS;
+        let bits = amount % BLOCK_BITS;
+        for i in (0..self.blocks.len()).rev() {
+            let high = if i >= words { self.blocks[i - words].data << bits } else { 0 };
+            let low = if bits > 0 && i > words {
+                self.blocks[i - words - 1].data >> (BLOCK_BITS - bits)
+            } else {
+                0
+            };
+            self.blocks[i].data = high | low;
+        }
+        self.clear_unused_bits();
+    }
+
+    /// Move every bit `amount` positions toward the start of the bit vector,
+    /// filling the end of the bit vector with `false` bits.
+    fn shift_down(&mut self, amount: usize) {
+        if amount >= self.length {
+            self.reset_all();
+            return;
+        }
+        let words = amount / BLOCK_BITS;
+        let bits = amount % BLOCK_BITS;
+        for i in 0..self.blocks.len() {
+            let low = self.blocks.get(i + words).map_or(0, |block| block.data >> bits);
+            let high = if bits > 0 {
+                self.blocks.get(i + words + 1).map_or(0, |block| block.data << (BLOCK_BITS - bits))
+            } else {
+                0
+            };
+            self.blocks[i].data = low | high;
+        }
+    }
+}
+
+/// Return the number of blocks needed to hold `length` bits.
+#[inline]
+fn block_count(length: usize) -> usize {
+    let partial = length % BLOCK_BITS;
+
+    length / BLOCK_BITS + (partial != 0) as usize
+}
+
+impl fmt::Display for BitVec {
+    /// Format the bit vector as a string of ones and zeros, starting from the
+    /// bit at position zero.
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        for position in 0..self.length {
+            if self.test(position) {
+                write!(formatter, "1")?;
+            } else {
+                write!(formatter, "0")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl From<BitSet> for BitVec {
+    /// Construct a 128-bit bit vector from a bit set.
+    #[inline]
+    fn from(bitset: BitSet) -> BitVec {
+        BitVec {
+            blocks: vec![bitset],
+            length: bitset.capacity(),
+        }
+    }
+}
+
+impl std::iter::FromIterator<bool> for BitVec {
+    fn from_iter<I>(iter: I) -> BitVec where I: IntoIterator<Item = bool> {
+        let mut bitvec = BitVec::new();
+        bitvec.extend(iter);
+
+        bitvec
+    }
+}
+
+impl Extend<bool> for BitVec {
+    fn extend<I>(&mut self, iter: I) where I: IntoIterator<Item = bool> {
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a BitVec {
+    type Item = usize;
+    type IntoIter = BitVecIter<'a>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+
+/// An iterator over the positions of the bits of a bit vector that are set to
+/// `true`.
+#[derive(Clone, Debug)]
+pub struct BitVecIter<'a> {
+    blocks: &'a [BitSet],
+    block: usize,
+    current: crate::Iter,
+}
+
+impl<'a> Iterator for BitVecIter<'a> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(position) = self.current.next() {
+                return Some(self.block * BLOCK_BITS + position);
+            }
+            self.block += 1;
+            self.current = self.blocks.get(self.block)?.iter();
+        }
+    }
+}
+
+
+macro_rules! impl_bitvec_binary_op {
+    ($op_trait:ident, $op_fn:ident, $assign_trait:ident, $assign_fn:ident, $op:tt) => {
+        impl<'a> ops::$op_trait<&'a BitVec> for &BitVec {
+            type Output = BitVec;
+
+            #[inline]
+            fn $op_fn(self, other: &'a BitVec) -> Self::Output {
+                self.zip_with(other, |left, right| left $op right)
+            }
+        }
+
+        impl ops::$op_trait<BitVec> for &BitVec {
+            type Output = BitVec;
+
+            #[inline]
+            fn $op_fn(self, other: BitVec) -> Self::Output {
+                self.zip_with(&other, |left, right| left $op right)
+            }
+        }
+
+        impl ops::$op_trait<&BitVec> for BitVec {
+            type Output = BitVec;
+
+            #[inline]
+            fn $op_fn(self, other: &BitVec) -> Self::Output {
+                self.zip_with(other, |left, right| left $op right)
+            }
+        }
+
+        impl ops::$op_trait<BitVec> for BitVec {
+            type Output = BitVec;
+
+            #[inline]
+            fn $op_fn(self, other: BitVec) -> Self::Output {
+                self.zip_with(&other, |left, right| left $op right)
+            }
+        }
+
+        impl ops::$assign_trait<&BitVec> for BitVec {
+            #[inline]
+            fn $assign_fn(&mut self, other: &BitVec) {
+                if self.length < other.length {
+                    self.resize(other.length, false);
+                }
+                for (i, block) in self.blocks.iter_mut().enumerate() {
+                    let right = other.blocks.get(i).map_or(0, |block| block.data);
+                    block.data = block.data $op right;
+                }
+            }
+        }
+
+        impl ops::$assign_trait<BitVec> for BitVec {
+            #[inline]
+            fn $assign_fn(&mut self, other: BitVec) {
+                ops::$assign_trait::$assign_fn(self, &other);
+            }
+        }
+    }
+}
+
+impl_bitvec_binary_op!(BitAnd, bitand, BitAndAssign, bitand_assign, &);
+impl_bitvec_binary_op!(BitOr, bitor, BitOrAssign, bitor_assign, |);
+impl_bitvec_binary_op!(BitXor, bitxor, BitXorAssign, bitxor_assign, ^);
+
+impl ops::Not for BitVec {
+    type Output = BitVec;
+
+    #[inline]
+    fn not(mut self) -> Self::Output {
+        self.flip_all();
+
+        self
+    }
+}
+
+impl ops::Not for &BitVec {
+    type Output = BitVec;
+
+    #[inline]
+    fn not(self) -> Self::Output {
+        !self.clone()
+    }
+}
+
+impl ops::Shl<usize> for BitVec {
+    type Output = BitVec;
+
+    /// Move every bit `amount` positions toward the end of the bit vector. The
+    /// length stays the same, so the bits moved past the end are discarded,
+    /// and shifting by the length or more clears the bit vector.
+    #[inline]
+    fn shl(mut self, amount: usize) -> Self::Output {
+        self.shift_up(amount);
+
+        self
+    }
+}
+
+impl ops::Shl<usize> for &BitVec {
+    type Output = BitVec;
+
+    #[inline]
+    fn shl(self, amount: usize) -> Self::Output {
+        self.clone() << amount
+    }
+}
+
+impl ops::Shr<usize> for BitVec {
+    type Output = BitVec;
+
+    /// Move every bit `amount` positions toward the start of the bit vector.
+    /// The length stays the same, so shifting by the length or more clears the
+    /// bit vector.
+    #[inline]
+    fn shr(mut self, amount: usize) -> Self::Output {
+        self.shift_down(amount);
+
+        self
+    }
+}
+
+impl ops::Shr<usize> for &BitVec {
+    type Output = BitVec;
+
+    #[inline]
+    fn shr(self, amount: usize) -> Self::Output {
+        self.clone() >> amount
+    }
+}
+
+impl ops::ShlAssign<usize> for BitVec {
+    #[inline]
+    fn shl_assign(&mut self, amount: usize) {
+        self.shift_up(amount);
+    }
+}
+
+impl ops::ShrAssign<usize> for BitVec {
+    #[inline]
+    fn shr_assign(&mut self, amount: usize) {
+        self.shift_down(amount);
+    }
+}
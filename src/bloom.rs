@@ -0,0 +1,684 @@
+use crate::{
+    BitSet,
+    BitVec,
+    StableBuildHasher,
+};
+use std::convert::TryFrom;
+use std::error;
+use std::fmt;
+use std::hash::{
+    BuildHasher,
+    Hash,
+    Hasher,
+};
+use std::ops;
+
+
+/// The default hasher builder of the Bloom filters, which hashes the same
+/// writes to the same value in every process and every release of the crate,
+/// so that serialized filters stay valid for items whose [`Hash`]
+/// implementations write a fixed encoding.
+pub type DefaultBloomHasher = StableBuildHasher;
+
+/// The number of bits in a block of a blocked Bloom filter, which is the size
+/// of a typical cache line.
+const BLOCK_BITS: usize = 512;
+
+/// The size of the header of a serialized Bloom filter: the number of hash
+/// functions followed by the number of bits.
+const HEADER_BYTES: usize = 12;
+
+/// The largest number of hash functions of a Bloom filter. The optimal number
+/// is about `-log2(false_positive_rate)`, so only false positive rates below
+/// `2^-64` would call for more.
+const MAX_HASHES: usize = 64;
+
+
+/// The error returned when decoding a Bloom filter from bytes fails.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct InvalidBloomFilter;
+
+impl fmt::Display for InvalidBloomFilter {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "the bytes do not encode a valid Bloom filter")
+    }
+}
+
+impl error::Error for InvalidBloomFilter {}
+
+
+/// Compute the number of bits and hash functions of a Bloom filter holding
+/// `expected_items` items with a false positive rate of
+/// `false_positive_rate`.
+fn optimal_params(expected_items: usize, false_positive_rate: f64) -> (usize, usize) {
+    let items = usize::max(expected_items, 1) as f64;
+    let rate = false_positive_rate.clamp(f64::MIN_POSITIVE, 0.5);
+    let ln2 = std::f64::consts::LN_2;
+    let bits = (-items * rate.ln() / (ln2 * ln2)).ceil();
+    let bits = usize::max(bits as usize, 1);
+    let hashes = ((bits as f64 / items) * ln2).round() as usize;
+
+    (bits, hashes.clamp(1, MAX_HASHES))
+}
+
+/// Return the number of bytes needed to hold `bits` bits.
+#[inline]
+fn byte_count(bits: usize) -> usize {
+    let partial = bits % 8;
+
+    bits / 8 + (partial != 0) as usize
+}
+
+/// Two independent hashes of an item, combined by double hashing into as many
+/// hash functions as needed.
+#[derive(Copy, Clone)]
+struct DoubleHash {
+    first: u64,
+    second: u64,
+}
+
+impl DoubleHash {
+    fn new<T, S>(builder: &S, item: &T) -> DoubleHash where T: Hash + ?Sized, S: BuildHasher {
+        let mut hasher = builder.build_hasher();
+        item.hash(&mut hasher);
+        let first = hasher.finish();
+        // Continue the same hasher to derive a second hash. Forcing it odd keeps
+        // the probe sequence from collapsing when the number of bits is even.
+        hasher.write_u64(first);
+        let second = hasher.finish() | 1;
+
+        DoubleHash { first, second }
+    }
+
+    /// Return the value of hash function `i` modulo `modulus`.
+    #[inline]
+    fn position(&self, i: usize, modulus: usize) -> usize {
+        let hash = self.first.wrapping_add((i as u64).wrapping_mul(self.second));
+
+        (hash % modulus as u64) as usize
+    }
+}
+
+/// Estimate the number of items in a filter of `bits` bits and `hashes` hash
+/// functions that has `ones` bits set.
+fn estimate_items(bits: usize, hashes: usize, ones: usize) -> f64 {
+    if ones >= bits {
+        return f64::INFINITY;
+    }
+    let bits = bits as f64;
+
+    -(bits / hashes as f64) * (1.0 - ones as f64 / bits).ln()
+}
+
+
+/// A Bloom filter, a probabilistic set that answers membership queries with
+/// no false negatives and a bounded rate of false positives.
+///
+/// Each item sets the bits selected by `k` hash functions, which are derived
+/// from two hashes of the item by double hashing. The bits live in a bit
+/// vector. Filters with the same number of bits and hash functions combine
+/// with `|` into a filter of the union of their items.
+///
+/// ## Example
+///
+/// ```
+/// # use bitset::{
+/// #     BloomFilter,
+/// # };
+/// #
+/// let mut filter = BloomFilter::new(1000, 0.01);
+/// filter.insert("alpha");
+/// filter.insert("beta");
+///
+/// assert!(filter.contains("alpha"));
+/// assert!(filter.contains("beta"));
+/// assert!(!filter.contains("gamma"));
+/// ```
+#[derive(Clone, Debug)]
+pub struct BloomFilter<S = DefaultBloomHasher> {
+    bits: BitVec,
+    hashes: usize,
+    builder: S,
+}
+
+impl BloomFilter<DefaultBloomHasher> {
+    /// The largest number of hash functions of a filter. Constructors clamp
+    /// the number of hash functions to it, and [`BloomFilter::from_bytes`]
+    /// rejects filters with more.
+    pub const MAX_HASHES: usize = MAX_HASHES;
+
+    /// Construct an empty filter sized for `expected_items` items with a false
+    /// positive rate of `false_positive_rate`.
+    #[inline]
+    pub fn new(expected_items: usize, false_positive_rate: f64) -> BloomFilter {
+        BloomFilter::with_hasher(expected_items, false_positive_rate, DefaultBloomHasher::default())
+    }
+
+    /// Construct an empty filter with `bits` bits and `hashes` hash functions.
+    ///
+    /// The number of bits is raised to at least one, and the number of hash
+    /// functions is clamped to `1..=BloomFilter::MAX_HASHES`.
+    #[inline]
+    pub fn with_params(bits: usize, hashes: usize) -> BloomFilter {
+        BloomFilter::with_params_and_hasher(bits, hashes, DefaultBloomHasher::default())
+    }
+
+    /// Decode a filter serialized by [`BloomFilter::to_bytes`].
+    ///
+    /// The function returns an error if the bytes are truncated, have trailing
+    /// data, set bits past the length of the filter, or give a number of hash
+    /// functions of zero or above [`BloomFilter::MAX_HASHES`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<BloomFilter, InvalidBloomFilter> {
+        if bytes.len() < HEADER_BYTES {
+            return Err(InvalidBloomFilter);
+        }
+        let mut hashes = [0; 4];
+        let mut length = [0; 8];
+        hashes.copy_from_slice(&bytes[0..4]);
+        length.copy_from_slice(&bytes[4..HEADER_BYTES]);
+        let hashes = u32::from_le_bytes(hashes) as usize;
+        let length = usize::try_from(u64::from_le_bytes(length)).map_err(|_| InvalidBloomFilter)?;
+        let body = &bytes[HEADER_BYTES..];
+        if hashes == 0 || hashes > MAX_HASHES || length == 0 || body.len() != byte_count(length) {
+            return Err(InvalidBloomFilter);
+        }
+
+        let blocks: Vec<BitSet> = body.chunks(16)
+            .map(|chunk| {
+                let mut block = [0; 16];
+                block[..chunk.len()].copy_from_slice(chunk);
+
+                BitSet::from_u128(u128::from_le_bytes(block))
+            })
+            .collect();
+        let bits = BitVec::from_blocks(&blocks, length);
+        if bits.count() != blocks.iter().map(|block| block.count()).sum::<usize>() {
+            return Err(InvalidBloomFilter);
+        }
+
+        Ok(BloomFilter {
+            bits,
+            hashes,
+            builder: DefaultBloomHasher::default(),
+        })
+    }
+}
+
+impl<S> BloomFilter<S> where S: BuildHasher {
+    /// Construct an empty filter sized for `expected_items` items with a false
+    /// positive rate of `false_positive_rate`, hashing items with `builder`.
+    pub fn with_hasher(expected_items: usize, false_positive_rate: f64, builder: S) -> BloomFilter<S> {
+        let (bits, hashes) = optimal_params(expected_items, false_positive_rate);
+
+        BloomFilter::with_params_and_hasher(bits, hashes, builder)
+    }
+
+    /// Construct an empty filter with `bits` bits and `hashes` hash functions,
+    /// hashing items with `builder`.
+    ///
+    /// The number of bits is raised to at least one, and the number of hash
+    /// functions is clamped to `1..=BloomFilter::MAX_HASHES`.
+    pub fn with_params_and_hasher(bits: usize, hashes: usize, builder: S) -> BloomFilter<S> {
+        BloomFilter {
+            bits: BitVec::zeros(usize::max(bits, 1)),
+            hashes: hashes.clamp(1, MAX_HASHES),
+            builder,
+        }
+    }
+
+    /// Return the number of bits of the filter.
+    #[inline]
+    pub fn bit_len(&self) -> usize {
+        self.bits.len()
+    }
+
+    /// Return the number of hash functions of the filter.
+    #[inline]
+    pub fn hashes(&self) -> usize {
+        self.hashes
+    }
+
+    /// Return the bits of the filter.
+    #[inline]
+    pub fn bits(&self) -> &BitVec {
+        &self.bits
+    }
+
+    /// Determine whether no item has been inserted into the filter.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.bits.none()
+    }
+
+    /// Insert the item `item` into the filter.
+    ///
+    /// The function returns `true` if the item was possibly absent before, that
+    /// is, if inserting it set at least one bit.
+    pub fn insert<T>(&mut self, item: &T) -> bool where T: Hash + ?Sized {
+        let hash = DoubleHash::new(&self.builder, item);
+        let length = self.bits.len();
+        let mut changed = false;
+        for i in 0..self.hashes {
+            let position = hash.position(i, length);
+            changed |= !self.bits.test(position);
+            self.bits.set(position, true);
+        }
+
+        changed
+    }
+
+    /// Determine whether the item `item` may have been inserted into the
+    /// filter.
+    ///
+    /// A result of `false` is always correct, and a result of `true` is wrong
+    /// with a probability close to the false positive rate of the filter.
+    pub fn contains<T>(&self, item: &T) -> bool where T: Hash + ?Sized {
+        let hash = DoubleHash::new(&self.builder, item);
+        let length = self.bits.len();
+
+        (0..self.hashes).all(|i| self.bits.test(hash.position(i, length)))
+    }
+
+    /// Remove every item from the filter.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.bits.reset_all();
+    }
+
+    /// Estimate the number of distinct items inserted into the filter from the
+    /// number of bits set.
+    ///
+    /// The estimate is infinite when every bit of the filter is set.
+    #[inline]
+    pub fn estimated_len(&self) -> f64 {
+        estimate_items(self.bits.len(), self.hashes, self.bits.count())
+    }
+
+    /// Estimate the current false positive rate of the filter from the
+    /// fraction of bits set.
+    #[inline]
+    pub fn estimated_false_positive_rate(&self) -> f64 {
+        let fraction = self.bits.count() as f64 / self.bits.len() as f64;
+
+        fraction.powi(self.hashes as i32)
+    }
+
+    /// Determine whether two filters have the same number of bits and hash
+    /// functions, so that they can be combined.
+    #[inline]
+    pub fn is_compatible(&self, other: &BloomFilter<S>) -> bool {
+        self.bits.len() == other.bits.len() && self.hashes == other.hashes
+    }
+
+    /// Construct the filter of the union of the items of two filters.
+    ///
+    /// The function returns `None` if the filters are not compatible.
+    pub fn union(&self, other: &BloomFilter<S>) -> Option<BloomFilter<S>> where S: Clone {
+        if self.is_compatible(other) {
+            Some(BloomFilter {
+                bits: &self.bits | &other.bits,
+                hashes: self.hashes,
+                builder: self.builder.clone(),
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Construct a filter that contains every item contained in both filters.
+    ///
+    /// The result may report more false positives than a filter built from the
+    /// common items directly. The function returns `None` if the filters are
+    /// not compatible.
+    pub fn intersection(&self, other: &BloomFilter<S>) -> Option<BloomFilter<S>> where S: Clone {
+        if self.is_compatible(other) {
+            Some(BloomFilter {
+                bits: &self.bits & &other.bits,
+                hashes: self.hashes,
+                builder: self.builder.clone(),
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Serialize the filter into bytes.
+    ///
+    /// The encoding is the number of hash functions as a little-endian `u32`,
+    /// the number of bits as a little-endian `u64`, and the bits packed into
+    /// bytes starting from the least significant bit. Only filters hashing with
+    /// [`DefaultBloomHasher`] can be decoded again.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use bitset::{
+    /// #     BloomFilter,
+    /// # };
+    /// #
+    /// let mut filter = BloomFilter::new(100, 0.01);
+    /// filter.insert(&42);
+    /// let decoded = BloomFilter::from_bytes(&filter.to_bytes()).unwrap();
+    ///
+    /// assert!(decoded.contains(&42));
+    /// assert_eq!(decoded.bits(), filter.bits());
+    /// ```
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let length = self.bits.len();
+        let mut bytes = Vec::with_capacity(HEADER_BYTES + byte_count(length));
+        bytes.extend_from_slice(&(self.hashes as u32).to_le_bytes());
+        bytes.extend_from_slice(&(length as u64).to_le_bytes());
+        for block in self.bits.blocks() {
+            bytes.extend_from_slice(&block.data.to_le_bytes());
+        }
+        bytes.truncate(HEADER_BYTES + byte_count(length));
+
+        bytes
+    }
+}
+
+impl<T, S> Extend<T> for BloomFilter<S> where T: Hash, S: BuildHasher {
+    fn extend<I>(&mut self, iter: I) where I: IntoIterator<Item = T> {
+        for item in iter {
+            self.insert(&item);
+        }
+    }
+}
+
+impl<S> PartialEq for BloomFilter<S> {
+    /// Compare two filters by their parameters and bits.
+    #[inline]
+    fn eq(&self, other: &BloomFilter<S>) -> bool {
+        self.hashes == other.hashes && self.bits == other.bits
+    }
+}
+
+impl<S> Eq for BloomFilter<S> {}
+
+impl<'a, S> ops::BitOr<&'a BloomFilter<S>> for &BloomFilter<S> where S: BuildHasher + Clone {
+    type Output = BloomFilter<S>;
+
+    /// Construct the filter of the union of the items of two filters.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the filters differ in their number of bits or hash functions.
+    #[inline]
+    fn bitor(self, other: &'a BloomFilter<S>) -> Self::Output {
+        self.union(other)
+            .expect("Bloom filters must have the same number of bits and hash functions")
+    }
+}
+
+impl<'a, S> ops::BitOrAssign<&'a BloomFilter<S>> for BloomFilter<S> where S: BuildHasher {
+    /// Add the items of another filter to this filter.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the filters differ in their number of bits or hash functions.
+    #[inline]
+    fn bitor_assign(&mut self, other: &'a BloomFilter<S>) {
+        assert!(
+            self.is_compatible(other),
+            "Bloom filters must have the same number of bits and hash functions"
+        );
+        self.bits |= &other.bits;
+    }
+}
+
+impl<'a, S> ops::BitAnd<&'a BloomFilter<S>> for &BloomFilter<S> where S: BuildHasher + Clone {
+    type Output = BloomFilter<S>;
+
+    /// Construct a filter that contains every item contained in both filters.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the filters differ in their number of bits or hash functions.
+    #[inline]
+    fn bitand(self, other: &'a BloomFilter<S>) -> Self::Output {
+        self.intersection(other)
+            .expect("Bloom filters must have the same number of bits and hash functions")
+    }
+}
+
+
+/// A blocked Bloom filter, which confines the bits of each item to a single
+/// block the size of a cache line.
+///
+/// A lookup touches one block of memory instead of `k` scattered bits, at the
+/// cost of a slightly higher false positive rate than a plain Bloom filter of
+/// the same size.
+///
+/// ## Example
+///
+/// ```
+/// # use bitset::{
+/// #     BlockedBloomFilter,
+/// # };
+/// #
+/// let mut filter = BlockedBloomFilter::new(1000, 0.01);
+/// filter.insert(&17_u64);
+///
+/// assert!(filter.contains(&17_u64));
+/// assert_eq!(filter.bit_len() % 512, 0);
+/// ```
+#[derive(Clone, Debug)]
+pub struct BlockedBloomFilter<S = DefaultBloomHasher> {
+    bits: BitVec,
+    hashes: usize,
+    builder: S,
+}
+
+impl BlockedBloomFilter<DefaultBloomHasher> {
+    /// Construct an empty filter sized for `expected_items` items with a false
+    /// positive rate of `false_positive_rate`.
+    #[inline]
+    pub fn new(expected_items: usize, false_positive_rate: f64) -> BlockedBloomFilter {
+        BlockedBloomFilter::with_hasher(expected_items, false_positive_rate, DefaultBloomHasher::default())
+    }
+}
+
+impl<S> BlockedBloomFilter<S> where S: BuildHasher {
+    /// Construct an empty filter sized for `expected_items` items with a false
+    /// positive rate of `false_positive_rate`, hashing items with `builder`.
+    ///
+    /// The number of bits is rounded up to a whole number of blocks.
+    pub fn with_hasher(expected_items: usize, false_positive_rate: f64, builder: S) -> BlockedBloomFilter<S> {
+        let (bits, hashes) = optimal_params(expected_items, false_positive_rate);
+        let partial = bits % BLOCK_BITS;
+        let blocks = bits / BLOCK_BITS + (partial != 0) as usize;
+
+        BlockedBloomFilter {
+            bits: BitVec::zeros(blocks * BLOCK_BITS),
+            hashes,
+            builder,
+        }
+    }
+
+    /// Return the number of bits of the filter.
+    #[inline]
+    pub fn bit_len(&self) -> usize {
+        self.bits.len()
+    }
+
+    /// Return the number of hash functions of the filter.
+    #[inline]
+    pub fn hashes(&self) -> usize {
+        self.hashes
+    }
+
+    /// Return the bits of the filter.
+    #[inline]
+    pub fn bits(&self) -> &BitVec {
+        &self.bits
+    }
+
+    /// Return the positions of the bits of an item.
+    fn positions<T>(&self, item: &T) -> impl Iterator<Item = usize> where T: Hash + ?Sized {
+        let hash = DoubleHash::new(&self.builder, item);
+        let blocks = self.bits.len() / BLOCK_BITS;
+        // The first hash selects the block, and its high half starts the probe
+        // sequence within the block, so that the bits within the block do not
+        // depend on the same low bits that chose the block.
+        let base = hash.position(0, blocks) * BLOCK_BITS;
+        let within = DoubleHash {
+            first: hash.first >> 32,
+            second: hash.second,
+        };
+
+        (0..self.hashes).map(move |i| base + within.position(i, BLOCK_BITS))
+    }
+
+    /// Insert the item `item` into the filter.
+    ///
+    /// The function returns `true` if inserting the item set at least one bit.
+    pub fn insert<T>(&mut self, item: &T) -> bool where T: Hash + ?Sized {
+        let positions: Vec<usize> = self.positions(item).collect();
+        let mut changed = false;
+        for position in positions {
+            changed |= !self.bits.test(position);
+            self.bits.set(position, true);
+        }
+
+        changed
+    }
+
+    /// Determine whether the item `item` may have been inserted into the
+    /// filter.
+    pub fn contains<T>(&self, item: &T) -> bool where T: Hash + ?Sized {
+        self.positions(item).all(|position| self.bits.test(position))
+    }
+
+    /// Remove every item from the filter.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.bits.reset_all();
+    }
+
+    /// Estimate the number of distinct items inserted into the filter from the
+    /// number of bits set.
+    #[inline]
+    pub fn estimated_len(&self) -> f64 {
+        estimate_items(self.bits.len(), self.hashes, self.bits.count())
+    }
+}
+
+
+/// A counting Bloom filter, which keeps a small counter in place of each bit
+/// so that items can be removed again.
+///
+/// Counters saturate at their maximum value and then never decrease, which
+/// keeps the filter free of false negatives at the cost of items that can no
+/// longer be removed completely.
+///
+/// ## Example
+///
+/// ```
+/// # use bitset::{
+/// #     CountingBloomFilter,
+/// # };
+/// #
+/// let mut filter = CountingBloomFilter::new(100, 0.01);
+/// filter.insert("session-1");
+///
+/// assert!(filter.contains("session-1"));
+/// assert!(filter.remove("session-1"));
+/// assert!(!filter.contains("session-1"));
+/// ```
+#[derive(Clone, Debug)]
+pub struct CountingBloomFilter<S = DefaultBloomHasher> {
+    counters: Vec<u8>,
+    hashes: usize,
+    builder: S,
+}
+
+impl CountingBloomFilter<DefaultBloomHasher> {
+    /// Construct an empty filter sized for `expected_items` items with a false
+    /// positive rate of `false_positive_rate`.
+    #[inline]
+    pub fn new(expected_items: usize, false_positive_rate: f64) -> CountingBloomFilter {
+        CountingBloomFilter::with_hasher(expected_items, false_positive_rate, DefaultBloomHasher::default())
+    }
+}
+
+impl<S> CountingBloomFilter<S> where S: BuildHasher {
+    /// Construct an empty filter sized for `expected_items` items with a false
+    /// positive rate of `false_positive_rate`, hashing items with `builder`.
+    pub fn with_hasher(expected_items: usize, false_positive_rate: f64, builder: S) -> CountingBloomFilter<S> {
+        let (counters, hashes) = optimal_params(expected_items, false_positive_rate);
+
+        CountingBloomFilter {
+            counters: vec![0; counters],
+            hashes,
+            builder,
+        }
+    }
+
+    /// Return the number of counters of the filter.
+    #[inline]
+    pub fn counter_len(&self) -> usize {
+        self.counters.len()
+    }
+
+    /// Return the number of hash functions of the filter.
+    #[inline]
+    pub fn hashes(&self) -> usize {
+        self.hashes
+    }
+
+    fn positions<T>(&self, item: &T) -> impl Iterator<Item = usize> where T: Hash + ?Sized {
+        let hash = DoubleHash::new(&self.builder, item);
+        let length = self.counters.len();
+
+        (0..self.hashes).map(move |i| hash.position(i, length))
+    }
+
+    /// Insert the item `item` into the filter.
+    pub fn insert<T>(&mut self, item: &T) where T: Hash + ?Sized {
+        let positions: Vec<usize> = self.positions(item).collect();
+        for position in positions {
+            self.counters[position] = self.counters[position].saturating_add(1);
+        }
+    }
+
+    /// Remove the item `item` from the filter.
+    ///
+    /// The function returns `false`, and leaves the filter unchanged, if the
+    /// item is not contained in the filter. Removing an item that was never
+    /// inserted but is a false positive corrupts the filter.
+    pub fn remove<T>(&mut self, item: &T) -> bool where T: Hash + ?Sized {
+        let positions: Vec<usize> = self.positions(item).collect();
+        if positions.iter().any(|&position| self.counters[position] == 0) {
+            return false;
+        }
+        for position in positions {
+            if self.counters[position] < u8::MAX {
+                self.counters[position] -= 1;
+            }
+        }
+
+        true
+    }
+
+    /// Determine whether the item `item` may have been inserted into the
+    /// filter.
+    pub fn contains<T>(&self, item: &T) -> bool where T: Hash + ?Sized {
+        self.positions(item).all(|position| self.counters[position] > 0)
+    }
+
+    /// Remove every item from the filter.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.counters.iter_mut().for_each(|counter| *counter = 0);
+    }
+
+    /// Convert the filter into a plain Bloom filter with the same hash
+    /// functions, which sets each bit whose counter is positive.
+    pub fn to_bloom_filter(&self) -> BloomFilter<S> where S: Clone {
+        BloomFilter {
+            bits: self.counters.iter().map(|&counter| counter > 0).collect(),
+            hashes: self.hashes,
+            builder: self.builder.clone(),
+        }
+    }
+}
@@ -0,0 +1,184 @@
+use crate::BitSet;
+
+
+/// Gather the bits of `value` at the positions set in `mask` into the low
+/// positions of the result, one mask bit at a time.
+#[inline]
+fn extract_portable(value: u128, mask: u128) -> u128 {
+    let mut result = 0;
+    let mut remaining = mask;
+    let mut target = 1;
+    while remaining != 0 {
+        let lowest = remaining & remaining.wrapping_neg();
+        if value & lowest != 0 {
+            result |= target;
+        }
+        target <<= 1;
+        remaining &= remaining - 1;
+    }
+
+    result
+}
+
+/// Scatter the low bits of `value` to the positions set in `mask`, one mask
+/// bit at a time.
+#[inline]
+fn deposit_portable(value: u128, mask: u128) -> u128 {
+    let mut result = 0;
+    let mut remaining = mask;
+    let mut source = 1;
+    while remaining != 0 {
+        let lowest = remaining & remaining.wrapping_neg();
+        if value & source != 0 {
+            result |= lowest;
+        }
+        source <<= 1;
+        remaining &= remaining - 1;
+    }
+
+    result
+}
+
+#[cfg(all(target_arch = "x86_64", target_os = "linux"))]
+mod bmi2 {
+    use std::arch::x86_64::{
+        _pdep_u64,
+        _pext_u64,
+    };
+
+
+    /// Gather bits with the `PEXT` instruction, one 64-bit half at a time.
+    ///
+    /// ## Safety
+    ///
+    /// The processor must support the BMI2 instruction set.
+    #[target_feature(enable = "bmi2")]
+    pub(super) unsafe fn extract(value: u128, mask: u128) -> u128 {
+        let (low_mask, high_mask) = (mask as u64, (mask >> 64) as u64);
+        let low = _pext_u64(value as u64, low_mask) as u128;
+        let high = _pext_u64((value >> 64) as u64, high_mask) as u128;
+
+        // The low half yields at most 64 bits, so the shift stays in range.
+        low | (high << low_mask.count_ones())
+    }
+
+    /// Scatter bits with the `PDEP` instruction, one 64-bit half at a time.
+    ///
+    /// ## Safety
+    ///
+    /// The processor must support the BMI2 instruction set.
+    #[target_feature(enable = "bmi2")]
+    pub(super) unsafe fn deposit(value: u128, mask: u128) -> u128 {
+        let (low_mask, high_mask) = (mask as u64, (mask >> 64) as u64);
+        let low = _pdep_u64(value as u64, low_mask) as u128;
+        let high = _pdep_u64((value >> low_mask.count_ones()) as u64, high_mask) as u128;
+
+        low | (high << 64)
+    }
+}
+
+#[cfg(all(target_arch = "x86_64", target_os = "linux"))]
+#[inline]
+fn extract_bits(value: u128, mask: u128) -> u128 {
+    if is_x86_feature_detected!("bmi2") {
+        // SAFETY: the processor supports BMI2, as just detected.
+        unsafe { bmi2::extract(value, mask) }
+    } else {
+        extract_portable(value, mask)
+    }
+}
+
+#[cfg(not(all(target_arch = "x86_64", target_os = "linux")))]
+#[inline]
+fn extract_bits(value: u128, mask: u128) -> u128 {
+    extract_portable(value, mask)
+}
+
+#[cfg(all(target_arch = "x86_64", target_os = "linux"))]
+#[inline]
+fn deposit_bits(value: u128, mask: u128) -> u128 {
+    if is_x86_feature_detected!("bmi2") {
+        // SAFETY: the processor supports BMI2, as just detected.
+        unsafe { bmi2::deposit(value, mask) }
+    } else {
+        deposit_portable(value, mask)
+    }
+}
+
+#[cfg(not(all(target_arch = "x86_64", target_os = "linux")))]
+#[inline]
+fn deposit_bits(value: u128, mask: u128) -> u128 {
+    deposit_portable(value, mask)
+}
+
+
+/// Parallel bit extract and deposit, as performed by the `PEXT` and `PDEP`
+/// instructions.
+///
+/// On x86_64 Linux, the BMI2 instructions are used when the processor
+/// supports them, which is detected at runtime. Elsewhere, a portable loop
+/// over the bits of the mask is used. Both paths give the same results.
+impl BitSet {
+    /// Gather the bits at the positions set in `mask` into the low positions of
+    /// the result, keeping their order.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use bitset::{
+    /// #     BitSet,
+    /// # };
+    /// #
+    /// let bitset = BitSet::from_u64(0b1011_0010);
+    /// let mask = BitSet::from_u64(0b1111_0000);
+    ///
+    /// assert_eq!(bitset.extract(&mask), BitSet::from_u64(0b1011));
+    /// ```
+    #[inline]
+    pub fn extract(&self, mask: &BitSet) -> BitSet {
+        BitSet::from_u128(extract_bits(self.data, mask.data))
+    }
+
+    /// Scatter the low bits of the bit set to the positions set in `mask`,
+    /// keeping their order.
+    ///
+    /// The result has `mask.count()` bits taken from the bit set, and this is
+    /// the inverse of [`BitSet::extract`] on the positions of the mask.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use bitset::{
+    /// #     BitSet,
+    /// # };
+    /// #
+    /// let bitset = BitSet::from_u64(0b1011);
+    /// let mask = BitSet::from_u64(0b1100_1100);
+    ///
+    /// assert_eq!(bitset.deposit(&mask), BitSet::from_u64(0b1000_1100));
+    /// ```
+    #[inline]
+    pub fn deposit(&self, mask: &BitSet) -> BitSet {
+        BitSet::from_u128(deposit_bits(self.data, mask.data))
+    }
+
+    /// Gather bits as [`BitSet::extract`] does, always with the portable loop.
+    ///
+    /// This exists so that the tests can check the portable path on processors
+    /// that support BMI2.
+    #[doc(hidden)]
+    #[inline]
+    pub fn extract_portable(&self, mask: &BitSet) -> BitSet {
+        BitSet::from_u128(extract_portable(self.data, mask.data))
+    }
+
+    /// Scatter bits as [`BitSet::deposit`] does, always with the portable loop.
+    ///
+    /// This exists so that the tests can check the portable path on processors
+    /// that support BMI2.
+    #[doc(hidden)]
+    #[inline]
+    pub fn deposit_portable(&self, mask: &BitSet) -> BitSet {
+        BitSet::from_u128(deposit_portable(self.data, mask.data))
+    }
+}
@@ -0,0 +1,308 @@
+/*!
+Linear algebra over GF(2), the field with two elements.
+
+A bit set is treated as a vector over GF(2), where bit `j` is the
+coordinate `j`, addition is logical XOR, and multiplication is logical AND. A
+matrix is a slice of bit sets holding its rows, so a matrix can have up to 128
+columns.
+*/
+
+use crate::{
+    BitSet,
+    BitVec,
+};
+use std::error;
+use std::fmt;
+
+
+/// The error returned when a system of linear equations has no solution.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct InconsistentSystem;
+
+impl fmt::Display for InconsistentSystem {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "the system of linear equations has no solution")
+    }
+}
+
+impl error::Error for InconsistentSystem {}
+
+
+/// Eliminate the rows of a matrix together with one extra bit per row,
+/// returning the pivot column of each non-zero row of the result.
+fn eliminate(rows: &mut [BitSet], extra: &mut [bool]) -> Vec<usize> {
+    let mut pivots = Vec::new();
+    for col in 0..BitSet::new().capacity() {
+        let rank = pivots.len();
+        if rank == rows.len() {
+            break;
+        }
+        let found = (rank..rows.len()).find(|&i| rows[i].test(col));
+        if let Some(pivot_row) = found {
+            rows.swap(rank, pivot_row);
+            extra.swap(rank, pivot_row);
+            let pivot = rows[rank];
+            let pivot_extra = extra[rank];
+            for i in 0..rows.len() {
+                if i != rank && rows[i].test(col) {
+                    rows[i] ^= pivot;
+                    extra[i] ^= pivot_extra;
+                }
+            }
+            pivots.push(col);
+        }
+    }
+
+    pivots
+}
+
+/// Transform a matrix over GF(2), given by its rows, into reduced row echelon
+/// form, returning its rank.
+///
+/// After the reduction, the first `rank` rows are non-zero, and the leading
+/// column of each of them is set in no other row. The leading columns increase
+/// from one row to the next. The remaining rows are zero.
+///
+/// ## Example
+///
+/// ```
+/// # use bitset::{
+/// #     BitSet,
+/// #     gf2_row_reduce,
+/// # };
+/// #
+/// let mut rows = [
+///     BitSet::from_u64(0b110),
+///     BitSet::from_u64(0b011),
+///     BitSet::from_u64(0b101),
+/// ];
+/// let rank = gf2_row_reduce(&mut rows);
+///
+/// assert_eq!(rank, 2);
+/// assert_eq!(rows, [BitSet::from_u64(0b101), BitSet::from_u64(0b110), BitSet::new()]);
+/// ```
+pub fn gf2_row_reduce(rows: &mut [BitSet]) -> usize {
+    let mut extra = vec![false; rows.len()];
+
+    eliminate(rows, &mut extra).len()
+}
+
+/// Compute the rank of a matrix over GF(2), given by its rows.
+pub fn gf2_rank(rows: &[BitSet]) -> usize {
+    let mut basis = XorBasis::new();
+
+    rows.iter().filter(|&&row| basis.insert(row)).count()
+}
+
+/// Solve the system of linear equations `A x = b` over GF(2).
+///
+/// The matrix `A` is given by its rows `rows`, and bit `i` of `rhs` is the
+/// right hand side of the equation of row `i`, so a system can have any
+/// number of equations. Free variables of the solution are set to zero. The
+/// function returns an error if the system has no solution.
+///
+/// ## Panics
+///
+/// Panics if the length of `rhs` differs from the number of rows.
+///
+/// ## Example
+///
+/// ```
+/// # use bitset::{
+/// #     BitSet,
+/// #     BitVec,
+/// #     gf2_solve,
+/// # };
+/// #
+/// // x0 + x1 = 1
+/// //      x1 = 1
+/// let rows = [BitSet::from_u64(0b11), BitSet::from_u64(0b10)];
+/// let rhs = BitVec::ones(2);
+///
+/// assert_eq!(gf2_solve(&rows, &rhs), Ok(BitSet::from_u64(0b10)));
+///
+/// // x0 = 0 and x0 = 1.
+/// let rows = [BitSet::from_u64(0b1), BitSet::from_u64(0b1)];
+/// let rhs: BitVec = vec![false, true].into_iter().collect();
+///
+/// assert!(gf2_solve(&rows, &rhs).is_err());
+/// ```
+pub fn gf2_solve(rows: &[BitSet], rhs: &BitVec) -> Result<BitSet, InconsistentSystem> {
+    assert_eq!(rows.len(), rhs.len(), "the right hand side must have one bit per row");
+    let mut reduced = rows.to_vec();
+    let mut extra: Vec<bool> = (0..rows.len()).map(|i| rhs.test(i)).collect();
+    let pivots = eliminate(&mut reduced, &mut extra);
+    if extra[pivots.len()..].iter().any(|&value| value) {
+        return Err(InconsistentSystem);
+    }
+
+    let mut solution = BitSet::new();
+    for (i, &col) in pivots.iter().enumerate() {
+        solution.set(col, extra[i]);
+    }
+
+    Ok(solution)
+}
+
+/// Compute a basis of the null space of a matrix over GF(2) with `cols`
+/// columns, given by its rows, which is the set of vectors `x` with `A x = 0`.
+///
+/// The basis has one vector for each column of the matrix that holds no pivot
+/// of its reduced row echelon form.
+///
+/// ## Panics
+///
+/// Panics if `cols` exceeds the capacity of a bit set.
+///
+/// ## Example
+///
+/// ```
+/// # use bitset::{
+/// #     BitSet,
+/// #     gf2_nullspace,
+/// # };
+/// #
+/// // x0 + x1 + x2 = 0
+/// let rows = [BitSet::from_u64(0b111)];
+/// let basis = gf2_nullspace(&rows, 3);
+///
+/// assert_eq!(basis, vec![BitSet::from_u64(0b011), BitSet::from_u64(0b101)]);
+/// ```
+pub fn gf2_nullspace(rows: &[BitSet], cols: usize) -> Vec<BitSet> {
+    assert!(cols <= BitSet::new().capacity(), "a matrix over GF(2) has at most 128 columns");
+    let mut reduced = rows.to_vec();
+    let mut extra = vec![false; rows.len()];
+    let pivots = eliminate(&mut reduced, &mut extra);
+    let mut pivot_columns = BitSet::new();
+    for &col in pivots.iter() {
+        pivot_columns.set(col, true);
+    }
+
+    (0..cols)
+        .filter(|&free| !pivot_columns.test(free))
+        .map(|free| {
+            let mut vector = BitSet::new();
+            vector.set(free, true);
+            for (i, &col) in pivots.iter().enumerate() {
+                if reduced[i].test(free) {
+                    vector.set(col, true);
+                }
+            }
+
+            vector
+        })
+        .collect()
+}
+
+
+/// An incrementally built basis of a subspace of GF(2) vectors, also known as
+/// a linear basis or XOR basis.
+///
+/// The basis holds at most one vector for each bit position, namely the one
+/// whose highest set bit is at that position. Inserting a vector reduces it
+/// against the basis, and keeps it only when it is linearly independent of the
+/// vectors already inserted.
+///
+/// ## Example
+///
+/// ```
+/// # use bitset::{
+/// #     BitSet,
+/// #     XorBasis,
+/// # };
+/// #
+/// let mut basis = XorBasis::new();
+///
+/// assert!(basis.insert(BitSet::from_u64(0b0110)));
+/// assert!(basis.insert(BitSet::from_u64(0b0011)));
+/// assert!(!basis.insert(BitSet::from_u64(0b0101)));
+/// assert_eq!(basis.rank(), 2);
+/// assert!(basis.contains_span(&BitSet::from_u64(0b0101)));
+/// assert!(!basis.contains_span(&BitSet::from_u64(0b1000)));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct XorBasis {
+    vectors: Vec<BitSet>,
+    rank: usize,
+}
+
+impl XorBasis {
+    /// Construct the basis of the zero subspace.
+    pub fn new() -> XorBasis {
+        XorBasis {
+            vectors: vec![BitSet::new(); BitSet::new().capacity()],
+            rank: 0,
+        }
+    }
+
+    /// Return the number of linearly independent vectors in the basis.
+    #[inline]
+    pub fn rank(&self) -> usize {
+        self.rank
+    }
+
+    /// Determine whether the basis spans only the zero vector.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.rank == 0
+    }
+
+    /// Insert the vector `vector` into the basis.
+    ///
+    /// The function returns `true` if the vector is linearly independent of
+    /// the basis, in which case the rank of the basis grows by one.
+    pub fn insert(&mut self, vector: BitSet) -> bool {
+        let reduced = self.reduce(&vector);
+        if reduced.none() {
+            return false;
+        }
+
+        let high = highest_one(&reduced);
+        self.vectors[high] = reduced;
+        self.rank += 1;
+
+        true
+    }
+
+    /// Determine whether the vector `vector` lies in the span of the basis.
+    #[inline]
+    pub fn contains_span(&self, vector: &BitSet) -> bool {
+        self.reduce(vector).none()
+    }
+
+    /// Reduce the vector `vector` against the basis.
+    ///
+    /// The result differs from `vector` by an element of the span of the
+    /// basis, and has no set bit at a position that is the highest bit of a
+    /// basis vector. In particular, the result is zero exactly when `vector`
+    /// lies in the span of the basis.
+    pub fn reduce(&self, vector: &BitSet) -> BitSet {
+        let mut reduced = *vector;
+        for position in (0..reduced.capacity()).rev() {
+            if reduced.test(position) && self.vectors[position].any() {
+                reduced ^= self.vectors[position];
+            }
+        }
+
+        reduced
+    }
+
+    /// Construct an iterator over the vectors of the basis, in increasing
+    /// order of their highest set bits.
+    pub fn iter(&self) -> impl Iterator<Item = &BitSet> {
+        self.vectors.iter().filter(|vector| vector.any())
+    }
+}
+
+impl Default for XorBasis {
+    #[inline]
+    fn default() -> XorBasis {
+        XorBasis::new()
+    }
+}
+
+/// Return the position of the highest set bit of a non-zero bit set.
+#[inline]
+fn highest_one(bitset: &BitSet) -> usize {
+    127 - bitset.data.leading_zeros() as usize
+}
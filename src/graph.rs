@@ -0,0 +1,208 @@
+use crate::{
+    BitMatrix,
+    BitVec,
+};
+use std::error;
+use std::fmt;
+
+
+/// The error returned when a topological order is requested for a graph that
+/// contains a cycle.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CycleError {
+    nodes: BitVec,
+}
+
+impl CycleError {
+    /// Return the set of nodes that lie on at least one cycle.
+    #[inline]
+    pub fn nodes(&self) -> &BitVec {
+        &self.nodes
+    }
+}
+
+impl fmt::Display for CycleError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let nodes: Vec<usize> = self.nodes.iter().collect();
+        write!(formatter, "the graph contains a cycle through the nodes {:?}", nodes)
+    }
+}
+
+impl error::Error for CycleError {}
+
+
+/// Graph algorithms on square bit matrices, interpreted as adjacency matrices
+/// of directed graphs. The entry at row `i` and column `j` is `true` when
+/// there is an edge from node `i` to node `j`.
+impl BitMatrix {
+    /// Compute the transitive closure of the relation.
+    ///
+    /// The entry at row `i` and column `j` of the result is `true` when there
+    /// is a path of one or more edges from node `i` to node `j`. This uses
+    /// Warshall's algorithm, where each step updates a whole row at once.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the matrix is not square.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use bitset::{
+    /// #     BitMatrix,
+    /// # };
+    /// #
+    /// let mut graph = BitMatrix::new(3, 3);
+    /// graph.set(0, 1, true);
+    /// graph.set(1, 2, true);
+    /// let closure = graph.transitive_closure();
+    ///
+    /// assert_eq!(closure.get(0, 2), Some(true));
+    /// assert_eq!(closure.get(0, 0), Some(false));
+    /// ```
+    pub fn transitive_closure(&self) -> BitMatrix {
+        assert!(self.is_square(), "the transitive closure needs a square bit matrix");
+        let mut closure = self.clone();
+        for k in 0..closure.rows {
+            let through = closure.data[k].clone();
+            for row in closure.data.iter_mut() {
+                if row.test(k) {
+                    *row |= &through;
+                }
+            }
+        }
+
+        closure
+    }
+
+    /// Compute the reflexive transitive closure of the relation.
+    ///
+    /// The entry at row `i` and column `j` of the result is `true` when there
+    /// is a path of zero or more edges from node `i` to node `j`.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the matrix is not square.
+    pub fn reflexive_transitive_closure(&self) -> BitMatrix {
+        let mut closure = self.transitive_closure();
+        for i in 0..closure.rows {
+            closure.set(i, i, true);
+        }
+
+        closure
+    }
+
+    /// Find the set of nodes reachable from the node `node` by a path of zero
+    /// or more edges.
+    ///
+    /// The function returns `None` if `node` is outside the graph.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the matrix is not square.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use bitset::{
+    /// #     BitMatrix,
+    /// # };
+    /// #
+    /// let mut graph = BitMatrix::new(4, 4);
+    /// graph.set(0, 1, true);
+    /// graph.set(1, 2, true);
+    /// graph.set(3, 0, true);
+    /// let reachable = graph.reachable_from(0).unwrap();
+    ///
+    /// assert_eq!(reachable.iter().collect::<Vec<_>>(), vec![0, 1, 2]);
+    /// ```
+    pub fn reachable_from(&self, node: usize) -> Option<BitVec> {
+        assert!(self.is_square(), "reachability needs a square bit matrix");
+        if node >= self.rows {
+            return None;
+        }
+
+        let mut visited = BitVec::zeros(self.rows);
+        let mut frontier = BitVec::zeros(self.rows);
+        visited.set(node, true);
+        frontier.set(node, true);
+        while frontier.any() {
+            let mut next = BitVec::zeros(self.rows);
+            for i in frontier.iter() {
+                next |= &self.data[i];
+            }
+            frontier = &next & !&visited;
+            visited |= &frontier;
+        }
+
+        Some(visited)
+    }
+
+    /// Determine whether the graph contains a cycle.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the matrix is not square.
+    #[inline]
+    pub fn has_cycle(&self) -> bool {
+        self.topological_order().is_err()
+    }
+
+    /// Compute a topological order of the nodes of the graph, in which every
+    /// node comes before the nodes it has edges to.
+    ///
+    /// Among the nodes that are ready to be placed, the one with the smallest
+    /// index comes first. If the graph contains a cycle, the function returns
+    /// an error holding every node that lies on a cycle.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the matrix is not square.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use bitset::{
+    /// #     BitMatrix,
+    /// # };
+    /// #
+    /// let mut graph = BitMatrix::new(4, 4);
+    /// graph.set(2, 0, true);
+    /// graph.set(0, 1, true);
+    ///
+    /// assert_eq!(graph.topological_order(), Ok(vec![2, 0, 1, 3]));
+    ///
+    /// graph.set(1, 2, true);
+    /// let error = graph.topological_order().unwrap_err();
+    ///
+    /// assert_eq!(error.nodes().iter().collect::<Vec<_>>(), vec![0, 1, 2]);
+    /// ```
+    pub fn topological_order(&self) -> Result<Vec<usize>, CycleError> {
+        assert!(self.is_square(), "a topological order needs a square bit matrix");
+        let size = self.rows;
+        let transpose = self.transpose();
+        let mut order = Vec::with_capacity(size);
+        let mut remaining = BitVec::ones(size);
+        while order.len() < size {
+            // A node is ready when none of its predecessors remain to be placed.
+            let ready = remaining.iter()
+                .find(|&node| transpose.data[node].and_count(&remaining) == 0);
+            match ready {
+                Some(node) => {
+                    order.push(node);
+                    remaining.set(node, false);
+                }
+                None => break,
+            }
+        }
+
+        if order.len() == size {
+            return Ok(order);
+        }
+
+        let closure = self.transitive_closure();
+        let nodes = (0..size).map(|node| closure.data[node].test(node)).collect();
+
+        Err(CycleError { nodes })
+    }
+}
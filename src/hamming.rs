@@ -0,0 +1,257 @@
+use crate::BitSet;
+use std::collections::BinaryHeap;
+
+
+/// A node of the tree, holding every fingerprint equal to its own.
+#[derive(Clone, Debug)]
+struct Node {
+    fingerprint: BitSet,
+    ids: Vec<usize>,
+    /// The children, each with its distance from this node.
+    children: Vec<(usize, usize)>,
+}
+
+/// An index over bit set fingerprints answering nearest neighbor and radius
+/// queries by Hamming distance.
+///
+/// The index is a BK-tree: every child of a node sits at a known distance from
+/// it, so by the triangle inequality a search at distance `d` from a node
+/// within radius `r` only visits the children at distances `d - r..=d + r`.
+/// Every inserted fingerprint gets an id, counting up from zero, that is never
+/// reused. Removing a fingerprint leaves its node in the tree to guide
+/// searches, and the tree is rebuilt once most of its nodes hold no
+/// fingerprints.
+///
+/// ## Example
+///
+/// ```
+/// # use bitset::{
+/// #     BitSet,
+/// #     HammingIndex,
+/// # };
+/// #
+/// let mut index = HammingIndex::new();
+/// let a = index.insert(BitSet::from_u64(0b0000_1111));
+/// let b = index.insert(BitSet::from_u64(0b0000_1110));
+/// let c = index.insert(BitSet::from_u64(0b1111_0000));
+/// let query = BitSet::from_u64(0b0000_0111);
+///
+/// assert_eq!(index.within(&query, 2), vec![(a, 1), (b, 2)]);
+/// assert_eq!(index.nearest(&query, 1), vec![(a, 1)]);
+/// index.remove(a);
+/// assert_eq!(index.nearest(&query, 1), vec![(b, 2)]);
+/// assert_eq!(index.within(&query, 8), vec![(b, 2), (c, 7)]);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct HammingIndex {
+    nodes: Vec<Node>,
+    /// The node holding each id, or `None` once the id is removed.
+    locations: Vec<Option<usize>>,
+    len: usize,
+    empty_nodes: usize,
+}
+
+impl HammingIndex {
+    /// Construct a new empty index.
+    pub fn new() -> HammingIndex {
+        HammingIndex {
+            nodes: Vec::new(),
+            locations: Vec::new(),
+            len: 0,
+            empty_nodes: 0,
+        }
+    }
+
+    /// Construct an index over the fingerprints, which get the ids
+    /// `0..fingerprints.len()` in order.
+    pub fn build(fingerprints: &[BitSet]) -> HammingIndex {
+        let mut index = HammingIndex::new();
+        index.locations.reserve(fingerprints.len());
+        for fingerprint in fingerprints.iter() {
+            index.insert(*fingerprint);
+        }
+
+        index
+    }
+
+    /// Return the number of fingerprints in the index.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Determine whether the index holds no fingerprints.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Return the fingerprint with the id `id`, or `None` if there is no such
+    /// fingerprint in the index.
+    #[inline]
+    pub fn get(&self, id: usize) -> Option<&BitSet> {
+        let node = (*self.locations.get(id)?)?;
+
+        Some(&self.nodes[node].fingerprint)
+    }
+
+    /// Insert a fingerprint into the index, returning its id.
+    pub fn insert(&mut self, fingerprint: BitSet) -> usize {
+        let id = self.locations.len();
+        let node = self.insert_node(fingerprint, id);
+        self.locations.push(Some(node));
+        self.len += 1;
+
+        id
+    }
+
+    /// Add the id to the node of the tree equal to the fingerprint, creating
+    /// the node if there is none, and return the node.
+    fn insert_node(&mut self, fingerprint: BitSet, id: usize) -> usize {
+        if self.nodes.is_empty() {
+            self.nodes.push(Node {
+                fingerprint,
+                ids: vec![id],
+                children: Vec::new(),
+            });
+            return 0;
+        }
+        let mut current = 0;
+        loop {
+            let distance = self.nodes[current].fingerprint.hamming_distance(&fingerprint);
+            if distance == 0 {
+                if self.nodes[current].ids.is_empty() {
+                    self.empty_nodes -= 1;
+                }
+                self.nodes[current].ids.push(id);
+                return current;
+            }
+            let child = self.nodes[current].children.iter()
+                .find(|&&(edge, _)| edge == distance)
+                .map(|&(_, child)| child);
+            match child {
+                Some(child) => current = child,
+                None => {
+                    let node = self.nodes.len();
+                    self.nodes.push(Node {
+                        fingerprint,
+                        ids: vec![id],
+                        children: Vec::new(),
+                    });
+                    self.nodes[current].children.push((distance, node));
+                    return node;
+                }
+            }
+        }
+    }
+
+    /// Remove the fingerprint with the id `id` from the index, returning it.
+    ///
+    /// The function returns `None` if there is no such fingerprint in the
+    /// index.
+    pub fn remove(&mut self, id: usize) -> Option<BitSet> {
+        let node = self.locations.get_mut(id)?.take()?;
+        let ids = &mut self.nodes[node].ids;
+        ids.retain(|&other| other != id);
+        if ids.is_empty() {
+            self.empty_nodes += 1;
+        }
+        self.len -= 1;
+        let fingerprint = self.nodes[node].fingerprint;
+        if self.empty_nodes > self.nodes.len() / 2 {
+            self.rebuild();
+        }
+
+        Some(fingerprint)
+    }
+
+    /// Rebuild the tree from the fingerprints still in the index, keeping
+    /// their ids.
+    fn rebuild(&mut self) {
+        let nodes = std::mem::take(&mut self.nodes);
+        self.empty_nodes = 0;
+        for node in nodes.into_iter() {
+            for id in node.ids.into_iter() {
+                let location = self.insert_node(node.fingerprint, id);
+                self.locations[id] = Some(location);
+            }
+        }
+    }
+
+    /// Find every fingerprint within Hamming distance `radius` of `query`,
+    /// returning the ids with their distances ordered by distance, then id.
+    pub fn within(&self, query: &BitSet, radius: usize) -> Vec<(usize, usize)> {
+        let mut found = Vec::new();
+        let mut stack = if self.nodes.is_empty() { vec![] } else { vec![0] };
+        while let Some(current) = stack.pop() {
+            let node = &self.nodes[current];
+            let distance = node.fingerprint.hamming_distance(query);
+            if distance <= radius {
+                found.extend(node.ids.iter().map(|&id| (id, distance)));
+            }
+            stack.extend(node.children.iter()
+                .filter(|&&(edge, _)| usize::max(edge, distance) - usize::min(edge, distance) <= radius)
+                .map(|&(_, child)| child));
+        }
+        found.sort_unstable_by_key(|&(id, distance)| (distance, id));
+
+        found
+    }
+
+    /// Find the `k` fingerprints nearest to `query`, returning the ids with
+    /// their distances ordered by distance, then id.
+    ///
+    /// Ties at the largest distance are broken by the smaller id, so the
+    /// result is the first `k` entries of the whole index in that order.
+    pub fn nearest(&self, query: &BitSet, k: usize) -> Vec<(usize, usize)> {
+        if k == 0 || self.nodes.is_empty() {
+            return Vec::new();
+        }
+        // The best candidates so far, with the worst one on top.
+        let mut best: BinaryHeap<(usize, usize)> = BinaryHeap::with_capacity(k + 1);
+        // The nodes left to visit, each with a lower bound on the distance of
+        // the fingerprints below it.
+        let mut stack = vec![(0, 0)];
+        while let Some((current, bound)) = stack.pop() {
+            let radius = match best.peek() {
+                Some(&(worst, _)) if best.len() == k => worst,
+                _ => usize::MAX,
+            };
+            if bound > radius {
+                continue;
+            }
+            let node = &self.nodes[current];
+            let distance = node.fingerprint.hamming_distance(query);
+            for &id in node.ids.iter() {
+                best.push((distance, id));
+                if best.len() > k {
+                    best.pop();
+                }
+            }
+            stack.extend(node.children.iter()
+                .map(|&(edge, child)| (child, usize::max(edge, distance) - usize::min(edge, distance))));
+        }
+
+        best.into_sorted_vec()
+            .into_iter()
+            .map(|(distance, id)| (id, distance))
+            .collect()
+    }
+}
+
+impl std::iter::FromIterator<BitSet> for HammingIndex {
+    fn from_iter<I: IntoIterator<Item = BitSet>>(iter: I) -> Self {
+        let mut index = HammingIndex::new();
+        index.extend(iter);
+
+        index
+    }
+}
+
+impl Extend<BitSet> for HammingIndex {
+    fn extend<I: IntoIterator<Item = BitSet>>(&mut self, iter: I) {
+        for fingerprint in iter {
+            self.insert(fingerprint);
+        }
+    }
+}
@@ -0,0 +1,200 @@
+use std::convert::TryFrom;
+use std::hash::{
+    BuildHasherDefault,
+    Hasher,
+};
+
+
+/// The hasher builder of [`StableHasher`], the default hasher builder of the
+/// hashed data structures whose hashes outlive the process.
+pub type StableBuildHasher = BuildHasherDefault<StableHasher>;
+
+/// The SipHash initialization constants, which spell out
+/// "somepseudorandomlygeneratedbytes".
+const INIT: [u64; 4] = [
+    0x736F_6D65_7073_6575,
+    0x646F_7261_6E64_6F6D,
+    0x6C79_6765_6E65_7261,
+    0x7465_6462_7974_6573,
+];
+
+
+/// A hasher computing SipHash-1-3 with both keys set to zero.
+///
+/// Unlike the hasher of the standard library, whose algorithm may change
+/// between releases of Rust, the hasher is fixed: the same sequence of writes
+/// hashes to the same value on every platform and in every version of the
+/// crate. Integers are written as their little-endian bytes, and `usize` and
+/// `isize` values are widened to 64 bits first.
+///
+/// The promise covers the bytes and integers written through the methods of
+/// [`Hasher`], not the [`Hash`] implementations of the standard library, which
+/// may write strings, slices and tuples differently in a later release of
+/// Rust. Hashes meant to be stored should write a fixed encoding of the value
+/// explicitly.
+///
+/// [`Hash`]: std::hash::Hash
+///
+/// SipHash is keyed, so with a known key it offers no protection against
+/// inputs chosen to collide.
+///
+/// ## Example
+///
+/// ```
+/// # use bitset::{
+/// #     StableHasher,
+/// # };
+/// # use std::hash::{
+/// #     Hasher,
+/// # };
+/// #
+/// let mut hasher = StableHasher::new();
+/// hasher.write_u64(42);
+///
+/// assert_eq!(hasher.finish(), 0x7B3E_724B_36EB_DF51);
+/// ```
+#[derive(Clone, Debug)]
+pub struct StableHasher {
+    state: [u64; 4],
+    /// The bytes written since the last full word, from the lowest byte up.
+    tail: u64,
+    tail_length: usize,
+    length: usize,
+}
+
+impl StableHasher {
+    /// Construct a new hasher over the empty input.
+    pub fn new() -> StableHasher {
+        StableHasher {
+            state: INIT,
+            tail: 0,
+            tail_length: 0,
+            length: 0,
+        }
+    }
+
+    /// Apply one SipRound to the state.
+    #[inline]
+    fn round(&mut self) {
+        let [mut v0, mut v1, mut v2, mut v3] = self.state;
+        v0 = v0.wrapping_add(v1);
+        v1 = v1.rotate_left(13) ^ v0;
+        v0 = v0.rotate_left(32);
+        v2 = v2.wrapping_add(v3);
+        v3 = v3.rotate_left(16) ^ v2;
+        v0 = v0.wrapping_add(v3);
+        v3 = v3.rotate_left(21) ^ v0;
+        v2 = v2.wrapping_add(v1);
+        v1 = v1.rotate_left(17) ^ v2;
+        v2 = v2.rotate_left(32);
+        self.state = [v0, v1, v2, v3];
+    }
+
+    /// Compress one word of the input into the state.
+    #[inline]
+    fn absorb(&mut self, word: u64) {
+        self.state[3] ^= word;
+        self.round();
+        self.state[0] ^= word;
+    }
+}
+
+impl Default for StableHasher {
+    #[inline]
+    fn default() -> StableHasher {
+        StableHasher::new()
+    }
+}
+
+impl Hasher for StableHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        self.length = self.length.wrapping_add(bytes.len());
+        let mut bytes = bytes;
+        if self.tail_length > 0 {
+            let fill = usize::min(8 - self.tail_length, bytes.len());
+            for &byte in bytes[..fill].iter() {
+                self.tail |= (byte as u64) << (8 * self.tail_length);
+                self.tail_length += 1;
+            }
+            bytes = &bytes[fill..];
+            if self.tail_length < 8 {
+                return;
+            }
+            let word = self.tail;
+            self.absorb(word);
+            self.tail = 0;
+            self.tail_length = 0;
+        }
+        let mut words = bytes.chunks_exact(8);
+        for word in &mut words {
+            let word = <[u8; 8]>::try_from(word).expect("a chunk has eight bytes");
+            self.absorb(u64::from_le_bytes(word));
+        }
+        for &byte in words.remainder().iter() {
+            self.tail |= (byte as u64) << (8 * self.tail_length);
+            self.tail_length += 1;
+        }
+    }
+
+    #[inline]
+    fn write_u16(&mut self, value: u16) {
+        self.write(&value.to_le_bytes());
+    }
+
+    #[inline]
+    fn write_u32(&mut self, value: u32) {
+        self.write(&value.to_le_bytes());
+    }
+
+    #[inline]
+    fn write_u64(&mut self, value: u64) {
+        self.write(&value.to_le_bytes());
+    }
+
+    #[inline]
+    fn write_u128(&mut self, value: u128) {
+        self.write(&value.to_le_bytes());
+    }
+
+    #[inline]
+    fn write_usize(&mut self, value: usize) {
+        self.write_u64(value as u64);
+    }
+
+    #[inline]
+    fn write_i16(&mut self, value: i16) {
+        self.write(&value.to_le_bytes());
+    }
+
+    #[inline]
+    fn write_i32(&mut self, value: i32) {
+        self.write(&value.to_le_bytes());
+    }
+
+    #[inline]
+    fn write_i64(&mut self, value: i64) {
+        self.write(&value.to_le_bytes());
+    }
+
+    #[inline]
+    fn write_i128(&mut self, value: i128) {
+        self.write(&value.to_le_bytes());
+    }
+
+    #[inline]
+    fn write_isize(&mut self, value: isize) {
+        self.write_i64(value as i64);
+    }
+
+    fn finish(&self) -> u64 {
+        let mut final_state = self.clone();
+        final_state.absorb(((self.length as u64 & 0xFF) << 56) | self.tail);
+        final_state.state[2] ^= 0xFF;
+        for _ in 0..3 {
+            final_state.round();
+        }
+        let [v0, v1, v2, v3] = final_state.state;
+
+        v0 ^ v1 ^ v2 ^ v3
+    }
+}
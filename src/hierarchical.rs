@@ -0,0 +1,510 @@
+use crate::BitSet;
+use std::ops;
+
+
+/// The number of bits needed to address a bit inside a single layer word.
+const WORD_BITS: usize = 7;
+
+/// The mask selecting a bit index inside a single layer word.
+const WORD_MASK: usize = (1 << WORD_BITS) - 1;
+
+/// The number of layers in a hierarchical bit set, including the bottom layer.
+const LAYERS: usize = 4;
+
+/// The number of addressable bits in a hierarchical bit set.
+const CAPACITY: usize = 1 << (WORD_BITS * LAYERS);
+
+
+/// A data type that exposes the layers of a hierarchical bit set.
+///
+/// Layer `0` holds the bits themselves as a sequence of 128-bit words. Each bit
+/// of a word in layer `level + 1` summarizes whether the corresponding word of
+/// layer `level` may contain set bits. Summaries are allowed to
+/// over-approximate: a summary bit may be set for a word that turns out to be
+/// empty, but a non-empty word must always have its summary bit set. This is
+/// what lets lazy join views compute their summaries from the summaries of
+/// their operands.
+pub trait LayeredBitSet {
+    /// Return the word at index `index` in the layer `level`.
+    ///
+    /// Words past the end of a layer are empty.
+    fn layer(&self, level: usize, index: usize) -> BitSet;
+
+    /// Test whether the bit at position `position` is set.
+    ///
+    /// If the position `position` exceeds the capacity of the bit set,
+    /// the function returns `false`.
+    #[inline]
+    fn contains(&self, position: usize) -> bool {
+        position < CAPACITY && self.layer(0, position >> WORD_BITS).test(position & WORD_MASK)
+    }
+
+    /// Find the smallest position of a set bit in the bit set.
+    ///
+    /// The function returns `None` if no bits are set.
+    #[inline]
+    fn first_one(&self) -> Option<usize> {
+        next_one_from(self, 0)
+    }
+
+    /// Find the smallest position of a set bit strictly greater than
+    /// `position`.
+    ///
+    /// The search skips over every empty region of the bit set by consulting
+    /// the upper layers, so it runs in time proportional to the number of
+    /// layers rather than the distance to the next set bit.
+    #[inline]
+    fn next_one_after(&self, position: usize) -> Option<usize> {
+        next_one_from(self, position.checked_add(1)?)
+    }
+
+    /// Construct an iterator over the positions of the set bits in
+    /// increasing order.
+    #[inline]
+    fn iter(&self) -> LayeredIter<'_, Self> {
+        LayeredIter {
+            set: self,
+            position: 0,
+        }
+    }
+}
+
+impl<S> LayeredBitSet for &S where S: LayeredBitSet + ?Sized {
+    #[inline]
+    fn layer(&self, level: usize, index: usize) -> BitSet {
+        (**self).layer(level, index)
+    }
+}
+
+/// Find the smallest set position greater than or equal to `start`.
+fn next_one_from<S>(set: &S, start: usize) -> Option<usize> where S: LayeredBitSet + ?Sized {
+    let mut position = start;
+    'search: while position < CAPACITY {
+        for level in 0..LAYERS {
+            let shift = WORD_BITS * level;
+            let index = position >> (shift + WORD_BITS);
+            let bit = (position >> shift) & WORD_MASK;
+            let word = set.layer(level, index).data;
+            // The bottom layer is searched starting at `position` itself. On the
+            // upper layers, the region containing `position` has already been
+            // exhausted by the layer below, so only later regions are searched.
+            let candidates = if level == 0 {
+                word & (!0_u128 << bit)
+            } else if bit < WORD_MASK {
+                word & (!0_u128 << (bit + 1))
+            } else {
+                0
+            };
+            if candidates == 0 {
+                continue;
+            }
+
+            let mut found = (index << WORD_BITS) | (candidates.trailing_zeros() as usize);
+            for lower in (0..level).rev() {
+                let word = set.layer(lower, found).data;
+                if word == 0 {
+                    // The summary over-approximated this region. Resume the search
+                    // after it.
+                    position = (found + 1) << (WORD_BITS * (lower + 1));
+                    continue 'search;
+                }
+                found = (found << WORD_BITS) | (word.trailing_zeros() as usize);
+            }
+
+            return Some(found);
+        }
+
+        return None;
+    }
+
+    None
+}
+
+
+/// A bit set made of several layers of 128-bit words, where each bit of an
+/// upper layer records whether a word of the layer below is non-zero.
+///
+/// Hierarchical bit sets are meant for very sparse sets of large indices, such
+/// as the entities of an entity component system. Iteration and searching skip
+/// over empty regions of the set in time proportional to the number of layers.
+/// Hierarchical bit sets can be combined lazily with the `&`, `|`, and `!`
+/// operators into join views that produce the matching positions without
+/// allocating any intermediate sets.
+#[derive(Clone, Debug)]
+pub struct HierarchicalBitSet {
+    layers: [Vec<BitSet>; LAYERS],
+}
+
+impl HierarchicalBitSet {
+    /// Construct a new hierarchical bit set with all bits set to `false`.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use bitset::{
+    /// #     HierarchicalBitSet,
+    /// #     LayeredBitSet,
+    /// # };
+    /// #
+    /// let bitset = HierarchicalBitSet::new();
+    ///
+    /// assert!(bitset.is_empty());
+    /// assert_eq!(bitset.first_one(), None);
+    /// ```
+    #[inline]
+    pub fn new() -> HierarchicalBitSet {
+        HierarchicalBitSet {
+            layers: [Vec::new(), Vec::new(), Vec::new(), Vec::new()],
+        }
+    }
+
+    /// Return the maximum number of bits that this bit set can hold.
+    #[inline]
+    pub const fn capacity(&self) -> usize {
+        CAPACITY
+    }
+
+    /// Set the bit at position `position` to `true`.
+    ///
+    /// The function returns `Some(true)` if the bit was previously unset,
+    /// `Some(false)` if it was already set, and `None` if `position` is outside
+    /// the capacity of the bit set.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use bitset::{
+    /// #     HierarchicalBitSet,
+    /// #     LayeredBitSet,
+    /// # };
+    /// #
+    /// let mut bitset = HierarchicalBitSet::new();
+    ///
+    /// assert_eq!(bitset.insert(1 << 20), Some(true));
+    /// assert_eq!(bitset.insert(1 << 20), Some(false));
+    /// assert!(bitset.contains(1 << 20));
+    /// assert_eq!(bitset.insert(bitset.capacity()), None);
+    /// ```
+    pub fn insert(&mut self, position: usize) -> Option<bool> {
+        if position >= self.capacity() {
+            return None;
+        }
+
+        let mut current = position;
+        for (level, layer) in self.layers.iter_mut().enumerate() {
+            let index = current >> WORD_BITS;
+            if layer.len() <= index {
+                layer.resize(index + 1, BitSet::new());
+            }
+            let word = &mut layer[index];
+            let was_empty = word.none();
+            let was_set = word.test(current & WORD_MASK);
+            word.set(current & WORD_MASK, true);
+            if level == 0 && was_set {
+                return Some(false);
+            }
+            if !was_empty {
+                break;
+            }
+            current = index;
+        }
+
+        Some(true)
+    }
+
+    /// Set the bit at position `position` to `false`.
+    ///
+    /// The function returns `true` if the bit was previously set.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use bitset::{
+    /// #     HierarchicalBitSet,
+    /// #     LayeredBitSet,
+    /// # };
+    /// #
+    /// let mut bitset = HierarchicalBitSet::new();
+    /// bitset.insert(70_000);
+    ///
+    /// assert!(bitset.remove(70_000));
+    /// assert!(!bitset.remove(70_000));
+    /// assert!(bitset.is_empty());
+    /// ```
+    pub fn remove(&mut self, position: usize) -> bool {
+        if !self.contains(position) {
+            return false;
+        }
+
+        let mut current = position;
+        for layer in self.layers.iter_mut() {
+            let index = current >> WORD_BITS;
+            let word = &mut layer[index];
+            word.set(current & WORD_MASK, false);
+            if word.any() {
+                break;
+            }
+            current = index;
+        }
+
+        true
+    }
+
+    /// Set all the bits in the bit set to `false`.
+    #[inline]
+    pub fn clear(&mut self) {
+        for layer in self.layers.iter_mut() {
+            layer.clear();
+        }
+    }
+
+    /// Test whether none of the bits in the bit set are set to true.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.layer(LAYERS - 1, 0).none()
+    }
+
+    /// Count up the number of bits in the bit set that are set to true.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use bitset::{
+    /// #     HierarchicalBitSet,
+    /// # };
+    /// #
+    /// let bitset: HierarchicalBitSet = vec![3, 300, 30_000].into_iter().collect();
+    ///
+    /// assert_eq!(bitset.count(), 3);
+    /// ```
+    pub fn count(&self) -> usize {
+        self.layers[0].iter().map(|word| word.count()).sum()
+    }
+}
+
+impl Default for HierarchicalBitSet {
+    #[inline]
+    fn default() -> HierarchicalBitSet {
+        HierarchicalBitSet::new()
+    }
+}
+
+impl PartialEq for HierarchicalBitSet {
+    fn eq(&self, other: &HierarchicalBitSet) -> bool {
+        // Removing bits can leave empty words at the end of a layer, so the
+        // layers are compared as if they were padded with empty words.
+        let length = usize::max(self.layers[0].len(), other.layers[0].len());
+        (0..length).all(|index| self.layer(0, index) == other.layer(0, index))
+    }
+}
+
+impl Eq for HierarchicalBitSet {}
+
+impl LayeredBitSet for HierarchicalBitSet {
+    #[inline]
+    fn layer(&self, level: usize, index: usize) -> BitSet {
+        self.layers
+            .get(level)
+            .and_then(|layer| layer.get(index))
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+impl std::iter::FromIterator<usize> for HierarchicalBitSet {
+    fn from_iter<I>(iter: I) -> HierarchicalBitSet where I: IntoIterator<Item = usize> {
+        let mut bitset = HierarchicalBitSet::new();
+        bitset.extend(iter);
+
+        bitset
+    }
+}
+
+impl Extend<usize> for HierarchicalBitSet {
+    fn extend<I>(&mut self, iter: I) where I: IntoIterator<Item = usize> {
+        for position in iter {
+            self.insert(position);
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a HierarchicalBitSet {
+    type Item = usize;
+    type IntoIter = LayeredIter<'a, HierarchicalBitSet>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+
+/// An iterator over the positions of the set bits of a hierarchical bit set or
+/// join view, in increasing order.
+#[derive(Clone, Debug)]
+pub struct LayeredIter<'a, S> where S: ?Sized {
+    set: &'a S,
+    position: usize,
+}
+
+impl<'a, S> Iterator for LayeredIter<'a, S> where S: LayeredBitSet + ?Sized {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let found = next_one_from(self.set, self.position);
+        self.position = match found {
+            Some(position) => position + 1,
+            None => CAPACITY,
+        };
+
+        found
+    }
+}
+
+
+/// A lazy view of the intersection of two hierarchical bit sets.
+#[derive(Copy, Clone, Debug)]
+pub struct BitSetAnd<A, B>(pub A, pub B);
+
+/// A lazy view of the union of two hierarchical bit sets.
+#[derive(Copy, Clone, Debug)]
+pub struct BitSetOr<A, B>(pub A, pub B);
+
+/// A lazy view of the complement of a hierarchical bit set.
+#[derive(Copy, Clone, Debug)]
+pub struct BitSetNot<A>(pub A);
+
+impl<A, B> LayeredBitSet for BitSetAnd<A, B> where A: LayeredBitSet, B: LayeredBitSet {
+    #[inline]
+    fn layer(&self, level: usize, index: usize) -> BitSet {
+        self.0.layer(level, index) & self.1.layer(level, index)
+    }
+}
+
+impl<A, B> LayeredBitSet for BitSetOr<A, B> where A: LayeredBitSet, B: LayeredBitSet {
+    #[inline]
+    fn layer(&self, level: usize, index: usize) -> BitSet {
+        self.0.layer(level, index) | self.1.layer(level, index)
+    }
+}
+
+impl<A> LayeredBitSet for BitSetNot<A> where A: LayeredBitSet {
+    #[inline]
+    fn layer(&self, level: usize, index: usize) -> BitSet {
+        // Any region of the complement may contain set bits, so every summary bit
+        // is set.
+        if level == 0 {
+            !self.0.layer(level, index)
+        } else {
+            !BitSet::new()
+        }
+    }
+}
+
+impl<'a, R> ops::BitAnd<R> for &'a HierarchicalBitSet where R: LayeredBitSet {
+    type Output = BitSetAnd<&'a HierarchicalBitSet, R>;
+
+    #[inline]
+    fn bitand(self, other: R) -> Self::Output {
+        BitSetAnd(self, other)
+    }
+}
+
+impl<'a, R> ops::BitOr<R> for &'a HierarchicalBitSet where R: LayeredBitSet {
+    type Output = BitSetOr<&'a HierarchicalBitSet, R>;
+
+    #[inline]
+    fn bitor(self, other: R) -> Self::Output {
+        BitSetOr(self, other)
+    }
+}
+
+impl<'a> ops::Not for &'a HierarchicalBitSet {
+    type Output = BitSetNot<&'a HierarchicalBitSet>;
+
+    #[inline]
+    fn not(self) -> Self::Output {
+        BitSetNot(self)
+    }
+}
+
+impl<A, B, R> ops::BitAnd<R> for BitSetAnd<A, B> where A: LayeredBitSet, B: LayeredBitSet, R: LayeredBitSet {
+    type Output = BitSetAnd<BitSetAnd<A, B>, R>;
+
+    #[inline]
+    fn bitand(self, other: R) -> Self::Output {
+        BitSetAnd(self, other)
+    }
+}
+
+impl<A, B, R> ops::BitOr<R> for BitSetAnd<A, B> where A: LayeredBitSet, B: LayeredBitSet, R: LayeredBitSet {
+    type Output = BitSetOr<BitSetAnd<A, B>, R>;
+
+    #[inline]
+    fn bitor(self, other: R) -> Self::Output {
+        BitSetOr(self, other)
+    }
+}
+
+impl<A, B> ops::Not for BitSetAnd<A, B> where A: LayeredBitSet, B: LayeredBitSet {
+    type Output = BitSetNot<BitSetAnd<A, B>>;
+
+    #[inline]
+    fn not(self) -> Self::Output {
+        BitSetNot(self)
+    }
+}
+
+impl<A, B, R> ops::BitAnd<R> for BitSetOr<A, B> where A: LayeredBitSet, B: LayeredBitSet, R: LayeredBitSet {
+    type Output = BitSetAnd<BitSetOr<A, B>, R>;
+
+    #[inline]
+    fn bitand(self, other: R) -> Self::Output {
+        BitSetAnd(self, other)
+    }
+}
+
+impl<A, B, R> ops::BitOr<R> for BitSetOr<A, B> where A: LayeredBitSet, B: LayeredBitSet, R: LayeredBitSet {
+    type Output = BitSetOr<BitSetOr<A, B>, R>;
+
+    #[inline]
+    fn bitor(self, other: R) -> Self::Output {
+        BitSetOr(self, other)
+    }
+}
+
+impl<A, B> ops::Not for BitSetOr<A, B> where A: LayeredBitSet, B: LayeredBitSet {
+    type Output = BitSetNot<BitSetOr<A, B>>;
+
+    #[inline]
+    fn not(self) -> Self::Output {
+        BitSetNot(self)
+    }
+}
+
+impl<A, R> ops::BitAnd<R> for BitSetNot<A> where A: LayeredBitSet, R: LayeredBitSet {
+    type Output = BitSetAnd<BitSetNot<A>, R>;
+
+    #[inline]
+    fn bitand(self, other: R) -> Self::Output {
+        BitSetAnd(self, other)
+    }
+}
+
+impl<A, R> ops::BitOr<R> for BitSetNot<A> where A: LayeredBitSet, R: LayeredBitSet {
+    type Output = BitSetOr<BitSetNot<A>, R>;
+
+    #[inline]
+    fn bitor(self, other: R) -> Self::Output {
+        BitSetOr(self, other)
+    }
+}
+
+impl<A> ops::Not for BitSetNot<A> where A: LayeredBitSet {
+    type Output = A;
+
+    #[inline]
+    fn not(self) -> Self::Output {
+        self.0
+    }
+}
@@ -40,6 +40,137 @@ generics.
 use std::fmt;
 use std::ops;
 
+mod bit_sliced;
+mod bitmap_index;
+mod bitmatrix;
+mod bitvec;
+mod bloom;
+mod deposit;
+mod gf2;
+mod graph;
+mod hamming;
+mod hash;
+mod hierarchical;
+mod minhash;
+mod minimize;
+mod morton;
+mod permute;
+mod poly;
+mod query;
+mod registry;
+mod replay;
+mod runs;
+mod schedule;
+mod similarity;
+mod slots;
+mod subsets;
+mod ternary;
+mod transpose;
+mod truth_table;
+
+pub use crate::bit_sliced::{
+    BitSlicedIndex,
+    RangeEncodedIndex,
+};
+pub use crate::bitmap_index::BitmapIndex;
+pub use crate::bitmatrix::BitMatrix;
+pub use crate::bitvec::{
+    BitVec,
+    BitVecIter,
+};
+pub use crate::bloom::{
+    BlockedBloomFilter,
+    BloomFilter,
+    CountingBloomFilter,
+    DefaultBloomHasher,
+    InvalidBloomFilter,
+};
+pub use crate::gf2::{
+    InconsistentSystem,
+    XorBasis,
+    gf2_nullspace,
+    gf2_rank,
+    gf2_row_reduce,
+    gf2_solve,
+};
+pub use crate::graph::CycleError;
+pub use crate::hamming::HammingIndex;
+pub use crate::hash::{
+    StableBuildHasher,
+    StableHasher,
+};
+pub use crate::hierarchical::{
+    BitSetAnd,
+    BitSetNot,
+    BitSetOr,
+    HierarchicalBitSet,
+    LayeredIter,
+    LayeredBitSet,
+};
+pub use crate::minhash::{
+    LshIndex,
+    MinHasher,
+    Signature,
+};
+pub use crate::minimize::{
+    Cover,
+    MinimizeMode,
+};
+pub use crate::permute::BitPermutation;
+pub use crate::poly::{
+    Gf2Field128,
+    Gf2Poly,
+};
+pub use crate::query::{
+    Query,
+    QueryDisplay,
+};
+pub use crate::registry::{
+    ComponentRegistry,
+    ComponentSet,
+    RegistryError,
+    RegistrySnapshot,
+};
+pub use crate::replay::{
+    ReplayStatus,
+    ReplayWindow,
+    WindowStorage,
+};
+pub use crate::runs::{
+    BitVecRuns,
+    Runs,
+};
+pub use crate::schedule::{
+    Conflict,
+    ConflictKind,
+    Schedule,
+    ScheduleError,
+    Scheduler,
+    SystemAccess,
+    SystemId,
+};
+pub use crate::subsets::{
+    BitVecCombinations,
+    BitVecSubsets,
+    Combinations,
+    Subsets,
+};
+pub use crate::ternary::{
+    Classifier,
+    ParseTernaryError,
+    Rule,
+    TernaryBitSet,
+};
+pub use crate::transpose::{
+    transpose_128,
+    transpose_64,
+    transpose_8,
+};
+pub use crate::truth_table::{
+    NpnTransform,
+    TruthTable,
+};
+
 
 /// A fixed-size sequence of N bits. Bit sets can be transformed by 
 /// standard logic operators and converted to and from integers.
@@ -474,6 +605,69 @@ impl BitSet {
 
         st
     }
+
+    /// Construct an iterator over the positions of the bits set to `true`, in
+    /// increasing order.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use bitset::{
+    /// #     BitSet,
+    /// # };
+    /// #
+    /// let bitset = BitSet::from_u128(0b1001_0010 | (1 << 127));
+    /// let positions: Vec<usize> = bitset.iter().collect();
+    ///
+    /// assert_eq!(positions, vec![1, 4, 7, 127]);
+    /// ```
+    #[inline]
+    pub fn iter(&self) -> Iter {
+        Iter {
+            data: self.data,
+        }
+    }
+}
+
+impl Default for BitSet {
+    #[inline]
+    fn default() -> BitSet {
+        BitSet::new()
+    }
+}
+
+impl IntoIterator for BitSet {
+    type Item = usize;
+    type IntoIter = Iter;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl IntoIterator for &BitSet {
+    type Item = usize;
+    type IntoIter = Iter;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl std::iter::FromIterator<usize> for BitSet {
+    /// Construct a bit set with the bits at the given positions set to `true`.
+    ///
+    /// Positions outside the capacity of the bit set are ignored.
+    fn from_iter<I>(iter: I) -> BitSet where I: IntoIterator<Item = usize> {
+        let mut bitset = BitSet::new();
+        for position in iter {
+            bitset.set(position, true);
+        }
+
+        bitset
+    }
 }
 
 impl fmt::Display for BitSet {
@@ -482,6 +676,50 @@ impl fmt::Display for BitSet {
     }
 }
 
+/// An iterator over the positions of the bits of a bit set that are set to
+/// `true`.
+#[derive(Clone, Debug)]
+pub struct Iter {
+    data: u128,
+}
+
+impl Iterator for Iter {
+    type Item = usize;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.data == 0 {
+            return None;
+        }
+        let position = self.data.trailing_zeros() as usize;
+        self.data &= self.data - 1;
+
+        Some(position)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let count = self.data.count_ones() as usize;
+
+        (count, Some(count))
+    }
+}
+
+impl DoubleEndedIterator for Iter {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.data == 0 {
+            return None;
+        }
+        let position = 127 - self.data.leading_zeros() as usize;
+        self.data ^= 1 << position;
+
+        Some(position)
+    }
+}
+
+impl ExactSizeIterator for Iter {}
+
 impl ops::BitAnd<BitSet> for BitSet {
     type Output = BitSet;
 
@@ -518,7 +756,7 @@ impl ops::BitAnd<BitSet> for &BitSet {
     }
 }
 
-impl<'a, 'b> ops::BitAnd<&'a BitSet> for &'b BitSet {
+impl<'a> ops::BitAnd<&'a BitSet> for &BitSet {
     type Output = BitSet;
 
     #[inline]
@@ -566,7 +804,7 @@ impl ops::BitOr<BitSet> for &BitSet {
     }
 }
 
-impl<'a, 'b> ops::BitOr<&'a BitSet> for &'b BitSet {
+impl<'a> ops::BitOr<&'a BitSet> for &BitSet {
     type Output = BitSet;
 
     #[inline]
@@ -614,7 +852,7 @@ impl ops::BitXor<BitSet> for &BitSet {
     }
 }
 
-impl<'a, 'b> ops::BitXor<&'a BitSet> for &'b BitSet {
+impl<'a> ops::BitXor<&'a BitSet> for &BitSet {
     type Output = BitSet;
 
     #[inline]
@@ -629,10 +867,25 @@ impl<'a, 'b> ops::BitXor<&'a BitSet> for &'b BitSet {
 impl ops::Shl<usize> for BitSet {
     type Output = BitSet;
 
+    /// Shift the bits of the bit set toward the higher positions. Shifting by
+    /// the capacity of the bit set or more yields the empty bit set.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use bitset::{
+    /// #     BitSet,
+    /// # };
+    /// #
+    /// let bitset = BitSet::from_u64(0b1011);
+    ///
+    /// assert_eq!(bitset << 2, BitSet::from_u64(0b101100));
+    /// assert_eq!(bitset << 128, BitSet::new());
+    /// ```
     #[inline]
     fn shl(self, amount: usize) -> Self::Output {
         let mut bitset = BitSet::new();
-        bitset.data = self.data << amount;
+        bitset.data = if amount < 128 { self.data << amount } else { 0 };
 
         bitset
     }
@@ -644,7 +897,7 @@ impl ops::Shl<usize> for &BitSet {
     #[inline]
     fn shl(self, amount: usize) -> Self::Output {
         let mut bitset = BitSet::new();
-        bitset.data = self.data << amount;
+        bitset.data = if amount < 128 { self.data << amount } else { 0 };
 
         bitset
     }
@@ -653,10 +906,12 @@ impl ops::Shl<usize> for &BitSet {
 impl ops::Shr<usize> for BitSet {
     type Output = BitSet;
 
+    /// Shift the bits of the bit set toward the lower positions. Shifting by
+    /// the capacity of the bit set or more yields the empty bit set.
     #[inline]
     fn shr(self, amount: usize) -> Self::Output {
         let mut bitset = BitSet::new();
-        bitset.data = self.data >> amount;
+        bitset.data = if amount < 128 { self.data >> amount } else { 0 };
 
         bitset
     }
@@ -668,7 +923,7 @@ impl ops::Shr<usize> for &BitSet {
     #[inline]
     fn shr(self, amount: usize) -> Self::Output {
         let mut bitset = BitSet::new();
-        bitset.data = self.data >> amount;
+        bitset.data = if amount < 128 { self.data >> amount } else { 0 };
 
         bitset
     }
@@ -719,14 +974,14 @@ impl ops::BitXorAssign<&BitSet> for BitSet {
 impl ops::ShlAssign<usize> for BitSet {
     #[inline]
     fn shl_assign(&mut self, amount: usize) {
-        self.data <<= amount;
+        self.data = if amount < 128 { self.data << amount } else { 0 };
     }
 }
 
 impl ops::ShrAssign<usize> for BitSet {
     #[inline]
     fn shr_assign(&mut self, amount: usize) {
-        self.data >>= amount;
+        self.data = if amount < 128 { self.data >> amount } else { 0 };
     }
 }
 
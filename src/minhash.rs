@@ -0,0 +1,387 @@
+use crate::{
+    BitSet,
+    BitVec,
+    StableBuildHasher,
+};
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{
+    BuildHasher,
+    Hash,
+    Hasher,
+};
+
+
+/// Advance a SplitMix64 generator, returning its next output.
+#[inline]
+fn split_mix(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+
+    mix(*state)
+}
+
+/// Scramble a word with the SplitMix64 finalizer, so that nearby inputs give
+/// unrelated outputs.
+#[inline]
+fn mix(value: u64) -> u64 {
+    let value = (value ^ (value >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    let value = (value ^ (value >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+
+    value ^ (value >> 31)
+}
+
+
+/// A MinHash signature of a set: for each hash function, the smallest hash of
+/// an element of the set.
+///
+/// Two sets agree on each value of their signatures with probability equal to
+/// their Jaccard similarity, so the fraction of agreeing values estimates it.
+/// The signature of the empty set has every value equal to `u64::MAX`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Signature {
+    values: Vec<u64>,
+}
+
+impl Signature {
+    /// Return the values of the signature, one for each hash function.
+    #[inline]
+    pub fn values(&self) -> &[u64] {
+        &self.values
+    }
+
+    /// Return the number of values of the signature.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Determine whether the signature has no values.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Estimate the Jaccard similarity of the sets behind two signatures, as
+    /// the fraction of their values that agree.
+    ///
+    /// The function returns `None` if the signatures have different lengths or
+    /// no values.
+    pub fn estimated_jaccard(&self, other: &Signature) -> Option<f64> {
+        if self.len() != other.len() || self.is_empty() {
+            return None;
+        }
+        let agreeing = self.values.iter()
+            .zip(other.values.iter())
+            .filter(|(left, right)| left == right)
+            .count();
+
+        Some(agreeing as f64 / self.len() as f64)
+    }
+}
+
+
+/// A family of hash functions computing MinHash signatures of sets of
+/// positions.
+///
+/// Each element is hashed once with the hasher builder, and the hash functions
+/// of the family scramble that hash with keys derived from the seed. Hashers
+/// built with the same number of hash functions, seed, and hasher builder
+/// compute the same signatures. The default hasher builder is
+/// [`StableBuildHasher`], which hashes the same writes to the same value in
+/// every release, so signatures of elements whose [`Hash`] implementations
+/// write a fixed encoding can be stored and compared later.
+///
+/// ## Example
+///
+/// ```
+/// # use bitset::{
+/// #     BitSet,
+/// #     MinHasher,
+/// # };
+/// #
+/// let hasher = MinHasher::new(256, 42);
+/// let a: BitSet = (0..100).collect();
+/// let b: BitSet = (20..120).collect();
+/// let estimate = hasher.bitset_signature(&a)
+///     .estimated_jaccard(&hasher.bitset_signature(&b))
+///     .unwrap();
+///
+/// // The exact similarity is 80 / 120.
+/// assert!((estimate - a.jaccard_similarity(&b)).abs() < 0.1);
+/// ```
+#[derive(Clone, Debug)]
+pub struct MinHasher<S = StableBuildHasher> {
+    keys: Vec<u64>,
+    builder: S,
+}
+
+impl MinHasher {
+    /// Construct a family of `hashes` hash functions derived from `seed`.
+    pub fn new(hashes: usize, seed: u64) -> MinHasher {
+        MinHasher::with_hasher(hashes, seed, StableBuildHasher::default())
+    }
+}
+
+impl<S> MinHasher<S> where S: BuildHasher {
+    /// Construct a family of `hashes` hash functions derived from `seed`,
+    /// hashing elements with `builder`.
+    pub fn with_hasher(hashes: usize, seed: u64, builder: S) -> MinHasher<S> {
+        let mut state = seed;
+        let keys = (0..hashes).map(|_| split_mix(&mut state)).collect();
+
+        MinHasher {
+            keys,
+            builder,
+        }
+    }
+
+    /// Return the number of hash functions, which is the length of the
+    /// signatures.
+    #[inline]
+    pub fn hashes(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Compute the signature of the set of elements.
+    pub fn signature<I>(&self, elements: I) -> Signature where I: IntoIterator<Item = usize> {
+        let mut values = vec![u64::MAX; self.keys.len()];
+        for element in elements {
+            let mut hasher = self.builder.build_hasher();
+            hasher.write_u64(element as u64);
+            let hash = hasher.finish();
+            for (value, &key) in values.iter_mut().zip(self.keys.iter()) {
+                *value = u64::min(*value, mix(hash ^ key));
+            }
+        }
+
+        Signature { values }
+    }
+
+    /// Compute the signature of the positions of the bits set to `true` in
+    /// a bit set.
+    #[inline]
+    pub fn bitset_signature(&self, bitset: &BitSet) -> Signature {
+        self.signature(bitset.iter())
+    }
+
+    /// Compute the signature of the positions of the bits set to `true` in
+    /// a bit vector.
+    #[inline]
+    pub fn bitvec_signature(&self, bitvec: &BitVec) -> Signature {
+        self.signature(bitvec.iter())
+    }
+}
+
+
+/// An index of MinHash signatures finding the pairs of sets that are likely
+/// to be similar, by locality-sensitive hashing.
+///
+/// The first `bands × rows` values of each signature are split into `bands`
+/// bands of `rows` values, and two signatures become a candidate pair when
+/// they agree on every value of at least one band. Sets with Jaccard
+/// similarity `s` become candidates with probability `1 - (1 - s^rows)^bands`,
+/// which rises steeply around the threshold `(1 / bands)^(1 / rows)`.
+///
+/// Candidates are only likely to be similar, so they should be verified with
+/// the exact similarity of the sets, as [`LshIndex::similar_pairs`] does.
+///
+/// ## Example
+///
+/// ```
+/// # use bitset::{
+/// #     BitVec,
+/// #     LshIndex,
+/// #     MinHasher,
+/// # };
+/// #
+/// let sets: Vec<BitVec> = vec![
+///     (0..1000).map(|i| i < 500).collect(),
+///     (0..1000).map(|i| i < 490).collect(),
+///     (0..1000).map(|i| i >= 500).collect(),
+/// ];
+/// let hasher = MinHasher::new(128, 7);
+/// let mut index = LshIndex::with_threshold(128, 0.8).unwrap();
+/// for set in sets.iter() {
+///     index.insert(&hasher.bitvec_signature(set));
+/// }
+/// let pairs = index.similar_pairs(0.9, |i, j| sets[i].jaccard_similarity(&sets[j]));
+///
+/// assert_eq!(pairs, vec![(0, 1, 0.98)]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct LshIndex {
+    bands: usize,
+    rows: usize,
+    /// For each band, the ids of the signatures by the hash of their values in
+    /// the band.
+    tables: Vec<HashMap<u64, Vec<usize>>>,
+    len: usize,
+}
+
+impl LshIndex {
+    /// Construct an empty index splitting signatures into `bands` bands of
+    /// `rows` values.
+    ///
+    /// The function returns `None` if either number is zero.
+    pub fn new(bands: usize, rows: usize) -> Option<LshIndex> {
+        if bands == 0 || rows == 0 {
+            return None;
+        }
+
+        Some(LshIndex {
+            bands,
+            rows,
+            tables: vec![HashMap::new(); bands],
+            len: 0,
+        })
+    }
+
+    /// Construct an empty index for signatures of `hashes` values, choosing
+    /// the numbers of bands and rows whose threshold is closest to
+    /// `threshold`.
+    ///
+    /// The function returns `None` if `hashes` is zero or `threshold` is not
+    /// in `0.0..=1.0`.
+    pub fn with_threshold(hashes: usize, threshold: f64) -> Option<LshIndex> {
+        if hashes == 0 || !(0.0..=1.0).contains(&threshold) {
+            return None;
+        }
+        let mut best = (1, 1);
+        let mut best_error = f64::INFINITY;
+        for rows in 1..=hashes {
+            let bands = hashes / rows;
+            let error = (band_threshold(bands, rows) - threshold).abs();
+            if error < best_error {
+                best = (bands, rows);
+                best_error = error;
+            }
+        }
+
+        LshIndex::new(best.0, best.1)
+    }
+
+    /// Return the number of bands.
+    #[inline]
+    pub fn bands(&self) -> usize {
+        self.bands
+    }
+
+    /// Return the number of values in each band.
+    #[inline]
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Return the similarity `(1 / bands)^(1 / rows)` at which sets become
+    /// candidates with a probability of about one half.
+    #[inline]
+    pub fn threshold(&self) -> f64 {
+        band_threshold(self.bands, self.rows)
+    }
+
+    /// Return the number of signatures in the index.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Determine whether the index holds no signatures.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Compute the hash of the values of each band of a signature.
+    ///
+    /// The band hashes only key the tables of this index in memory, and are
+    /// never stored, so they may use the hasher of the standard library.
+    fn band_keys<'a>(&'a self, signature: &'a Signature) -> impl Iterator<Item = u64> + 'a {
+        signature.values[..self.bands * self.rows]
+            .chunks(self.rows)
+            .map(|band| {
+                let mut hasher = DefaultHasher::new();
+                band.hash(&mut hasher);
+
+                hasher.finish()
+            })
+    }
+
+    /// Insert a signature into the index, returning its id. The ids count up
+    /// from zero in the order of insertion.
+    ///
+    /// The function returns `None` if the signature has fewer than
+    /// `bands × rows` values.
+    pub fn insert(&mut self, signature: &Signature) -> Option<usize> {
+        if signature.len() < self.bands * self.rows {
+            return None;
+        }
+        let id = self.len;
+        let keys: Vec<u64> = self.band_keys(signature).collect();
+        for (table, key) in self.tables.iter_mut().zip(keys) {
+            table.entry(key).or_default().push(id);
+        }
+        self.len += 1;
+
+        Some(id)
+    }
+
+    /// Find the ids of the signatures in the index that agree with
+    /// `signature` on at least one band, in increasing order.
+    ///
+    /// The function returns no candidates if the signature has fewer than
+    /// `bands × rows` values.
+    pub fn candidates(&self, signature: &Signature) -> Vec<usize> {
+        if signature.len() < self.bands * self.rows {
+            return Vec::new();
+        }
+        let mut found: Vec<usize> = self.tables.iter()
+            .zip(self.band_keys(signature))
+            .filter_map(|(table, key)| table.get(&key))
+            .flatten()
+            .copied()
+            .collect();
+        found.sort_unstable();
+        found.dedup();
+
+        found
+    }
+
+    /// Find the pairs of ids `(i, j)` with `i < j` of the signatures in the
+    /// index that agree on at least one band, in increasing order.
+    pub fn candidate_pairs(&self) -> Vec<(usize, usize)> {
+        let mut pairs = Vec::new();
+        for bucket in self.tables.iter().flat_map(|table| table.values()) {
+            for (k, &i) in bucket.iter().enumerate() {
+                pairs.extend(bucket[k + 1..].iter().map(|&j| (i, j)));
+            }
+        }
+        pairs.sort_unstable();
+        pairs.dedup();
+
+        pairs
+    }
+
+    /// Find the candidate pairs whose exact similarity, computed by
+    /// `similarity`, is at least `threshold`. Each pair is returned with its
+    /// similarity, in increasing order of the ids.
+    ///
+    /// Use the intersection counts of the sets themselves to compute the
+    /// similarity, such as [`BitVec::jaccard_similarity`].
+    pub fn similar_pairs<F>(&self, threshold: f64, mut similarity: F) -> Vec<(usize, usize, f64)>
+    where
+        F: FnMut(usize, usize) -> f64
+    {
+        self.candidate_pairs()
+            .into_iter()
+            .map(|(i, j)| (i, j, similarity(i, j)))
+            .filter(|&(_, _, value)| value >= threshold)
+            .collect()
+    }
+}
+
+/// Compute the similarity at which sets become candidates with a probability
+/// of about one half.
+#[inline]
+fn band_threshold(bands: usize, rows: usize) -> f64 {
+    (1.0 / bands as f64).powf(1.0 / rows as f64)
+}
@@ -0,0 +1,341 @@
+use crate::truth_table::{
+    entries_mask,
+    VAR_MASKS,
+};
+use crate::{
+    BitSet,
+    TernaryBitSet,
+    TruthTable,
+};
+use std::cmp::Ordering;
+use std::fmt;
+
+
+/// Return the number of literals of a cube.
+#[inline]
+fn literals(cube: &TernaryBitSet) -> usize {
+    cube.care().count()
+}
+
+/// Compute the entries of the truth table of a cube over `vars` variables.
+fn product_table(cube: &TernaryBitSet, vars: usize) -> u128 {
+    cube.care().iter().fold(entries_mask(vars), |table, var| {
+        if cube.value().test(var) {
+            table & VAR_MASKS[var]
+        } else {
+            table & !VAR_MASKS[var]
+        }
+    })
+}
+
+/// Compare two cubes by their literals in increasing order of variable, with
+/// a variable coming before its negation.
+fn cmp_literals(left: &TernaryBitSet, right: &TernaryBitSet) -> Ordering {
+    let literals = |cube: &TernaryBitSet| {
+        cube.care().iter().map(|var| (var, !cube.value().test(var))).collect::<Vec<_>>()
+    };
+
+    literals(left).cmp(&literals(right))
+}
+
+/// Format a cube as a conjunction of literals, where `1` is the cube with no
+/// literals.
+fn fmt_product(cube: &TernaryBitSet, formatter: &mut fmt::Formatter) -> fmt::Result {
+    if cube.care().none() {
+        return write!(formatter, "1");
+    }
+    for (i, var) in cube.care().iter().enumerate() {
+        if i > 0 {
+            write!(formatter, " & ")?;
+        }
+        if cube.value().test(var) {
+            write!(formatter, "x{}", var)?;
+        } else {
+            write!(formatter, "!x{}", var)?;
+        }
+    }
+
+    Ok(())
+}
+
+
+/// The search strategy of the two-level minimizer.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum MinimizeMode {
+    /// Find a cover with the fewest cubes, and among those the fewest
+    /// literals. The search is exponential in the worst case.
+    Exact,
+    /// Select prime implicants greedily by the number of uncovered
+    /// assignments they cover, then drop redundant cubes.
+    Heuristic,
+}
+
+/// A sum of products of a Boolean function of up to seven variables, made of
+/// the cubes whose union is the set of assignments where the function is
+/// `true`.
+///
+/// Each cube is a product of literals stored as a [`TernaryBitSet`]: variable
+/// `i` appears in the product when digit `i` is not don't care, and the digit
+/// is then the polarity of the literal, `1` for the variable itself and `0`
+/// for its negation.
+///
+/// ## Example
+///
+/// ```
+/// # use bitset::{
+/// #     BitSet,
+/// #     Cover,
+/// #     MinimizeMode,
+/// # };
+/// #
+/// // The on-set of x0 & !x2 | x1 over three variables.
+/// let on_set = BitSet::from_u64(0b1100_1110);
+/// let cover = Cover::minimize(3, on_set, BitSet::new(), MinimizeMode::Exact).unwrap();
+///
+/// assert_eq!(cover.to_string(), "x0 & !x2 | x1");
+/// assert_eq!(cover.literals(), 3);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Cover {
+    vars: usize,
+    cubes: Vec<TernaryBitSet>,
+}
+
+impl Cover {
+    /// Minimize the function of `vars` variables that is `true` on the
+    /// assignments in `on_set`, and may take either value on the assignments in
+    /// `dont_care`.
+    ///
+    /// Bit `k` of each set stands for the assignment whose bit `i` is the value
+    /// of variable `i`. Assignments in both sets are treated as don't cares.
+    /// Every cube of the result is a prime implicant of the function. The
+    /// function returns `None` if `vars` exceeds [`TruthTable::MAX_VARS`].
+    pub fn minimize(vars: usize, on_set: BitSet, dont_care: BitSet, mode: MinimizeMode) -> Option<Cover> {
+        if vars > TruthTable::MAX_VARS {
+            return None;
+        }
+        let entries = entries_mask(vars);
+        let required = on_set.data & !dont_care.data & entries;
+        let allowed = (on_set.data | dont_care.data) & entries;
+        let primes: Vec<(TernaryBitSet, u128)> = prime_implicants(vars, allowed)
+            .into_iter()
+            .filter(|&(_, table)| table & required != 0)
+            .collect();
+        let mut selected = match mode {
+            MinimizeMode::Exact => exact_cover(&primes, required),
+            MinimizeMode::Heuristic => greedy_cover(&primes, required),
+        };
+        selected.sort_by(cmp_literals);
+
+        Some(Cover {
+            vars,
+            cubes: selected,
+        })
+    }
+
+    /// Minimize the function given by the truth table `function`.
+    #[inline]
+    pub fn from_truth_table(function: &TruthTable, mode: MinimizeMode) -> Cover {
+        Cover::minimize(function.vars(), function.table(), BitSet::new(), mode)
+            .expect("a truth table has at most the maximum number of variables")
+    }
+
+    /// Return the number of variables of the function.
+    #[inline]
+    pub fn vars(&self) -> usize {
+        self.vars
+    }
+
+    /// Return the cubes of the cover.
+    #[inline]
+    pub fn cubes(&self) -> &[TernaryBitSet] {
+        &self.cubes
+    }
+
+    /// Return the total number of literals over all cubes of the cover.
+    #[inline]
+    pub fn literals(&self) -> usize {
+        self.cubes.iter().map(literals).sum()
+    }
+
+    /// Evaluate the cover on an assignment, where bit `i` of `assignment` is
+    /// the value of variable `i`.
+    #[inline]
+    pub fn evaluate(&self, assignment: usize) -> bool {
+        let assignment = BitSet::from_u64(assignment as u64);
+
+        self.cubes.iter().any(|cube| cube.matches(&assignment))
+    }
+
+    /// Compute the truth table of the function the cover represents.
+    pub fn truth_table(&self) -> TruthTable {
+        let table = self.cubes.iter().fold(0, |table, cube| table | product_table(cube, self.vars));
+
+        TruthTable::new(self.vars, BitSet::from_u128(table))
+            .expect("a cover has at most the maximum number of variables")
+    }
+}
+
+impl fmt::Display for Cover {
+    /// Format the cover as a disjunction of cubes, where `0` is the empty
+    /// cover.
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        if self.cubes.is_empty() {
+            return write!(formatter, "0");
+        }
+        for (i, cube) in self.cubes.iter().enumerate() {
+            if i > 0 {
+                write!(formatter, " | ")?;
+            }
+            fmt_product(cube, formatter)?;
+        }
+
+        Ok(())
+    }
+}
+
+
+/// Find every prime implicant of the function of `vars` variables that is
+/// `true` on the entries of `allowed`, together with its truth table.
+///
+/// Every cube is one of the `3^vars` assignments of a polarity or absence to
+/// each variable, so the cubes are enumerated directly. A cube is prime when it
+/// is an implicant and dropping any one of its literals leaves the function.
+fn prime_implicants(vars: usize, allowed: u128) -> Vec<(TernaryBitSet, u128)> {
+    let mut primes = Vec::new();
+    let cubes = (0..vars).fold(1_usize, |count, _| count * 3);
+    for index in 0..cubes {
+        let mut value = BitSet::new();
+        let mut mask = BitSet::new();
+        let mut digits = index;
+        for var in 0..vars {
+            match digits % 3 {
+                1 => {
+                    mask.set(var, true);
+                }
+                2 => {
+                    mask.set(var, true);
+                    value.set(var, true);
+                }
+                _ => {}
+            }
+            digits /= 3;
+        }
+        let cube = TernaryBitSet::new(value, mask);
+        let table = product_table(&cube, vars);
+        if table & !allowed != 0 {
+            continue;
+        }
+        let is_prime = mask.iter().all(|var| {
+            let mut wider = mask;
+            wider.set(var, false);
+
+            product_table(&TernaryBitSet::new(value, wider), vars) & !allowed != 0
+        });
+        if is_prime {
+            primes.push((cube, table));
+        }
+    }
+
+    primes
+}
+
+/// The cost of a cover: the number of cubes, then the number of literals.
+fn cost(cubes: &[TernaryBitSet]) -> (usize, usize) {
+    (cubes.len(), cubes.iter().map(literals).sum())
+}
+
+/// Select the primes that alone cover an entry of `required`, returning them
+/// along with the entries that remain uncovered.
+fn essential_primes(primes: &[(TernaryBitSet, u128)], required: u128) -> (Vec<usize>, u128) {
+    let mut essential = Vec::new();
+    let mut uncovered = required;
+    let mut remaining = required;
+    while remaining != 0 {
+        let entry = 1 << remaining.trailing_zeros();
+        remaining &= remaining - 1;
+        let mut covering = primes.iter().enumerate().filter(|(_, (_, table))| table & entry != 0);
+        if let (Some((index, (_, table))), None) = (covering.next(), covering.next()) {
+            if !essential.contains(&index) {
+                essential.push(index);
+                uncovered &= !table;
+            }
+        }
+    }
+
+    (essential, uncovered)
+}
+
+/// Find a cover of `required` by the primes with the smallest cost, using
+/// branch and bound over the primes that cover the first uncovered entry.
+fn exact_cover(primes: &[(TernaryBitSet, u128)], required: u128) -> Vec<TernaryBitSet> {
+    let (essential, uncovered) = essential_primes(primes, required);
+    let mut chosen: Vec<TernaryBitSet> = essential.iter().map(|&index| primes[index].0).collect();
+    let mut best = None;
+    search_cover(primes, uncovered, &mut chosen, &mut best);
+
+    best.expect("the prime implicants cover the on-set")
+}
+
+fn search_cover(primes: &[(TernaryBitSet, u128)], uncovered: u128, chosen: &mut Vec<TernaryBitSet>, best: &mut Option<Vec<TernaryBitSet>>) {
+    if uncovered == 0 {
+        let improves = match best.as_ref() {
+            Some(best) => cost(chosen) < cost(best),
+            None => true,
+        };
+        if improves {
+            *best = Some(chosen.clone());
+        }
+        return;
+    }
+    if let Some(best) = best.as_ref() {
+        // At least one more cube is needed.
+        if chosen.len() + 1 > best.len() {
+            return;
+        }
+    }
+
+    // Branch on the entry covered by the fewest primes.
+    let entry = (0..128)
+        .map(|position| 1_u128 << position)
+        .filter(|&entry| uncovered & entry != 0)
+        .min_by_key(|&entry| primes.iter().filter(|(_, table)| table & entry != 0).count())
+        .expect("an entry is uncovered");
+    for &(cube, table) in primes.iter().filter(|(_, table)| table & entry != 0) {
+        chosen.push(cube);
+        search_cover(primes, uncovered & !table, chosen, best);
+        chosen.pop();
+    }
+}
+
+/// Cover `required` by the essential primes, then by the prime covering the
+/// most uncovered entries until none remain, and finally drop the cubes that
+/// the others already cover.
+fn greedy_cover(primes: &[(TernaryBitSet, u128)], required: u128) -> Vec<TernaryBitSet> {
+    let (essential, mut uncovered) = essential_primes(primes, required);
+    let mut chosen: Vec<(TernaryBitSet, u128)> = essential.iter().map(|&index| primes[index]).collect();
+    while uncovered != 0 {
+        let &(cube, table) = primes.iter()
+            .max_by_key(|&&(cube, table)| ((table & uncovered).count_ones(), usize::MAX - literals(&cube)))
+            .expect("the prime implicants cover the on-set");
+        chosen.push((cube, table));
+        uncovered &= !table;
+    }
+
+    // Drop redundant cubes, trying the ones with the most literals first.
+    chosen.sort_by_key(|&(cube, _)| usize::MAX - literals(&cube));
+    let mut i = 0;
+    while i < chosen.len() {
+        let others = chosen.iter()
+            .enumerate()
+            .filter(|&(j, _)| j != i)
+            .fold(0, |covered, (_, &(_, table))| covered | table);
+        if required & !others == 0 {
+            chosen.remove(i);
+        } else {
+            i += 1;
+        }
+    }
+
+    chosen.into_iter().map(|(cube, _)| cube).collect()
+}
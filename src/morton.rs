@@ -0,0 +1,484 @@
+use crate::BitSet;
+use std::ops::RangeInclusive;
+
+
+/// Compute the mask of the positions of the code that hold the bits of one
+/// coordinate.
+const fn dimension_mask(dims: usize, dim: usize, bits: usize) -> u128 {
+    let mut mask = 0;
+    let mut i = 0;
+    while i < bits {
+        mask |= 1 << (dims * i + dim);
+        i += 1;
+    }
+
+    mask
+}
+
+const MASKS_2D: [u128; 2] = [
+    dimension_mask(2, 0, 64),
+    dimension_mask(2, 1, 64),
+];
+
+const MASKS_3D: [u128; 3] = [
+    dimension_mask(3, 0, BitSet::MORTON_BITS_3D),
+    dimension_mask(3, 1, BitSet::MORTON_BITS_3D),
+    dimension_mask(3, 2, BitSet::MORTON_BITS_3D),
+];
+
+/// The largest coordinate of a three-dimensional code.
+const MAX_3D: u64 = (1 << BitSet::MORTON_BITS_3D) - 1;
+
+
+/// Add `delta` to the coordinate held in the positions `mask` of `code`,
+/// without decoding it.
+///
+/// The positions outside the mask are filled with ones before adding, so the
+/// carries pass over them. A carry or borrow out of the code is an overflow.
+fn add_dilated(code: u128, mask: u128, delta: i64, max: u64) -> Option<u128> {
+    let magnitude = delta.unsigned_abs();
+    if magnitude > max {
+        return None;
+    }
+    let dilated = BitSet::from_u64(magnitude).deposit(&BitSet::from_u128(mask)).data;
+    let coordinate = if delta >= 0 {
+        match (code | !mask).overflowing_add(dilated) {
+            (sum, false) => sum & mask,
+            (_, true) => return None,
+        }
+    } else {
+        match (code & mask).overflowing_sub(dilated) {
+            (difference, false) => difference & mask,
+            (_, true) => return None,
+        }
+    };
+
+    Some((code & !mask) | coordinate)
+}
+
+/// Determine whether every coordinate of `code` lies between the coordinates
+/// of `min` and `max`. Comparing the masked codes compares the coordinates,
+/// since interleaving keeps their order.
+fn in_box(code: u128, min: u128, max: u128, masks: &[u128]) -> bool {
+    masks.iter().all(|&mask| {
+        let coordinate = code & mask;
+        min & mask <= coordinate && coordinate <= max & mask
+    })
+}
+
+/// Find the smallest code of the box greater than `code` and the largest code
+/// of the box less than `code`, for a code outside the box, by the algorithm of
+/// Tropf and Herzog.
+fn bigmin_litmax(code: u128, min: u128, max: u128, masks: &[u128]) -> (Option<u128>, Option<u128>) {
+    let dims = masks.len();
+    let (mut min, mut max) = (min, max);
+    let mut bigmin = None;
+    let mut litmax = None;
+    let top = 128 - 128 % dims;
+    for bit in (0..top).rev() {
+        let position = 1_u128 << bit;
+        // The lower positions of the same coordinate.
+        let lower = masks[bit % dims] & (position - 1);
+        // Set the bit and clear the lower bits of its coordinate.
+        let load_high = |value: u128| (value | position) & !lower;
+        // Clear the bit and set the lower bits of its coordinate.
+        let load_low = |value: u128| (value & !position) | lower;
+        match (code & position != 0, min & position != 0, max & position != 0) {
+            (false, false, true) => {
+                bigmin = Some(load_high(min));
+                max = load_low(max);
+            }
+            (false, true, true) => {
+                bigmin = Some(min);
+                return (bigmin, litmax);
+            }
+            (true, false, false) => {
+                litmax = Some(max);
+                return (bigmin, litmax);
+            }
+            (true, false, true) => {
+                litmax = Some(load_low(max));
+                min = load_high(min);
+            }
+            _ => {}
+        }
+    }
+
+    (bigmin, litmax)
+}
+
+/// Find the smallest code of the box greater than `code`.
+fn next_in_box(code: u128, min: u128, max: u128, masks: &[u128]) -> Option<u128> {
+    let next = code.checked_add(1)?;
+    if in_box(next, min, max, masks) {
+        Some(next)
+    } else {
+        bigmin_litmax(next, min, max, masks).0
+    }
+}
+
+/// Find the largest code of the box less than `code`.
+fn prev_in_box(code: u128, min: u128, max: u128, masks: &[u128]) -> Option<u128> {
+    let prev = code.checked_sub(1)?;
+    if in_box(prev, min, max, masks) {
+        Some(prev)
+    } else {
+        bigmin_litmax(prev, min, max, masks).1
+    }
+}
+
+/// A block of the Z-order curve met while decomposing a box: either a run of
+/// consecutive codes inside the box, or an aligned block of the codes sharing
+/// the bits above `level` that is only partly inside the box.
+#[derive(Copy, Clone)]
+enum Block {
+    Inside { low: u128, high: u128 },
+    Partial { prefix: u128, level: usize },
+}
+
+/// Append a block to the blocks of a decomposition, merging runs of codes
+/// inside the box that touch.
+fn push_block(blocks: &mut Vec<Block>, block: Block) {
+    if let (Some(Block::Inside { high, .. }), Block::Inside { low: next, high: next_high }) = (blocks.last_mut(), block) {
+        if high.checked_add(1) == Some(next) {
+            *high = next_high;
+            return;
+        }
+    }
+    blocks.push(block);
+}
+
+/// Classify the aligned block of the codes sharing the bits of `prefix` above
+/// `level`, or return `None` if no code of the block lies in the box.
+fn classify(prefix: u128, level: usize, min: u128, max: u128, masks: &[u128]) -> Option<Block> {
+    let span = if level == 128 { u128::MAX } else { (1 << level) - 1 };
+    let (low, high) = (prefix, prefix | span);
+    if masks.iter().any(|&mask| high & mask < min & mask || low & mask > max & mask) {
+        None
+    } else if in_box(low, min, max, masks) && in_box(high, min, max, masks) {
+        Some(Block::Inside { low, high })
+    } else {
+        Some(Block::Partial { prefix, level })
+    }
+}
+
+/// Split the box into at most `max_ranges` intervals of codes covering it.
+///
+/// The blocks of the Z-order curve that are partly inside the box are split
+/// into their children one level at a time, as long as the intervals stay
+/// within the limit. Each pass over the blocks takes `O(max_ranges × 2^dims)`
+/// steps, and there is at most one pass per level of the curve.
+fn decompose(min: u128, max: u128, masks: &[u128], max_ranges: usize) -> Vec<RangeInclusive<BitSet>> {
+    if masks.iter().any(|&mask| min & mask > max & mask) {
+        return Vec::new();
+    }
+    let dims = masks.len();
+    let max_ranges = usize::max(max_ranges, 1);
+    let mut blocks: Vec<Block> = classify(0, 128 / dims * dims, min, max, masks).into_iter().collect();
+    let mut refined = true;
+    while refined {
+        refined = false;
+        let mut next = Vec::with_capacity(blocks.len());
+        for (i, &block) in blocks.iter().enumerate() {
+            let (prefix, level) = match block {
+                Block::Partial { prefix, level } => (prefix, level),
+                inside => {
+                    push_block(&mut next, inside);
+                    continue;
+                }
+            };
+            let (length, last) = (next.len(), next.last().copied());
+            let level = level - dims;
+            for child in 0..(1_u128 << dims) {
+                if let Some(child) = classify(prefix | (child << level), level, min, max, masks) {
+                    push_block(&mut next, child);
+                }
+            }
+            if next.len() + (blocks.len() - i - 1) <= max_ranges {
+                refined = true;
+            } else {
+                // Splitting the block would exceed the limit, so keep it whole.
+                next.truncate(length);
+                if let Some(last) = last {
+                    next[length - 1] = last;
+                }
+                next.push(block);
+            }
+        }
+        blocks = next;
+    }
+
+    // Every code of the box lies between `min` and `max`, so the blocks left
+    // partly inside the box are trimmed to that interval.
+    let mut ranges: Vec<RangeInclusive<BitSet>> = Vec::with_capacity(blocks.len());
+    for block in blocks {
+        let (low, high) = match block {
+            Block::Inside { low, high } => (low, high),
+            Block::Partial { prefix, level } => {
+                let span = if level == 128 { u128::MAX } else { (1 << level) - 1 };
+                (u128::max(prefix, min), u128::min(prefix | span, max))
+            }
+        };
+        match ranges.last_mut() {
+            Some(last) if last.end().data.checked_add(1) == Some(low) => {
+                *last = *last.start()..=BitSet::from_u128(high);
+            }
+            _ => ranges.push(BitSet::from_u128(low)..=BitSet::from_u128(high)),
+        }
+    }
+
+    ranges
+}
+
+/// Morton codes, also known as Z-order codes, stored in bit sets.
+///
+/// A Morton code interleaves the bits of the coordinates of a point, so that
+/// bit `i` of coordinate `d` lands at position `dims * i + d` of the code.
+/// Sorting points by their codes orders them along the Z-order curve, which
+/// keeps nearby points close together. Two-dimensional codes hold coordinates
+/// of up to 64 bits, and three-dimensional codes hold coordinates of up to
+/// [`BitSet::MORTON_BITS_3D`] bits.
+///
+/// The box queries find, for a code outside an axis-aligned box, the next code
+/// inside the box in Z-order (BIGMIN) and the previous one (LITMAX), following
+/// Tropf and Herzog. They let a scan over sorted codes skip the parts of the
+/// curve that leave the box.
+impl BitSet {
+    /// The number of bits of a coordinate of a three-dimensional Morton code.
+    pub const MORTON_BITS_3D: usize = 42;
+
+    /// Encode the point `(x, y)` as a two-dimensional Morton code.
+    ///
+    /// The bits of `x` land on the even positions of the code, and the bits of
+    /// `y` on the odd positions.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use bitset::{
+    /// #     BitSet,
+    /// # };
+    /// #
+    /// let code = BitSet::morton_encode2(0b11, 0b01);
+    ///
+    /// assert_eq!(code, BitSet::from_u64(0b0111));
+    /// assert_eq!(code.morton_decode2(), (0b11, 0b01));
+    /// ```
+    #[inline]
+    pub fn morton_encode2(x: u64, y: u64) -> BitSet {
+        let x = BitSet::from_u64(x).deposit(&BitSet::from_u128(MASKS_2D[0]));
+        let y = BitSet::from_u64(y).deposit(&BitSet::from_u128(MASKS_2D[1]));
+
+        x | y
+    }
+
+    /// Decode a two-dimensional Morton code into the point `(x, y)`.
+    #[inline]
+    pub fn morton_decode2(&self) -> (u64, u64) {
+        let x = self.extract(&BitSet::from_u128(MASKS_2D[0])).data as u64;
+        let y = self.extract(&BitSet::from_u128(MASKS_2D[1])).data as u64;
+
+        (x, y)
+    }
+
+    /// Encode the point `(x, y, z)` as a three-dimensional Morton code.
+    ///
+    /// The function returns `None` if a coordinate does not fit in
+    /// [`BitSet::MORTON_BITS_3D`] bits.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use bitset::{
+    /// #     BitSet,
+    /// # };
+    /// #
+    /// let code = BitSet::morton_encode3(1, 0, 1).unwrap();
+    ///
+    /// assert_eq!(code, BitSet::from_u64(0b101));
+    /// assert_eq!(code.morton_decode3(), (1, 0, 1));
+    /// assert!(BitSet::morton_encode3(1 << 42, 0, 0).is_none());
+    /// ```
+    #[inline]
+    pub fn morton_encode3(x: u64, y: u64, z: u64) -> Option<BitSet> {
+        if x > MAX_3D || y > MAX_3D || z > MAX_3D {
+            return None;
+        }
+        let x = BitSet::from_u64(x).deposit(&BitSet::from_u128(MASKS_3D[0]));
+        let y = BitSet::from_u64(y).deposit(&BitSet::from_u128(MASKS_3D[1]));
+        let z = BitSet::from_u64(z).deposit(&BitSet::from_u128(MASKS_3D[2]));
+
+        Some(x | y | z)
+    }
+
+    /// Decode a three-dimensional Morton code into the point `(x, y, z)`.
+    ///
+    /// The two highest bits of the code, which belong to no coordinate, are
+    /// ignored.
+    #[inline]
+    pub fn morton_decode3(&self) -> (u64, u64, u64) {
+        let x = self.extract(&BitSet::from_u128(MASKS_3D[0])).data as u64;
+        let y = self.extract(&BitSet::from_u128(MASKS_3D[1])).data as u64;
+        let z = self.extract(&BitSet::from_u128(MASKS_3D[2])).data as u64;
+
+        (x, y, z)
+    }
+
+    /// Compute the two-dimensional Morton code of the point `(x + dx, y + dy)`
+    /// directly from this code of the point `(x, y)`.
+    ///
+    /// The function returns `None` if a coordinate leaves the range of a
+    /// `u64`.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use bitset::{
+    /// #     BitSet,
+    /// # };
+    /// #
+    /// let code = BitSet::morton_encode2(7, 3);
+    ///
+    /// assert_eq!(code.morton_neighbor2(1, -1), Some(BitSet::morton_encode2(8, 2)));
+    /// assert_eq!(code.morton_neighbor2(-8, 0), None);
+    /// ```
+    pub fn morton_neighbor2(&self, dx: i64, dy: i64) -> Option<BitSet> {
+        let code = add_dilated(self.data, MASKS_2D[0], dx, u64::MAX)?;
+        let code = add_dilated(code, MASKS_2D[1], dy, u64::MAX)?;
+
+        Some(BitSet::from_u128(code))
+    }
+
+    /// Compute the three-dimensional Morton code of the point
+    /// `(x + dx, y + dy, z + dz)` directly from this code of the point
+    /// `(x, y, z)`.
+    ///
+    /// The function returns `None` if a coordinate leaves the range of
+    /// [`BitSet::MORTON_BITS_3D`] bits.
+    pub fn morton_neighbor3(&self, dx: i64, dy: i64, dz: i64) -> Option<BitSet> {
+        let code = self.data & (MASKS_3D[0] | MASKS_3D[1] | MASKS_3D[2]);
+        let code = add_dilated(code, MASKS_3D[0], dx, MAX_3D)?;
+        let code = add_dilated(code, MASKS_3D[1], dy, MAX_3D)?;
+        let code = add_dilated(code, MASKS_3D[2], dz, MAX_3D)?;
+
+        Some(BitSet::from_u128(code))
+    }
+
+    /// Determine whether the point of this two-dimensional Morton code lies in
+    /// the box spanned by the points of the codes `min` and `max`.
+    #[inline]
+    pub fn morton_in_box2(&self, min: &BitSet, max: &BitSet) -> bool {
+        in_box(self.data, min.data, max.data, &MASKS_2D)
+    }
+
+    /// Find the smallest two-dimensional Morton code greater than this code
+    /// whose point lies in the box spanned by the points of the codes `min` and
+    /// `max`. This is the BIGMIN of Tropf and Herzog.
+    ///
+    /// The function returns `None` if no such code exists.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use bitset::{
+    /// #     BitSet,
+    /// # };
+    /// #
+    /// // The box from (2, 2) to (3, 5) is not contiguous on the Z-order curve.
+    /// let min = BitSet::morton_encode2(2, 2);
+    /// let max = BitSet::morton_encode2(3, 5);
+    /// let next = BitSet::morton_encode2(3, 3).morton_bigmin2(&min, &max).unwrap();
+    ///
+    /// assert_eq!(next.morton_decode2(), (2, 4));
+    /// ```
+    #[inline]
+    pub fn morton_bigmin2(&self, min: &BitSet, max: &BitSet) -> Option<BitSet> {
+        next_in_box(self.data, min.data, max.data, &MASKS_2D).map(BitSet::from_u128)
+    }
+
+    /// Find the largest two-dimensional Morton code less than this code whose
+    /// point lies in the box spanned by the points of the codes `min` and
+    /// `max`. This is the LITMAX of Tropf and Herzog.
+    ///
+    /// The function returns `None` if no such code exists.
+    #[inline]
+    pub fn morton_litmax2(&self, min: &BitSet, max: &BitSet) -> Option<BitSet> {
+        prev_in_box(self.data, min.data, max.data, &MASKS_2D).map(BitSet::from_u128)
+    }
+
+    /// Split the box spanned by the points of the two-dimensional Morton codes
+    /// `min` and `max` into at most `max_ranges` intervals of consecutive
+    /// codes, in increasing order.
+    ///
+    /// The number of maximal intervals of codes of a box grows with the size of
+    /// the box, so the number of intervals is capped. With a large enough
+    /// `max_ranges`, the intervals are exactly the maximal intervals of codes
+    /// of the box. Otherwise some intervals also cover codes outside the box,
+    /// so a scan over them must still check its codes with
+    /// [`BitSet::morton_in_box2`]. A `max_ranges` of zero is treated as one.
+    /// The decomposition takes `O(max_ranges × 256)` steps.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use bitset::{
+    /// #     BitSet,
+    /// # };
+    /// #
+    /// let min = BitSet::morton_encode2(0, 0);
+    /// let max = BitSet::morton_encode2(2, 1);
+    /// let ranges = |max_ranges| {
+    ///     BitSet::morton_decompose2(&min, &max, max_ranges)
+    ///         .into_iter()
+    ///         .map(|range| (range.start().to_u64().unwrap(), range.end().to_u64().unwrap()))
+    ///         .collect::<Vec<_>>()
+    /// };
+    ///
+    /// assert_eq!(ranges(8), vec![(0, 4), (6, 6)]);
+    /// assert_eq!(ranges(1), vec![(0, 6)]);
+    /// ```
+    #[inline]
+    pub fn morton_decompose2(min: &BitSet, max: &BitSet, max_ranges: usize) -> Vec<RangeInclusive<BitSet>> {
+        decompose(min.data, max.data, &MASKS_2D, max_ranges)
+    }
+
+    /// Determine whether the point of this three-dimensional Morton code lies
+    /// in the box spanned by the points of the codes `min` and `max`.
+    #[inline]
+    pub fn morton_in_box3(&self, min: &BitSet, max: &BitSet) -> bool {
+        in_box(self.data, min.data, max.data, &MASKS_3D)
+    }
+
+    /// Find the smallest three-dimensional Morton code greater than this code
+    /// whose point lies in the box spanned by the points of the codes `min` and
+    /// `max`.
+    ///
+    /// The function returns `None` if no such code exists.
+    #[inline]
+    pub fn morton_bigmin3(&self, min: &BitSet, max: &BitSet) -> Option<BitSet> {
+        next_in_box(self.data, min.data, max.data, &MASKS_3D).map(BitSet::from_u128)
+    }
+
+    /// Find the largest three-dimensional Morton code less than this code
+    /// whose point lies in the box spanned by the points of the codes `min` and
+    /// `max`.
+    ///
+    /// The function returns `None` if no such code exists.
+    #[inline]
+    pub fn morton_litmax3(&self, min: &BitSet, max: &BitSet) -> Option<BitSet> {
+        prev_in_box(self.data, min.data, max.data, &MASKS_3D).map(BitSet::from_u128)
+    }
+
+    /// Split the box spanned by the points of the three-dimensional Morton
+    /// codes `min` and `max` into at most `max_ranges` intervals of consecutive
+    /// codes, in increasing order.
+    ///
+    /// As with [`BitSet::morton_decompose2`], the intervals are the maximal
+    /// ones with a large enough `max_ranges`, and otherwise some of them also
+    /// cover codes outside the box. The decomposition takes
+    /// `O(max_ranges × 336)` steps.
+    #[inline]
+    pub fn morton_decompose3(min: &BitSet, max: &BitSet, max_ranges: usize) -> Vec<RangeInclusive<BitSet>> {
+        decompose(min.data, max.data, &MASKS_3D, max_ranges)
+    }
+}
@@ -0,0 +1,312 @@
+use crate::BitSet;
+use std::ops;
+
+
+/// The number of bits in a bit set.
+const WORD_BITS: usize = 128;
+
+/// The number of stages of a Beneš network on 128 bits.
+const STAGES: usize = 13;
+
+/// The distance between the two bits that each stage of the network swaps.
+const DISTANCES: [usize; STAGES] = [64, 32, 16, 8, 4, 2, 1, 2, 4, 8, 16, 32, 64];
+
+/// Swap the bit at every position set in `mask` with the bit `distance`
+/// positions above it.
+#[inline]
+fn delta_swap(value: u128, mask: u128, distance: usize) -> u128 {
+    let swapped = ((value >> distance) ^ value) & mask;
+
+    value ^ swapped ^ (swapped << distance)
+}
+
+/// Set the switches of the Beneš network on the `destinations.len()` positions
+/// starting at `base`, which sends the bit at local position `i` to local
+/// position `destinations[i]`. This is the looping algorithm: the two inputs
+/// of a switch must enter different halves of the network, and the two
+/// outputs of a switch must leave different halves, so the choice for one
+/// input forces the choices along a cycle of switches.
+fn route(masks: &mut [u128; STAGES], base: usize, destinations: &[usize], depth: usize) {
+    let len = destinations.len();
+    let half = len / 2;
+    if len == 2 {
+        if destinations[0] == 1 {
+            masks[depth] |= 1 << base;
+        }
+        return;
+    }
+    let mut sources = vec![0; len];
+    for (source, &destination) in destinations.iter().enumerate() {
+        sources[destination] = source;
+    }
+    // The half of the network that each input enters.
+    let mut upper: Vec<Option<bool>> = vec![None; len];
+    for start in 0..half {
+        if upper[start].is_some() {
+            continue;
+        }
+        let mut input = start;
+        loop {
+            upper[input] = Some(false);
+            upper[input ^ half] = Some(true);
+            // The output sharing a switch with the destination of this input
+            // must come from the upper half, and so must the input sending to
+            // it, which places its partner in the lower half.
+            let partner = sources[destinations[input] ^ half];
+            if upper[partner].is_some() {
+                break;
+            }
+            input = partner ^ half;
+        }
+    }
+    let last = STAGES - 1 - depth;
+    let mut lower_destinations = vec![0; half];
+    let mut upper_destinations = vec![0; half];
+    for (input, &destination) in destinations.iter().enumerate() {
+        let is_upper = upper[input] == Some(true);
+        if input < half && is_upper {
+            masks[depth] |= 1 << (base + input);
+        }
+        if !is_upper {
+            if destination >= half {
+                masks[last] |= 1 << (base + destination - half);
+            }
+            lower_destinations[input % half] = destination % half;
+        } else {
+            upper_destinations[input % half] = destination % half;
+        }
+    }
+    route(masks, base, &lower_destinations, depth + 1);
+    route(masks, base + half, &upper_destinations, depth + 1);
+}
+
+
+/// A fixed permutation of the bits of a bit set, compiled into a Beneš
+/// network.
+///
+/// The network is a sequence of 13 stages, each of which swaps pairs of bits a
+/// fixed distance apart, so applying a permutation takes a constant number of
+/// word operations. Routing the network takes time linear in the number of
+/// bits and happens once, when the permutation is built.
+///
+/// ## Example
+///
+/// ```
+/// # use bitset::{
+/// #     BitPermutation,
+/// #     BitSet,
+/// # };
+/// #
+/// // Rotate every bit one position up.
+/// let destinations: Vec<usize> = (0..128).map(|i| (i + 1) % 128).collect();
+/// let rotate = BitPermutation::new(&destinations).unwrap();
+/// let bitset = BitSet::from_u128(0b1011 | (1 << 127));
+///
+/// assert_eq!(rotate.apply(&bitset), BitSet::from_u128(0b1_0111));
+/// assert_eq!(rotate.inverse().apply(&rotate.apply(&bitset)), bitset);
+/// ```
+#[derive(Clone, Debug)]
+pub struct BitPermutation {
+    destinations: Vec<usize>,
+    masks: [u128; STAGES],
+}
+
+impl BitPermutation {
+    /// Construct the permutation that sends bit `i` to position
+    /// `destinations[i]`.
+    ///
+    /// The function returns `None` unless `destinations` is a permutation of
+    /// `0..128`.
+    pub fn new(destinations: &[usize]) -> Option<BitPermutation> {
+        if destinations.len() != WORD_BITS {
+            return None;
+        }
+        let mut seen = BitSet::new();
+        for &destination in destinations.iter() {
+            if destination >= WORD_BITS || seen.test(destination) {
+                return None;
+            }
+            seen.set(destination, true);
+        }
+        let mut masks = [0; STAGES];
+        route(&mut masks, 0, destinations, 0);
+
+        Some(BitPermutation {
+            destinations: destinations.to_vec(),
+            masks,
+        })
+    }
+
+    /// Construct the permutation that leaves every bit in place.
+    #[inline]
+    pub fn identity() -> BitPermutation {
+        BitPermutation {
+            destinations: (0..WORD_BITS).collect(),
+            masks: [0; STAGES],
+        }
+    }
+
+    /// Return the position that bit `position` is sent to, or `None` if the
+    /// position is past the capacity of a bit set.
+    #[inline]
+    pub fn destination(&self, position: usize) -> Option<usize> {
+        self.destinations.get(position).copied()
+    }
+
+    /// Return the destination of each bit, indexed by the bit position.
+    #[inline]
+    pub fn destinations(&self) -> &[usize] {
+        &self.destinations
+    }
+
+    /// Apply the permutation to a bit set, sending each bit `i` to position
+    /// `self.destination(i)`.
+    #[inline]
+    pub fn apply(&self, bitset: &BitSet) -> BitSet {
+        let data = self.masks.iter()
+            .zip(DISTANCES.iter())
+            .filter(|(&mask, _)| mask != 0)
+            .fold(bitset.data, |data, (&mask, &distance)| delta_swap(data, mask, distance));
+
+        BitSet::from_u128(data)
+    }
+
+    /// Construct the permutation that undoes this one.
+    ///
+    /// Each stage of the network is its own inverse and the distances of the
+    /// stages are symmetric, so the inverse runs the same stages backwards.
+    pub fn inverse(&self) -> BitPermutation {
+        let mut destinations = vec![0; WORD_BITS];
+        for (source, &destination) in self.destinations.iter().enumerate() {
+            destinations[destination] = source;
+        }
+        let mut masks = self.masks;
+        masks.reverse();
+
+        BitPermutation {
+            destinations,
+            masks,
+        }
+    }
+
+    /// Construct the permutation that applies this one, and then `other`.
+    pub fn then(&self, other: &BitPermutation) -> BitPermutation {
+        let destinations: Vec<usize> = self.destinations.iter()
+            .map(|&destination| other.destinations[destination])
+            .collect();
+        let mut masks = [0; STAGES];
+        route(&mut masks, 0, &destinations, 0);
+
+        BitPermutation {
+            destinations,
+            masks,
+        }
+    }
+}
+
+impl Default for BitPermutation {
+    fn default() -> BitPermutation {
+        BitPermutation::identity()
+    }
+}
+
+/// Two permutations are equal when they send every bit to the same position,
+/// however their networks are routed.
+impl PartialEq for BitPermutation {
+    #[inline]
+    fn eq(&self, other: &BitPermutation) -> bool {
+        self.destinations == other.destinations
+    }
+}
+
+impl Eq for BitPermutation {}
+
+impl ops::Index<usize> for BitPermutation {
+    type Output = usize;
+
+    #[inline]
+    fn index(&self, position: usize) -> &Self::Output {
+        &self.destinations[position]
+    }
+}
+
+
+/// Common fixed permutations of the bits of a bit set.
+impl BitSet {
+    /// Reverse the order of the bits, sending bit `i` to position `127 - i`.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use bitset::{
+    /// #     BitSet,
+    /// # };
+    /// #
+    /// let bitset = BitSet::from_u64(0b0110);
+    ///
+    /// assert_eq!(bitset.reverse_bits(), BitSet::from_u128(0b0110 << 124));
+    /// ```
+    #[inline]
+    pub fn reverse_bits(&self) -> BitSet {
+        BitSet::from_u128(self.data.reverse_bits())
+    }
+
+    /// Reverse the order of the bytes, keeping the order of the bits within
+    /// each byte.
+    #[inline]
+    pub fn swap_bytes(&self) -> BitSet {
+        BitSet::from_u128(self.data.swap_bytes())
+    }
+
+    /// Exchange the two nibbles of every byte.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use bitset::{
+    /// #     BitSet,
+    /// # };
+    /// #
+    /// let bitset = BitSet::from_u64(0x12_AB);
+    ///
+    /// assert_eq!(bitset.swap_nibbles(), BitSet::from_u64(0x21_BA));
+    /// ```
+    #[inline]
+    pub fn swap_nibbles(&self) -> BitSet {
+        const LOW_NIBBLES: u128 = 0x0F0F_0F0F_0F0F_0F0F_0F0F_0F0F_0F0F_0F0F;
+        let data = ((self.data >> 4) & LOW_NIBBLES) | ((self.data & LOW_NIBBLES) << 4);
+
+        BitSet::from_u128(data)
+    }
+
+    /// Exchange the values of the bits at positions `i` and `j`.
+    ///
+    /// If either position is out of range, the function leaves the bit set
+    /// unchanged and returns `None`.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use bitset::{
+    /// #     BitSet,
+    /// # };
+    /// #
+    /// let mut bitset = BitSet::from_u64(0b0001);
+    /// bitset.swap_bits(0, 3);
+    ///
+    /// assert_eq!(bitset, BitSet::from_u64(0b1000));
+    /// assert_eq!(bitset.swap_bits(0, 128), None);
+    /// ```
+    #[inline]
+    pub fn swap_bits(&mut self, i: usize, j: usize) -> Option<()> {
+        if i >= WORD_BITS || j >= WORD_BITS {
+            return None;
+        }
+        // The two bits differ exactly when flipping both swaps them.
+        if ((self.data >> i) ^ (self.data >> j)) & 1 != 0 {
+            self.data ^= (1 << i) | (1 << j);
+        }
+
+        Some(())
+    }
+}
@@ -0,0 +1,416 @@
+use crate::BitSet;
+use std::fmt;
+use std::ops;
+
+
+/// Multiply two 128-bit polynomials without carries, returning the low and
+/// high halves of the 256-bit product.
+fn carryless_mul(left: u128, right: u128) -> (u128, u128) {
+    let mut low = 0;
+    let mut high = 0;
+    let mut remaining = right;
+    while remaining != 0 {
+        let shift = remaining.trailing_zeros();
+        low ^= left << shift;
+        if shift > 0 {
+            high ^= left >> (128 - shift);
+        }
+        remaining &= remaining - 1;
+    }
+
+    (low, high)
+}
+
+/// Compute the remainder of the 256-bit polynomial `(low, high)` divided by
+/// the non-zero polynomial `modulus`.
+fn wide_remainder(mut low: u128, mut high: u128, modulus: u128) -> u128 {
+    let degree = 127 - modulus.leading_zeros() as usize;
+    while high != 0 {
+        let top = 255 - high.leading_zeros() as usize;
+        let shift = top - degree;
+        if shift >= 128 {
+            high ^= modulus << (shift - 128);
+        } else {
+            low ^= modulus << shift;
+            if shift > 0 {
+                high ^= modulus >> (128 - shift);
+            }
+        }
+    }
+
+    narrow_remainder(low, modulus)
+}
+
+/// Compute the quotient and remainder of `dividend` divided by the non-zero
+/// polynomial `divisor`.
+fn narrow_div_rem(mut dividend: u128, divisor: u128) -> (u128, u128) {
+    let degree = 127 - divisor.leading_zeros();
+    let mut quotient = 0;
+    while dividend != 0 && 127 - dividend.leading_zeros() >= degree {
+        let shift = (127 - dividend.leading_zeros()) - degree;
+        quotient |= 1 << shift;
+        dividend ^= divisor << shift;
+    }
+
+    (quotient, dividend)
+}
+
+#[inline]
+fn narrow_remainder(dividend: u128, divisor: u128) -> u128 {
+    narrow_div_rem(dividend, divisor).1
+}
+
+
+/// A polynomial over GF(2) of degree at most 127, stored in a bit set.
+///
+/// Bit `i` of the bit set is the coefficient of `x^i`. Addition and
+/// subtraction of polynomials are both logical XOR of their coefficients.
+/// Multiplication is carry-less: the product of two polynomials of degree at
+/// most 127 has degree at most 254, so it is returned as two halves.
+///
+/// ## Example
+///
+/// ```
+/// # use bitset::{
+/// #     Gf2Poly,
+/// # };
+/// #
+/// // (x + 1) * (x + 1) = x^2 + 1 over GF(2).
+/// let poly = Gf2Poly::from_u128(0b11);
+/// let (low, high) = poly.widening_mul(&poly);
+///
+/// assert_eq!(low, Gf2Poly::from_u128(0b101));
+/// assert!(high.is_zero());
+/// assert_eq!(low.to_string(), "x^2 + 1");
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Gf2Poly {
+    coefficients: BitSet,
+}
+
+impl Gf2Poly {
+    /// Construct a polynomial whose coefficients are the bits of `coefficients`.
+    #[inline]
+    pub const fn new(coefficients: BitSet) -> Gf2Poly {
+        Gf2Poly {
+            coefficients,
+        }
+    }
+
+    /// Construct a polynomial whose coefficients are the bits of `value`.
+    #[inline]
+    pub const fn from_u128(value: u128) -> Gf2Poly {
+        Gf2Poly::new(BitSet::from_u128(value))
+    }
+
+    /// Construct the zero polynomial.
+    #[inline]
+    pub const fn zero() -> Gf2Poly {
+        Gf2Poly::from_u128(0)
+    }
+
+    /// Construct the constant polynomial `1`.
+    #[inline]
+    pub const fn one() -> Gf2Poly {
+        Gf2Poly::from_u128(1)
+    }
+
+    /// Return the coefficients of the polynomial as a bit set.
+    #[inline]
+    pub const fn coefficients(&self) -> BitSet {
+        self.coefficients
+    }
+
+    /// Return the coefficients of the polynomial as an integer.
+    #[inline]
+    pub const fn to_u128(&self) -> u128 {
+        self.coefficients.data
+    }
+
+    /// Determine whether the polynomial is the zero polynomial.
+    #[inline]
+    pub fn is_zero(&self) -> bool {
+        self.coefficients.none()
+    }
+
+    /// Return the degree of the polynomial.
+    ///
+    /// The function returns `None` for the zero polynomial.
+    #[inline]
+    pub fn degree(&self) -> Option<usize> {
+        if self.is_zero() {
+            None
+        } else {
+            Some(127 - self.to_u128().leading_zeros() as usize)
+        }
+    }
+
+    /// Multiply two polynomials without carries, returning the low and high
+    /// 128 coefficients of the 256-bit product.
+    #[inline]
+    pub fn widening_mul(&self, other: &Gf2Poly) -> (Gf2Poly, Gf2Poly) {
+        let (low, high) = carryless_mul(self.to_u128(), other.to_u128());
+
+        (Gf2Poly::from_u128(low), Gf2Poly::from_u128(high))
+    }
+
+    /// Multiply two polynomials.
+    ///
+    /// The function returns `None` if the degree of the product exceeds 127.
+    #[inline]
+    pub fn checked_mul(&self, other: &Gf2Poly) -> Option<Gf2Poly> {
+        let (low, high) = self.widening_mul(other);
+        if high.is_zero() {
+            Some(low)
+        } else {
+            None
+        }
+    }
+
+    /// Divide the polynomial by `divisor`, returning the quotient and the
+    /// remainder.
+    ///
+    /// The function returns `None` if `divisor` is the zero polynomial.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use bitset::{
+    /// #     Gf2Poly,
+    /// # };
+    /// #
+    /// // x^3 + x + 1 = (x^2 + x) * (x + 1) + 1.
+    /// let dividend = Gf2Poly::from_u128(0b1011);
+    /// let divisor = Gf2Poly::from_u128(0b11);
+    /// let (quotient, remainder) = dividend.div_rem(&divisor).unwrap();
+    ///
+    /// assert_eq!(quotient, Gf2Poly::from_u128(0b110));
+    /// assert_eq!(remainder, Gf2Poly::one());
+    /// ```
+    pub fn div_rem(&self, divisor: &Gf2Poly) -> Option<(Gf2Poly, Gf2Poly)> {
+        if divisor.is_zero() {
+            return None;
+        }
+        let (quotient, remainder) = narrow_div_rem(self.to_u128(), divisor.to_u128());
+
+        Some((Gf2Poly::from_u128(quotient), Gf2Poly::from_u128(remainder)))
+    }
+
+    /// Compute the greatest common divisor of two polynomials.
+    ///
+    /// The greatest common divisor of two zero polynomials is zero.
+    pub fn gcd(&self, other: &Gf2Poly) -> Gf2Poly {
+        let mut left = self.to_u128();
+        let mut right = other.to_u128();
+        while right != 0 {
+            let remainder = narrow_remainder(left, right);
+            left = right;
+            right = remainder;
+        }
+
+        Gf2Poly::from_u128(left)
+    }
+
+    /// Multiply two polynomials modulo the polynomial `modulus`.
+    ///
+    /// The function returns `None` if `modulus` is the zero polynomial.
+    pub fn mul_mod(&self, other: &Gf2Poly, modulus: &Gf2Poly) -> Option<Gf2Poly> {
+        if modulus.is_zero() {
+            return None;
+        }
+        let (low, high) = carryless_mul(self.to_u128(), other.to_u128());
+
+        Some(Gf2Poly::from_u128(wide_remainder(low, high, modulus.to_u128())))
+    }
+
+    /// Raise the polynomial to the power `exponent` modulo the polynomial
+    /// `modulus`.
+    ///
+    /// The function returns `None` if `modulus` is the zero polynomial.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use bitset::{
+    /// #     Gf2Poly,
+    /// # };
+    /// #
+    /// // In GF(2^3) = GF(2)[x] / (x^3 + x + 1), x has multiplicative order 7.
+    /// let x = Gf2Poly::from_u128(0b10);
+    /// let modulus = Gf2Poly::from_u128(0b1011);
+    ///
+    /// assert_eq!(x.pow_mod(7, &modulus), Some(Gf2Poly::one()));
+    /// assert_ne!(x.pow_mod(3, &modulus), Some(Gf2Poly::one()));
+    /// ```
+    pub fn pow_mod(&self, exponent: u128, modulus: &Gf2Poly) -> Option<Gf2Poly> {
+        if modulus.is_zero() {
+            return None;
+        }
+        let modulus = modulus.to_u128();
+        let mut base = narrow_remainder(self.to_u128(), modulus);
+        let mut result = narrow_remainder(1, modulus);
+        let mut exponent = exponent;
+        while exponent != 0 {
+            if exponent & 1 != 0 {
+                let (low, high) = carryless_mul(result, base);
+                result = wide_remainder(low, high, modulus);
+            }
+            let (low, high) = carryless_mul(base, base);
+            base = wide_remainder(low, high, modulus);
+            exponent >>= 1;
+        }
+
+        Some(Gf2Poly::from_u128(result))
+    }
+}
+
+impl From<BitSet> for Gf2Poly {
+    #[inline]
+    fn from(coefficients: BitSet) -> Gf2Poly {
+        Gf2Poly::new(coefficients)
+    }
+}
+
+impl fmt::Display for Gf2Poly {
+    /// Format the polynomial as a sum of powers of `x`, from the highest power
+    /// down.
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_zero() {
+            return write!(formatter, "0");
+        }
+        for (i, power) in self.coefficients.iter().rev().enumerate() {
+            if i > 0 {
+                write!(formatter, " + ")?;
+            }
+            match power {
+                0 => write!(formatter, "1")?,
+                1 => write!(formatter, "x")?,
+                _ => write!(formatter, "x^{}", power)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl ops::Add<Gf2Poly> for Gf2Poly {
+    type Output = Gf2Poly;
+
+    #[inline]
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn add(self, other: Gf2Poly) -> Self::Output {
+        // Coefficients are added modulo two.
+        Gf2Poly::new(self.coefficients ^ other.coefficients)
+    }
+}
+
+impl ops::Sub<Gf2Poly> for Gf2Poly {
+    type Output = Gf2Poly;
+
+    #[inline]
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn sub(self, other: Gf2Poly) -> Self::Output {
+        // Coefficients are added modulo two.
+        Gf2Poly::new(self.coefficients ^ other.coefficients)
+    }
+}
+
+
+/// The finite field GF(2^128), represented as the polynomials over GF(2) of
+/// degree at most 127 modulo a reduction polynomial `x^128 + r(x)`.
+///
+/// The field is described by the low part `r(x)` of its reduction polynomial,
+/// since the full polynomial does not fit into 128 bits. The reduction
+/// polynomial must be irreducible for the result to be a field; this is not
+/// checked.
+///
+/// Elements use the natural bit order, in which bit `i` is the coefficient of
+/// `x^i`. GHASH stores its elements with the bits of each 128-bit block
+/// reflected, so GHASH blocks must be converted with `u128::reverse_bits`
+/// before and after multiplying with [`Gf2Field128::ghash`].
+///
+/// ## Example
+///
+/// ```
+/// # use bitset::{
+/// #     Gf2Field128,
+/// #     Gf2Poly,
+/// # };
+/// #
+/// let field = Gf2Field128::ghash();
+/// let a = Gf2Poly::from_u128(0xDEAD_BEEF_0000_0000_0000_0000_1234_5678);
+///
+/// assert_eq!(field.mul(&a, &field.inverse(&a).unwrap()), Gf2Poly::one());
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Gf2Field128 {
+    reduction: Gf2Poly,
+}
+
+impl Gf2Field128 {
+    /// Construct the field with the reduction polynomial `x^128 + reduction`.
+    #[inline]
+    pub const fn new(reduction: Gf2Poly) -> Gf2Field128 {
+        Gf2Field128 {
+            reduction,
+        }
+    }
+
+    /// Construct the field used by GHASH, with the reduction polynomial
+    /// `x^128 + x^7 + x^2 + x + 1`.
+    #[inline]
+    pub const fn ghash() -> Gf2Field128 {
+        Gf2Field128::new(Gf2Poly::from_u128(0x87))
+    }
+
+    /// Return the low part `r(x)` of the reduction polynomial `x^128 + r(x)`.
+    #[inline]
+    pub const fn reduction(&self) -> Gf2Poly {
+        self.reduction
+    }
+
+    /// Multiply two field elements.
+    pub fn mul(&self, left: &Gf2Poly, right: &Gf2Poly) -> Gf2Poly {
+        let reduction = self.reduction.to_u128();
+        let (mut low, mut high) = carryless_mul(left.to_u128(), right.to_u128());
+        // Since x^128 = r(x) in the field, the high half folds back into the low
+        // half after multiplying it by r(x). The degree of the high half drops
+        // with every fold.
+        while high != 0 {
+            let (folded_low, folded_high) = carryless_mul(high, reduction);
+            low ^= folded_low;
+            high = folded_high;
+        }
+
+        Gf2Poly::from_u128(low)
+    }
+
+    /// Raise a field element to the power `exponent`.
+    pub fn pow(&self, base: &Gf2Poly, exponent: u128) -> Gf2Poly {
+        let mut result = Gf2Poly::one();
+        let mut base = *base;
+        let mut exponent = exponent;
+        while exponent != 0 {
+            if exponent & 1 != 0 {
+                result = self.mul(&result, &base);
+            }
+            base = self.mul(&base, &base);
+            exponent >>= 1;
+        }
+
+        result
+    }
+
+    /// Compute the multiplicative inverse of a field element.
+    ///
+    /// The function returns `None` for the zero element.
+    #[inline]
+    pub fn inverse(&self, element: &Gf2Poly) -> Option<Gf2Poly> {
+        if element.is_zero() {
+            None
+        } else {
+            // The multiplicative group has order 2^128 - 1.
+            Some(self.pow(element, u128::MAX - 1))
+        }
+    }
+}
@@ -0,0 +1,238 @@
+use crate::{
+    BitSet,
+    ComponentRegistry,
+};
+use std::fmt;
+
+
+/// A filter over archetype signatures made of three component masks.
+///
+/// A signature matches a query when it contains every component of the `all`
+/// mask, at least one component of the `any` mask, and no component of the
+/// `none` mask. An empty `any` mask places no restriction on a signature.
+///
+/// ## Example
+///
+/// ```
+/// # use bitset::{
+/// #     BitSet,
+/// #     Query,
+/// # };
+/// #
+/// let query = Query::new()
+///     .with_all(BitSet::from_u64(0b0011))
+///     .with_none(BitSet::from_u64(0b1000));
+///
+/// assert!(query.matches(&BitSet::from_u64(0b0111)));
+/// assert!(!query.matches(&BitSet::from_u64(0b0101)));
+/// assert!(!query.matches(&BitSet::from_u64(0b1011)));
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Query {
+    all: BitSet,
+    any: BitSet,
+    none: BitSet,
+}
+
+impl Query {
+    /// Construct a new query that matches every signature.
+    #[inline]
+    pub const fn new() -> Query {
+        Query {
+            all: BitSet::new(),
+            any: BitSet::new(),
+            none: BitSet::new(),
+        }
+    }
+
+    /// Construct a new query from its `all`, `any`, and `none` masks.
+    #[inline]
+    pub const fn from_masks(all: BitSet, any: BitSet, none: BitSet) -> Query {
+        Query {
+            all,
+            any,
+            none,
+        }
+    }
+
+    /// Add the components in `mask` to the components a signature must all
+    /// contain.
+    #[inline]
+    pub fn with_all(mut self, mask: BitSet) -> Query {
+        self.all |= mask;
+        self
+    }
+
+    /// Add the components in `mask` to the components a signature must contain
+    /// at least one of.
+    #[inline]
+    pub fn with_any(mut self, mask: BitSet) -> Query {
+        self.any |= mask;
+        self
+    }
+
+    /// Add the components in `mask` to the components a signature must not
+    /// contain.
+    #[inline]
+    pub fn with_none(mut self, mask: BitSet) -> Query {
+        self.none |= mask;
+        self
+    }
+
+    /// Return the mask of components a signature must all contain.
+    #[inline]
+    pub const fn all(&self) -> BitSet {
+        self.all
+    }
+
+    /// Return the mask of components a signature must contain at least one of.
+    #[inline]
+    pub const fn any(&self) -> BitSet {
+        self.any
+    }
+
+    /// Return the mask of components a signature must not contain.
+    #[inline]
+    pub const fn none(&self) -> BitSet {
+        self.none
+    }
+
+    /// Determine whether the archetype signature `signature` matches the
+    /// query.
+    ///
+    /// The three conditions are combined without short-circuiting, so the
+    /// test compiles down to straight-line code without branches.
+    #[inline]
+    pub fn matches(&self, signature: &BitSet) -> bool {
+        let data = signature.data;
+        let has_all = (data & self.all.data) == self.all.data;
+        let has_any = ((data & self.any.data) != 0) | (self.any.data == 0);
+        let has_none = (data & self.none.data) == 0;
+
+        has_all & has_any & has_none
+    }
+
+    /// Find the indices of the signatures in `signatures` that match the query.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use bitset::{
+    /// #     BitSet,
+    /// #     Query,
+    /// # };
+    /// #
+    /// let signatures = [
+    ///     BitSet::from_u64(0b001),
+    ///     BitSet::from_u64(0b011),
+    ///     BitSet::from_u64(0b110),
+    ///     BitSet::from_u64(0b111),
+    /// ];
+    /// let query = Query::new()
+    ///     .with_all(BitSet::from_u64(0b010))
+    ///     .with_any(BitSet::from_u64(0b101));
+    ///
+    /// assert_eq!(query.filter(&signatures), vec![1, 2, 3]);
+    /// ```
+    pub fn filter(&self, signatures: &[BitSet]) -> Vec<usize> {
+        let mut indices = Vec::new();
+        self.filter_into(signatures, &mut indices);
+
+        indices
+    }
+
+    /// Find the indices of the signatures in `signatures` that match the query,
+    /// appending them to `indices`.
+    ///
+    /// This lets a caller reuse the same buffer across many queries.
+    pub fn filter_into(&self, signatures: &[BitSet], indices: &mut Vec<usize>) {
+        indices.extend(
+            signatures.iter()
+                .enumerate()
+                .filter(|(_, signature)| self.matches(signature))
+                .map(|(index, _)| index)
+        );
+    }
+
+    /// Construct a value that displays the query with the component names
+    /// from the registry `registry`.
+    ///
+    /// Components that have no name in the registry are displayed by their
+    /// bit positions.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use bitset::{
+    /// #     ComponentRegistry,
+    /// #     Query,
+    /// # };
+    /// #
+    /// let mut registry = ComponentRegistry::new();
+    /// registry.register_name("position").unwrap();
+    /// registry.register_name("velocity").unwrap();
+    /// let query = Query::new()
+    ///     .with_all(registry.signature_of_names(vec!["position", "velocity"]).unwrap());
+    ///
+    /// assert_eq!(
+    ///     query.display(&registry).to_string(),
+    ///     "Query { all: [position, velocity], any: [], none: [] }"
+    /// );
+    /// ```
+    #[inline]
+    pub fn display<'a>(&'a self, registry: &'a ComponentRegistry) -> QueryDisplay<'a> {
+        QueryDisplay {
+            query: self,
+            registry: Some(registry),
+        }
+    }
+}
+
+impl fmt::Display for Query {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let display = QueryDisplay {
+            query: self,
+            registry: None,
+        };
+
+        display.fmt(formatter)
+    }
+}
+
+
+/// A helper for displaying a query with the component names from a registry.
+#[derive(Copy, Clone, Debug)]
+pub struct QueryDisplay<'a> {
+    query: &'a Query,
+    registry: Option<&'a ComponentRegistry>,
+}
+
+impl<'a> QueryDisplay<'a> {
+    fn fmt_mask(&self, formatter: &mut fmt::Formatter, mask: &BitSet) -> fmt::Result {
+        write!(formatter, "[")?;
+        for (i, position) in mask.iter().enumerate() {
+            if i > 0 {
+                write!(formatter, ", ")?;
+            }
+            match self.registry.and_then(|registry| registry.name_of(position)) {
+                Some(name) => write!(formatter, "{}", name)?,
+                None => write!(formatter, "{}", position)?,
+            }
+        }
+
+        write!(formatter, "]")
+    }
+}
+
+impl<'a> fmt::Display for QueryDisplay<'a> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "Query {{ all: ")?;
+        self.fmt_mask(formatter, &self.query.all)?;
+        write!(formatter, ", any: ")?;
+        self.fmt_mask(formatter, &self.query.any)?;
+        write!(formatter, ", none: ")?;
+        self.fmt_mask(formatter, &self.query.none)?;
+
+        write!(formatter, " }}")
+    }
+}
@@ -0,0 +1,476 @@
+use crate::BitSet;
+use std::any::{
+    self,
+    TypeId,
+};
+use std::collections::HashMap;
+use std::error;
+use std::fmt;
+use std::str;
+
+
+/// The errors that can occur when registering or looking up components.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RegistryError {
+    /// Every bit position of the registry is already assigned to a component.
+    CapacityExceeded {
+        /// The maximum number of components the registry can hold.
+        capacity: usize,
+    },
+    /// A component was looked up that was never registered.
+    NotRegistered {
+        /// The name of the missing component.
+        name: String,
+    },
+    /// A component name cannot be written to a registry snapshot, because it
+    /// is empty, contains a line break, or starts with whitespace.
+    InvalidName {
+        /// The rejected name.
+        name: String,
+    },
+    /// A component type was registered under a name that already belongs to a
+    /// different type. Type names are not guaranteed to be unique.
+    NameConflict {
+        /// The name shared by the two types.
+        name: String,
+    },
+    /// A line of a registry snapshot could not be parsed, or it assigns an
+    /// index or name that is already taken.
+    InvalidSnapshot {
+        /// The line number of the offending entry, starting from one.
+        line: usize,
+    },
+}
+
+impl fmt::Display for RegistryError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RegistryError::CapacityExceeded { capacity } => {
+                write!(formatter, "cannot register more than {} components", capacity)
+            }
+            RegistryError::NotRegistered { name } => {
+                write!(formatter, "component `{}` is not registered", name)
+            }
+            RegistryError::InvalidName { name } => {
+                write!(formatter, "component name {:?} cannot be stored in a snapshot", name)
+            }
+            RegistryError::NameConflict { name } => {
+                write!(formatter, "component `{}` is already registered for a different type", name)
+            }
+            RegistryError::InvalidSnapshot { line } => {
+                write!(formatter, "invalid registry snapshot entry on line {}", line)
+            }
+        }
+    }
+}
+
+impl error::Error for RegistryError {}
+
+
+/// A data type describing a collection of component types.
+///
+/// This trait is implemented for tuples of up to twelve types. A single
+/// component type is written as a one-element tuple `(T,)`.
+pub trait ComponentSet {
+    /// Return the type identifier and the type name of every component type in
+    /// the collection.
+    fn components() -> Vec<(TypeId, &'static str)>;
+}
+
+macro_rules! impl_component_set {
+    ($($type_param:ident),+) => {
+        impl<$($type_param),+> ComponentSet for ($($type_param,)+) where $($type_param: 'static),+ {
+            fn components() -> Vec<(TypeId, &'static str)> {
+                vec![$((TypeId::of::<$type_param>(), any::type_name::<$type_param>())),+]
+            }
+        }
+    }
+}
+
+impl_component_set!(A);
+impl_component_set!(A, B);
+impl_component_set!(A, B, C);
+impl_component_set!(A, B, C, D);
+impl_component_set!(A, B, C, D, E);
+impl_component_set!(A, B, C, D, E, F);
+impl_component_set!(A, B, C, D, E, F, G);
+impl_component_set!(A, B, C, D, E, F, G, H);
+impl_component_set!(A, B, C, D, E, F, G, H, I);
+impl_component_set!(A, B, C, D, E, F, G, H, I, J);
+impl_component_set!(A, B, C, D, E, F, G, H, I, J, K);
+impl_component_set!(A, B, C, D, E, F, G, H, I, J, K, L);
+
+
+/// A mapping from component types and component names to stable bit
+/// positions in a bit set.
+///
+/// Every component is identified by its name. Components registered by type
+/// use the type's name as reported by `std::any::type_name`, so a type and a
+/// name that agree share the same bit position. Since type identifiers are not
+/// stable between runs of a program, the registry persists through the names
+/// of its components with [`ComponentRegistry::snapshot`] and
+/// [`ComponentRegistry::from_snapshot`].
+#[derive(Clone, Debug, Default)]
+pub struct ComponentRegistry {
+    names: Vec<Option<String>>,
+    by_name: HashMap<String, usize>,
+    by_type: HashMap<TypeId, usize>,
+}
+
+impl ComponentRegistry {
+    /// Construct a new registry with no components.
+    #[inline]
+    pub fn new() -> ComponentRegistry {
+        ComponentRegistry {
+            names: Vec::new(),
+            by_name: HashMap::new(),
+            by_type: HashMap::new(),
+        }
+    }
+
+    /// Construct a registry that assigns the same bit positions to the same
+    /// component names as the registry the snapshot was taken from.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use bitset::{
+    /// #     ComponentRegistry,
+    /// #     RegistrySnapshot,
+    /// # };
+    /// #
+    /// struct Position;
+    /// struct Velocity;
+    ///
+    /// let mut registry = ComponentRegistry::new();
+    /// registry.register::<Position>().unwrap();
+    /// let velocity = registry.register::<Velocity>().unwrap();
+    /// let saved = registry.snapshot().to_string();
+    ///
+    /// // Later, perhaps in another run of the program.
+    /// let snapshot: RegistrySnapshot = saved.parse().unwrap();
+    /// let mut restored = ComponentRegistry::from_snapshot(&snapshot);
+    ///
+    /// assert_eq!(restored.register::<Velocity>(), Ok(velocity));
+    /// ```
+    pub fn from_snapshot(snapshot: &RegistrySnapshot) -> ComponentRegistry {
+        let mut registry = ComponentRegistry::new();
+        for (index, name) in snapshot.entries.iter() {
+            registry.assign(*index, name.clone());
+        }
+
+        registry
+    }
+
+    /// Return the maximum number of components that the registry can hold.
+    #[inline]
+    pub const fn capacity(&self) -> usize {
+        BitSet::new().capacity()
+    }
+
+    /// Return the number of registered components.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.by_name.len()
+    }
+
+    /// Determine whether the registry has no components.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.by_name.is_empty()
+    }
+
+    /// Register the component type `T`, returning its bit position.
+    ///
+    /// Registering a type more than once returns the same bit position. The
+    /// function returns an error if every bit position is already taken, or if
+    /// the name of the type already belongs to a different type.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use bitset::{
+    /// #     ComponentRegistry,
+    /// # };
+    /// #
+    /// struct Position;
+    /// struct Velocity;
+    ///
+    /// let mut registry = ComponentRegistry::new();
+    ///
+    /// assert_eq!(registry.register::<Position>(), Ok(0));
+    /// assert_eq!(registry.register::<Velocity>(), Ok(1));
+    /// assert_eq!(registry.register::<Position>(), Ok(0));
+    /// ```
+    pub fn register<T>(&mut self) -> Result<usize, RegistryError> where T: 'static {
+        self.register_type(TypeId::of::<T>(), any::type_name::<T>())
+    }
+
+    /// Register a component by name, returning its bit position.
+    ///
+    /// Registering a name more than once returns the same bit position. The
+    /// function returns an error if every bit position is already taken, or if
+    /// the name cannot be stored in a [`RegistrySnapshot`].
+    pub fn register_name(&mut self, name: &str) -> Result<usize, RegistryError> {
+        if let Some(&index) = self.by_name.get(name) {
+            return Ok(index);
+        }
+        if !is_valid_name(name) {
+            return Err(RegistryError::InvalidName { name: String::from(name) });
+        }
+
+        let index = self.next_free_index()?;
+        self.assign(index, String::from(name));
+
+        Ok(index)
+    }
+
+    /// Register every component type in the collection `S`, returning the
+    /// signature of the collection.
+    pub fn register_set<S>(&mut self) -> Result<BitSet, RegistryError> where S: ComponentSet {
+        let mut signature = BitSet::new();
+        for (type_id, name) in S::components() {
+            let index = self.register_type(type_id, name)?;
+            signature.set(index, true);
+        }
+
+        Ok(signature)
+    }
+
+    /// Return the bit position of the component type `T`, if it is
+    /// registered.
+    #[inline]
+    pub fn index_of<T>(&self) -> Option<usize> where T: 'static {
+        self.index_of_type_id(TypeId::of::<T>())
+            .or_else(|| self.index_of_name(any::type_name::<T>()))
+    }
+
+    /// Return the bit position of the component type with the type identifier
+    /// `type_id`, if it is registered.
+    #[inline]
+    pub fn index_of_type_id(&self, type_id: TypeId) -> Option<usize> {
+        self.by_type.get(&type_id).copied()
+    }
+
+    /// Return the bit position of the component named `name`, if it is
+    /// registered.
+    #[inline]
+    pub fn index_of_name(&self, name: &str) -> Option<usize> {
+        self.by_name.get(name).copied()
+    }
+
+    /// Return the name of the component at bit position `index`, if there is
+    /// one.
+    #[inline]
+    pub fn name_of(&self, index: usize) -> Option<&str> {
+        self.names.get(index).and_then(|name| name.as_deref())
+    }
+
+    /// Compute the bit set signature of a collection of component types.
+    ///
+    /// The function returns an error if any of the types is not registered.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use bitset::{
+    /// #     BitSet,
+    /// #     ComponentRegistry,
+    /// # };
+    /// #
+    /// struct Position;
+    /// struct Velocity;
+    /// struct Sprite;
+    ///
+    /// let mut registry = ComponentRegistry::new();
+    /// registry.register::<Position>().unwrap();
+    /// registry.register::<Velocity>().unwrap();
+    /// registry.register::<Sprite>().unwrap();
+    ///
+    /// let signature = registry.signature::<(Position, Sprite)>().unwrap();
+    ///
+    /// assert_eq!(signature, BitSet::from_u64(0b101));
+    /// assert!(registry.signature::<(String,)>().is_err());
+    /// ```
+    pub fn signature<S>(&self) -> Result<BitSet, RegistryError> where S: ComponentSet {
+        let mut signature = BitSet::new();
+        for (type_id, name) in S::components() {
+            let index = self.index_of_type_id(type_id)
+                .or_else(|| self.index_of_name(name))
+                .ok_or_else(|| RegistryError::NotRegistered { name: String::from(name) })?;
+            signature.set(index, true);
+        }
+
+        Ok(signature)
+    }
+
+    /// Compute the bit set signature of a list of component type identifiers.
+    ///
+    /// The function returns an error if any of the types is not registered.
+    pub fn signature_of_type_ids<I>(&self, type_ids: I) -> Result<BitSet, RegistryError>
+        where I: IntoIterator<Item = TypeId>
+    {
+        let mut signature = BitSet::new();
+        for type_id in type_ids {
+            let index = self.index_of_type_id(type_id)
+                .ok_or_else(|| RegistryError::NotRegistered { name: format!("{:?}", type_id) })?;
+            signature.set(index, true);
+        }
+
+        Ok(signature)
+    }
+
+    /// Compute the bit set signature of a list of component names.
+    ///
+    /// The function returns an error if any of the names is not registered.
+    pub fn signature_of_names<'a, I>(&self, names: I) -> Result<BitSet, RegistryError>
+        where I: IntoIterator<Item = &'a str>
+    {
+        let mut signature = BitSet::new();
+        for name in names {
+            let index = self.index_of_name(name)
+                .ok_or_else(|| RegistryError::NotRegistered { name: String::from(name) })?;
+            signature.set(index, true);
+        }
+
+        Ok(signature)
+    }
+
+    /// Take a snapshot of the names and bit positions of the registered
+    /// components.
+    pub fn snapshot(&self) -> RegistrySnapshot {
+        let entries = self.names.iter()
+            .enumerate()
+            .filter_map(|(index, name)| name.as_ref().map(|name| (index, name.clone())))
+            .collect();
+
+        RegistrySnapshot {
+            entries,
+        }
+    }
+
+    fn register_type(&mut self, type_id: TypeId, name: &str) -> Result<usize, RegistryError> {
+        if let Some(&index) = self.by_type.get(&type_id) {
+            return Ok(index);
+        }
+
+        // A name registered on its own, or restored from a snapshot, may be
+        // claimed by a type, but only by one type.
+        if let Some(&index) = self.by_name.get(name) {
+            if self.by_type.values().any(|&owned| owned == index) {
+                return Err(RegistryError::NameConflict { name: String::from(name) });
+            }
+        }
+        let index = self.register_name(name)?;
+        self.by_type.insert(type_id, index);
+
+        Ok(index)
+    }
+
+    fn next_free_index(&self) -> Result<usize, RegistryError> {
+        self.names.iter()
+            .position(|name| name.is_none())
+            .or(Some(self.names.len()))
+            .filter(|&index| index < self.capacity())
+            .ok_or(RegistryError::CapacityExceeded { capacity: self.capacity() })
+    }
+
+    fn assign(&mut self, index: usize, name: String) {
+        if self.names.len() <= index {
+            self.names.resize(index + 1, None);
+        }
+        self.by_name.insert(name.clone(), index);
+        self.names[index] = Some(name);
+    }
+}
+
+/// Determine whether a component name survives a round trip through a
+/// registry snapshot, which stores one name per line after a space.
+fn is_valid_name(name: &str) -> bool {
+    match name.chars().next() {
+        Some(first) => !first.is_whitespace() && !name.contains(&['\n', '\r'][..]),
+        None => false,
+    }
+}
+
+
+/// A serializable record of the bit positions assigned by a component
+/// registry.
+///
+/// A snapshot is written as one line per component, holding the bit position
+/// followed by a space and the name of the component. Component names must
+/// be nonempty, and must not contain line breaks or start with whitespace.
+///
+/// ## Example
+///
+/// ```
+/// # use bitset::{
+/// #     ComponentRegistry,
+/// #     RegistrySnapshot,
+/// # };
+/// #
+/// let mut registry = ComponentRegistry::new();
+/// registry.register_name("position").unwrap();
+/// registry.register_name("velocity").unwrap();
+///
+/// let snapshot = registry.snapshot();
+///
+/// assert_eq!(snapshot.to_string(), "0 position\n1 velocity\n");
+/// assert_eq!(snapshot.to_string().parse::<RegistrySnapshot>(), Ok(snapshot));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RegistrySnapshot {
+    entries: Vec<(usize, String)>,
+}
+
+impl RegistrySnapshot {
+    /// Return the bit positions and names of the components in the snapshot,
+    /// in order of increasing bit position.
+    #[inline]
+    pub fn entries(&self) -> &[(usize, String)] {
+        &self.entries
+    }
+}
+
+impl fmt::Display for RegistrySnapshot {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        for (index, name) in self.entries.iter() {
+            writeln!(formatter, "{} {}", index, name)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl str::FromStr for RegistrySnapshot {
+    type Err = RegistryError;
+
+    fn from_str(st: &str) -> Result<RegistrySnapshot, RegistryError> {
+        let capacity = BitSet::new().capacity();
+        let mut taken = BitSet::new();
+        let mut names = HashMap::new();
+        let mut entries = Vec::new();
+        for (number, line) in st.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let invalid = RegistryError::InvalidSnapshot { line: number + 1 };
+            let mut fields = line.splitn(2, ' ');
+            let index = fields.next()
+                .and_then(|index| index.parse::<usize>().ok())
+                .filter(|&index| index < capacity && !taken.test(index))
+                .ok_or_else(|| invalid.clone())?;
+            let name = fields.next()
+                .filter(|name| is_valid_name(name) && !names.contains_key(*name))
+                .ok_or(invalid)?;
+            taken.set(index, true);
+            names.insert(name, index);
+            entries.push((index, String::from(name)));
+        }
+        entries.sort();
+
+        Ok(RegistrySnapshot {
+            entries,
+        })
+    }
+}
@@ -0,0 +1,230 @@
+use crate::{
+    BitSet,
+    BitVec,
+};
+use std::convert::TryFrom;
+
+
+/// The bit storage of a replay window, where bit `i` records whether the
+/// sequence number `i` below the highest one seen has been received.
+pub trait WindowStorage: Sized {
+    /// Construct the storage for a window of `size` sequence numbers with no
+    /// bits set.
+    ///
+    /// The function returns `None` if `size` is zero or exceeds what the
+    /// storage can hold.
+    fn with_window(size: usize) -> Option<Self>;
+
+    /// Move every bit `delta` positions toward the older sequence numbers,
+    /// discarding the bits that fall out of the storage.
+    fn advance(&mut self, delta: usize);
+
+    /// Determine whether the bit at offset `offset` is set.
+    fn is_marked(&self, offset: usize) -> bool;
+
+    /// Set the bit at offset `offset`.
+    fn mark(&mut self, offset: usize);
+
+    /// Reset every bit of the storage.
+    fn clear_marks(&mut self);
+}
+
+impl WindowStorage for BitSet {
+    #[inline]
+    fn with_window(size: usize) -> Option<BitSet> {
+        if size > 0 && size <= BitSet::new().capacity() {
+            Some(BitSet::new())
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    fn advance(&mut self, delta: usize) {
+        *self <<= delta;
+    }
+
+    #[inline]
+    fn is_marked(&self, offset: usize) -> bool {
+        self.test(offset)
+    }
+
+    #[inline]
+    fn mark(&mut self, offset: usize) {
+        self.set(offset, true);
+    }
+
+    #[inline]
+    fn clear_marks(&mut self) {
+        self.reset_all();
+    }
+}
+
+impl WindowStorage for BitVec {
+    #[inline]
+    fn with_window(size: usize) -> Option<BitVec> {
+        if size > 0 {
+            Some(BitVec::zeros(size))
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    fn advance(&mut self, delta: usize) {
+        *self <<= delta;
+    }
+
+    #[inline]
+    fn is_marked(&self, offset: usize) -> bool {
+        self.test(offset)
+    }
+
+    #[inline]
+    fn mark(&mut self, offset: usize) {
+        self.set(offset, true);
+    }
+
+    #[inline]
+    fn clear_marks(&mut self) {
+        self.reset_all();
+    }
+}
+
+
+/// The outcome of checking a sequence number against a replay window.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ReplayStatus {
+    /// The sequence number has not been seen and lies within or above the
+    /// window.
+    Accepted,
+    /// The sequence number has already been seen.
+    Duplicate,
+    /// The sequence number lies below the window, so it can no longer be told
+    /// apart from a replay.
+    TooOld,
+}
+
+/// A sliding anti-replay window over sequence numbers, in the style of the
+/// windows of IPsec and DTLS.
+///
+/// The window tracks the highest sequence number seen and a bitmap of the
+/// `size` sequence numbers ending at it. A larger sequence number slides the
+/// window forward by the difference. Windows of up to 128 sequence numbers fit
+/// in a `BitSet`, and larger windows use a `BitVec`.
+///
+/// ## Example
+///
+/// ```
+/// # use bitset::{
+/// #     BitSet,
+/// #     ReplayStatus,
+/// #     ReplayWindow,
+/// # };
+/// #
+/// let mut window = ReplayWindow::<BitSet>::new(64).unwrap();
+///
+/// assert_eq!(window.check_and_update(100), ReplayStatus::Accepted);
+/// assert_eq!(window.check_and_update(98), ReplayStatus::Accepted);
+/// assert_eq!(window.check_and_update(100), ReplayStatus::Duplicate);
+/// assert_eq!(window.check_and_update(36), ReplayStatus::TooOld);
+/// assert_eq!(window.check_and_update(37), ReplayStatus::Accepted);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ReplayWindow<S = BitSet> {
+    highest: Option<u64>,
+    size: usize,
+    bits: S,
+}
+
+impl<S> ReplayWindow<S> where S: WindowStorage {
+    /// Construct an empty window of `size` sequence numbers.
+    ///
+    /// The function returns `None` if `size` is zero or does not fit in the
+    /// storage.
+    #[inline]
+    pub fn new(size: usize) -> Option<ReplayWindow<S>> {
+        S::with_window(size).map(|bits| ReplayWindow {
+            highest: None,
+            size,
+            bits,
+        })
+    }
+
+    /// Return the number of sequence numbers the window covers.
+    #[inline]
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Return the highest sequence number accepted so far.
+    #[inline]
+    pub fn highest(&self) -> Option<u64> {
+        self.highest
+    }
+
+    /// Check the sequence number `seq` against the window without recording
+    /// it.
+    ///
+    /// This allows a protocol to check a packet before authenticating it, and
+    /// to record it with [`ReplayWindow::update`] only after the
+    /// authentication succeeds.
+    pub fn check(&self, seq: u64) -> ReplayStatus {
+        let highest = match self.highest {
+            Some(highest) if seq <= highest => highest,
+            _ => return ReplayStatus::Accepted,
+        };
+        let offset = highest - seq;
+        if offset >= self.size as u64 {
+            ReplayStatus::TooOld
+        } else if self.bits.is_marked(offset as usize) {
+            ReplayStatus::Duplicate
+        } else {
+            ReplayStatus::Accepted
+        }
+    }
+
+    /// Record the sequence number `seq` as received, sliding the window
+    /// forward if it is the highest so far.
+    ///
+    /// Sequence numbers below the window are ignored.
+    pub fn update(&mut self, seq: u64) {
+        match self.highest {
+            Some(highest) if seq <= highest => {
+                let offset = highest - seq;
+                if offset < self.size as u64 {
+                    self.bits.mark(offset as usize);
+                }
+            }
+            Some(highest) => {
+                let delta = usize::try_from(seq - highest).unwrap_or(usize::MAX);
+                self.bits.advance(delta);
+                self.bits.mark(0);
+                self.highest = Some(seq);
+            }
+            None => {
+                self.bits.mark(0);
+                self.highest = Some(seq);
+            }
+        }
+    }
+
+    /// Check the sequence number `seq` against the window, and record it if
+    /// it is accepted.
+    #[inline]
+    pub fn check_and_update(&mut self, seq: u64) -> ReplayStatus {
+        let status = self.check(seq);
+        if status == ReplayStatus::Accepted {
+            self.update(seq);
+        }
+
+        status
+    }
+
+    /// Forget every sequence number seen so far.
+    #[inline]
+    pub fn reset(&mut self) {
+        self.highest = None;
+        self.bits.clear_marks();
+    }
+}
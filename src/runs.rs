@@ -0,0 +1,195 @@
+use crate::{
+    BitSet,
+    BitVec,
+};
+use std::iter::FusedIterator;
+use std::ops::Range;
+
+
+impl BitSet {
+    /// Construct an iterator over the maximal runs of consecutive bits set to
+    /// `true`, in increasing order.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use bitset::{
+    /// #     BitSet,
+    /// # };
+    /// #
+    /// let bitset = BitSet::from_u64(0b1110_0110_0001);
+    /// let runs: Vec<_> = bitset.runs().collect();
+    ///
+    /// assert_eq!(runs, vec![0..1, 5..7, 9..12]);
+    /// assert_eq!(bitset.run_count(), 3);
+    /// ```
+    #[inline]
+    pub fn runs(&self) -> Runs {
+        Runs {
+            data: self.data,
+        }
+    }
+
+    /// Construct an iterator over the maximal runs of consecutive bits set to
+    /// `false`, in increasing order.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use bitset::{
+    /// #     BitSet,
+    /// # };
+    /// #
+    /// let bitset = BitSet::from_u128(!0b1110_0110_0001);
+    /// let gaps: Vec<_> = bitset.zero_runs().collect();
+    ///
+    /// assert_eq!(gaps, vec![0..1, 5..7, 9..12]);
+    /// ```
+    #[inline]
+    pub fn zero_runs(&self) -> Runs {
+        Runs {
+            data: !self.data,
+        }
+    }
+
+    /// Count up the number of maximal runs of consecutive bits set to `true`.
+    #[inline]
+    pub fn run_count(&self) -> usize {
+        // A run starts at every set bit whose lower neighbour is clear.
+        (self.data & !(self.data << 1)).count_ones() as usize
+    }
+}
+
+/// An iterator over the maximal runs of consecutive set bits of a bit set.
+#[derive(Clone, Debug)]
+pub struct Runs {
+    data: u128,
+}
+
+impl Iterator for Runs {
+    type Item = Range<usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.data == 0 {
+            return None;
+        }
+        let start = self.data.trailing_zeros() as usize;
+        let len = (self.data >> start).trailing_ones() as usize;
+        let end = start + len;
+        // Clear the run, taking care not to shift by the full width.
+        self.data &= if end < 128 { u128::MAX << end } else { 0 };
+
+        Some(start..end)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let count = BitSet::from_u128(self.data).run_count();
+
+        (count, Some(count))
+    }
+}
+
+impl DoubleEndedIterator for Runs {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.data == 0 {
+            return None;
+        }
+        let end = 128 - self.data.leading_zeros() as usize;
+        let len = (self.data << (128 - end)).leading_ones() as usize;
+        let start = end - len;
+        // Clear the run, taking care not to shift by the full width.
+        self.data &= if start > 0 { u128::MAX >> (128 - start) } else { 0 };
+
+        Some(start..end)
+    }
+}
+
+impl ExactSizeIterator for Runs {}
+
+impl FusedIterator for Runs {}
+
+
+impl BitVec {
+    /// Construct an iterator over the maximal runs of consecutive bits set to
+    /// `true`, in increasing order.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use bitset::{
+    /// #     BitVec,
+    /// # };
+    /// #
+    /// let mut bitvec = BitVec::zeros(300);
+    /// bitvec.allocate_run(10);
+    /// bitvec.allocate_run_aligned(100, 128);
+    /// let runs: Vec<_> = bitvec.runs().collect();
+    ///
+    /// assert_eq!(runs, vec![0..10, 128..228]);
+    /// assert_eq!(bitvec.zero_runs().collect::<Vec<_>>(), vec![10..128, 228..300]);
+    /// ```
+    #[inline]
+    pub fn runs(&self) -> BitVecRuns<'_> {
+        BitVecRuns {
+            bitvec: self,
+            position: 0,
+            ones: true,
+        }
+    }
+
+    /// Construct an iterator over the maximal runs of consecutive bits set to
+    /// `false`, in increasing order.
+    #[inline]
+    pub fn zero_runs(&self) -> BitVecRuns<'_> {
+        BitVecRuns {
+            bitvec: self,
+            position: 0,
+            ones: false,
+        }
+    }
+
+    /// Count up the number of maximal runs of consecutive bits set to `true`.
+    pub fn run_count(&self) -> usize {
+        let mut carry = 0;
+        let mut count = 0;
+        for block in self.blocks.iter() {
+            // A run starts at every set bit whose lower neighbour is clear,
+            // where the lower neighbour of the first bit of a block is the last
+            // bit of the previous block.
+            count += (block.data & !((block.data << 1) | carry)).count_ones() as usize;
+            carry = block.data >> 127;
+        }
+
+        count
+    }
+}
+
+/// An iterator over the maximal runs of consecutive bits of a bit vector that
+/// have the same value.
+#[derive(Clone, Debug)]
+pub struct BitVecRuns<'a> {
+    bitvec: &'a BitVec,
+    position: usize,
+    ones: bool,
+}
+
+impl<'a> Iterator for BitVecRuns<'a> {
+    type Item = Range<usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (start, end) = if self.ones {
+            let start = self.bitvec.next_one(self.position)?;
+            (start, self.bitvec.next_zero(start))
+        } else {
+            let start = self.bitvec.next_zero(self.position)?;
+            (start, self.bitvec.next_one(start))
+        };
+        let end = end.unwrap_or(self.bitvec.len());
+        self.position = end;
+
+        Some(start..end)
+    }
+}
+
+impl<'a> FusedIterator for BitVecRuns<'a> {}
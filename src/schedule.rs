@@ -0,0 +1,367 @@
+use crate::BitSet;
+use std::collections::VecDeque;
+use std::error;
+use std::fmt;
+
+
+/// An identifier for a system registered with a scheduler.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SystemId(usize);
+
+impl SystemId {
+    /// Return the position of the system in declaration order.
+    #[inline]
+    pub const fn index(&self) -> usize {
+        self.0
+    }
+}
+
+
+/// The components a system reads and writes.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SystemAccess {
+    name: String,
+    reads: BitSet,
+    writes: BitSet,
+}
+
+impl SystemAccess {
+    /// Return the name of the system.
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Return the mask of components the system reads.
+    #[inline]
+    pub const fn reads(&self) -> BitSet {
+        self.reads
+    }
+
+    /// Return the mask of components the system writes.
+    #[inline]
+    pub const fn writes(&self) -> BitSet {
+        self.writes
+    }
+}
+
+
+/// The way in which two systems access the same components.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ConflictKind {
+    /// Both systems write the components.
+    WriteWrite,
+    /// The first system reads components that the second system writes.
+    ReadWrite,
+    /// The first system writes components that the second system reads.
+    WriteRead,
+}
+
+
+/// A pair of systems that cannot run at the same time because their component
+/// accesses collide.
+///
+/// The `first` system is always declared before the `second` system, and it
+/// runs in an earlier stage.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Conflict {
+    /// The system declared first.
+    pub first: SystemId,
+    /// The system declared second.
+    pub second: SystemId,
+    /// How the two systems access the colliding components.
+    pub kind: ConflictKind,
+    /// The components on which the systems collide.
+    pub components: BitSet,
+}
+
+impl fmt::Display for Conflict {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let first = self.first.index();
+        let second = self.second.index();
+        let components: Vec<usize> = self.components.iter().collect();
+        match self.kind {
+            ConflictKind::WriteWrite => write!(
+                formatter, "systems {} and {} both write components {:?}", first, second, components
+            ),
+            ConflictKind::ReadWrite => write!(
+                formatter, "system {} reads components {:?} that system {} writes", first, components, second
+            ),
+            ConflictKind::WriteRead => write!(
+                formatter, "system {} writes components {:?} that system {} reads", first, components, second
+            ),
+        }
+    }
+}
+
+
+/// The errors that can occur when building a schedule.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ScheduleError {
+    /// The explicit ordering constraints, together with the orderings implied
+    /// by conflicts, form a cycle.
+    Cycle {
+        /// The systems that could not be placed in any stage.
+        systems: Vec<SystemId>,
+    },
+}
+
+impl fmt::Display for ScheduleError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ScheduleError::Cycle { systems } => {
+                let indices: Vec<usize> = systems.iter().map(|system| system.index()).collect();
+                write!(formatter, "the ordering of systems {:?} contains a cycle", indices)
+            }
+        }
+    }
+}
+
+impl error::Error for ScheduleError {}
+
+
+/// A sequence of stages of systems. The systems inside a stage have no
+/// conflicting component accesses and can run in parallel.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Schedule {
+    stages: Vec<Vec<SystemId>>,
+    conflicts: Vec<Conflict>,
+}
+
+impl Schedule {
+    /// Return the stages of the schedule in execution order. The systems inside
+    /// each stage are in declaration order.
+    #[inline]
+    pub fn stages(&self) -> &[Vec<SystemId>] {
+        &self.stages
+    }
+
+    /// Return the conflicts that forced systems into different stages.
+    #[inline]
+    pub fn conflicts(&self) -> &[Conflict] {
+        &self.conflicts
+    }
+
+    /// Return the index of the stage the system `system` runs in.
+    pub fn stage_of(&self, system: SystemId) -> Option<usize> {
+        self.stages.iter().position(|stage| stage.contains(&system))
+    }
+}
+
+
+/// A scheduler that groups systems into stages that can run in parallel, based
+/// on the components each system reads and writes.
+///
+/// Two systems conflict when both write a component, or when one reads a
+/// component the other writes. Conflicting systems run in declaration order.
+/// Explicit ordering constraints can additionally force a system to run after
+/// another one.
+///
+/// ## Example
+///
+/// ```
+/// # use bitset::{
+/// #     BitSet,
+/// #     Scheduler,
+/// # };
+/// #
+/// let position = BitSet::from_u64(0b001);
+/// let velocity = BitSet::from_u64(0b010);
+/// let sprite = BitSet::from_u64(0b100);
+///
+/// let mut scheduler = Scheduler::new();
+/// let physics = scheduler.add_system("physics", velocity, position);
+/// let animate = scheduler.add_system("animate", BitSet::new(), sprite);
+/// let render = scheduler.add_system("render", position | sprite, BitSet::new());
+/// let schedule = scheduler.build().unwrap();
+///
+/// assert_eq!(schedule.stages(), &[vec![physics, animate], vec![render]]);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Scheduler {
+    systems: Vec<SystemAccess>,
+    orderings: Vec<(SystemId, SystemId)>,
+}
+
+impl Scheduler {
+    /// Construct a new scheduler with no systems.
+    #[inline]
+    pub fn new() -> Scheduler {
+        Scheduler {
+            systems: Vec::new(),
+            orderings: Vec::new(),
+        }
+    }
+
+    /// Declare a system with the component masks `reads` and `writes`.
+    pub fn add_system(&mut self, name: &str, reads: BitSet, writes: BitSet) -> SystemId {
+        let id = SystemId(self.systems.len());
+        self.systems.push(SystemAccess {
+            name: String::from(name),
+            reads,
+            writes,
+        });
+
+        id
+    }
+
+    /// Require the system `before` to run in an earlier stage than the system
+    /// `after`.
+    ///
+    /// The function returns `None` if either system was not declared with this
+    /// scheduler.
+    pub fn add_ordering(&mut self, before: SystemId, after: SystemId) -> Option<()> {
+        if before.index() < self.systems.len() && after.index() < self.systems.len() {
+            self.orderings.push((before, after));
+
+            Some(())
+        } else {
+            None
+        }
+    }
+
+    /// Return the number of declared systems.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.systems.len()
+    }
+
+    /// Determine whether no systems have been declared.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.systems.is_empty()
+    }
+
+    /// Return the component accesses of the system `system`.
+    #[inline]
+    pub fn system(&self, system: SystemId) -> Option<&SystemAccess> {
+        self.systems.get(system.index())
+    }
+
+    /// Find every conflict between a pair of declared systems.
+    ///
+    /// A pair of systems can conflict in more than one way, in which case each
+    /// kind of conflict is reported separately.
+    pub fn conflicts(&self) -> Vec<Conflict> {
+        let mut conflicts = Vec::new();
+        for (i, first) in self.systems.iter().enumerate() {
+            for (j, second) in self.systems.iter().enumerate().skip(i + 1) {
+                let collisions = [
+                    (ConflictKind::WriteWrite, first.writes & second.writes),
+                    (ConflictKind::ReadWrite, first.reads & second.writes),
+                    (ConflictKind::WriteRead, first.writes & second.reads),
+                ];
+                for (kind, components) in collisions.iter() {
+                    if components.any() {
+                        conflicts.push(Conflict {
+                            first: SystemId(i),
+                            second: SystemId(j),
+                            kind: *kind,
+                            components: *components,
+                        });
+                    }
+                }
+            }
+        }
+
+        conflicts
+    }
+
+    /// Describe the conflict `conflict` using the names of the systems
+    /// involved.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use bitset::{
+    /// #     BitSet,
+    /// #     Scheduler,
+    /// # };
+    /// #
+    /// let mut scheduler = Scheduler::new();
+    /// scheduler.add_system("physics", BitSet::new(), BitSet::from_u64(0b011));
+    /// scheduler.add_system("render", BitSet::from_u64(0b110), BitSet::new());
+    /// let conflicts = scheduler.conflicts();
+    ///
+    /// assert_eq!(
+    ///     scheduler.explain(&conflicts[0]),
+    ///     "`physics` writes components [1] that `render` reads"
+    /// );
+    /// ```
+    pub fn explain(&self, conflict: &Conflict) -> String {
+        let name_of = |system: SystemId| {
+            self.system(system).map_or_else(|| format!("{}", system.index()), |access| access.name.clone())
+        };
+        let first = name_of(conflict.first);
+        let second = name_of(conflict.second);
+        let components: Vec<usize> = conflict.components.iter().collect();
+        match conflict.kind {
+            ConflictKind::WriteWrite => {
+                format!("`{}` and `{}` both write components {:?}", first, second, components)
+            }
+            ConflictKind::ReadWrite => {
+                format!("`{}` reads components {:?} that `{}` writes", first, components, second)
+            }
+            ConflictKind::WriteRead => {
+                format!("`{}` writes components {:?} that `{}` reads", first, components, second)
+            }
+        }
+    }
+
+    /// Group the declared systems into stages.
+    ///
+    /// Every system is placed in the earliest stage that comes after all the
+    /// systems it conflicts with that were declared before it, and after all
+    /// the systems it is explicitly ordered after. The function returns an
+    /// error if the explicit orderings contradict each other or the
+    /// declaration order of conflicting systems.
+    pub fn build(&self) -> Result<Schedule, ScheduleError> {
+        let conflicts = self.conflicts();
+        let count = self.systems.len();
+        let mut successors = vec![Vec::new(); count];
+        let mut predecessor_count = vec![0; count];
+        let edges = conflicts.iter()
+            .map(|conflict| (conflict.first, conflict.second))
+            .chain(self.orderings.iter().copied());
+        for (before, after) in edges {
+            successors[before.index()].push(after.index());
+            predecessor_count[after.index()] += 1;
+        }
+
+        let mut stage_of = vec![0; count];
+        let mut ready: VecDeque<usize> = (0..count)
+            .filter(|&system| predecessor_count[system] == 0)
+            .collect();
+        let mut placed = 0;
+        while let Some(system) = ready.pop_front() {
+            placed += 1;
+            for &successor in successors[system].iter() {
+                stage_of[successor] = usize::max(stage_of[successor], stage_of[system] + 1);
+                predecessor_count[successor] -= 1;
+                if predecessor_count[successor] == 0 {
+                    ready.push_back(successor);
+                }
+            }
+        }
+
+        if placed < count {
+            let systems = (0..count)
+                .filter(|&system| predecessor_count[system] > 0)
+                .map(SystemId)
+                .collect();
+
+            return Err(ScheduleError::Cycle { systems });
+        }
+
+        let stage_count = stage_of.iter().max().map_or(0, |&stage| stage + 1);
+        let mut stages = vec![Vec::new(); stage_count];
+        for (system, &stage) in stage_of.iter().enumerate() {
+            stages[stage].push(SystemId(system));
+        }
+
+        Ok(Schedule {
+            stages,
+            conflicts,
+        })
+    }
+}
@@ -0,0 +1,209 @@
+use crate::{
+    BitSet,
+    BitVec,
+};
+
+
+/// Divide `numerator` by `denominator`, where two empty sets are identical and
+/// an empty set shares nothing with a nonempty one.
+#[inline]
+fn ratio(numerator: f64, denominator: f64, both_empty: bool) -> f64 {
+    if both_empty {
+        1.0
+    } else if denominator == 0.0 {
+        0.0
+    } else {
+        numerator / denominator
+    }
+}
+
+
+/// Population counts of set operations, and the similarity measures built on
+/// them.
+///
+/// The counts never construct the combined bit set. The similarity measures
+/// return `1.0` when both bit sets are empty and `0.0` when exactly one of
+/// them is, where the formula would divide by zero.
+impl BitSet {
+    /// Count up the number of bits set to `true` in both bit sets.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use bitset::{
+    /// #     BitSet,
+    /// # };
+    /// #
+    /// let a = BitSet::from_u64(0b1100);
+    /// let b = BitSet::from_u64(0b1010);
+    ///
+    /// assert_eq!(a.and_count(&b), 1);
+    /// assert_eq!(a.or_count(&b), 3);
+    /// assert_eq!(a.xor_count(&b), 2);
+    /// assert_eq!(a.andnot_count(&b), 1);
+    /// ```
+    #[inline]
+    pub fn and_count(&self, other: &BitSet) -> usize {
+        (self.data & other.data).count_ones() as usize
+    }
+
+    /// Count up the number of bits set to `true` in either bit set.
+    #[inline]
+    pub fn or_count(&self, other: &BitSet) -> usize {
+        (self.data | other.data).count_ones() as usize
+    }
+
+    /// Count up the number of bits set to `true` in exactly one of the bit
+    /// sets.
+    #[inline]
+    pub fn xor_count(&self, other: &BitSet) -> usize {
+        (self.data ^ other.data).count_ones() as usize
+    }
+
+    /// Count up the number of bits set to `true` in this bit set and `false`
+    /// in `other`.
+    #[inline]
+    pub fn andnot_count(&self, other: &BitSet) -> usize {
+        (self.data & !other.data).count_ones() as usize
+    }
+
+    /// Compute the Hamming distance between the bit sets, which is the number
+    /// of positions where they differ.
+    #[inline]
+    pub fn hamming_distance(&self, other: &BitSet) -> usize {
+        self.xor_count(other)
+    }
+
+    /// Compute the Jaccard similarity `|A ∩ B| / |A ∪ B|` of the bit sets.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use bitset::{
+    /// #     BitSet,
+    /// # };
+    /// #
+    /// let a = BitSet::from_u64(0b0111);
+    /// let b = BitSet::from_u64(0b1110);
+    ///
+    /// assert_eq!(a.jaccard_similarity(&b), 0.5);
+    /// assert_eq!(BitSet::new().jaccard_similarity(&BitSet::new()), 1.0);
+    /// ```
+    #[inline]
+    pub fn jaccard_similarity(&self, other: &BitSet) -> f64 {
+        let union = self.or_count(other);
+
+        ratio(self.and_count(other) as f64, union as f64, union == 0)
+    }
+
+    /// Compute the Sørensen–Dice coefficient `2 |A ∩ B| / (|A| + |B|)` of the
+    /// bit sets.
+    #[inline]
+    pub fn dice(&self, other: &BitSet) -> f64 {
+        let total = self.count() + other.count();
+
+        ratio(2.0 * self.and_count(other) as f64, total as f64, total == 0)
+    }
+
+    /// Compute the cosine similarity `|A ∩ B| / sqrt(|A| |B|)` of the bit sets,
+    /// taken as vectors of zeros and ones.
+    #[inline]
+    pub fn cosine(&self, other: &BitSet) -> f64 {
+        let (left, right) = (self.count(), other.count());
+        let norms = ((left * right) as f64).sqrt();
+
+        ratio(self.and_count(other) as f64, norms, left + right == 0)
+    }
+
+    /// Compute the overlap coefficient `|A ∩ B| / min(|A|, |B|)` of the bit
+    /// sets, which is `1.0` whenever one bit set is a nonempty subset of the
+    /// other.
+    #[inline]
+    pub fn overlap_coefficient(&self, other: &BitSet) -> f64 {
+        let (left, right) = (self.count(), other.count());
+        let smaller = usize::min(left, right);
+
+        ratio(self.and_count(other) as f64, smaller as f64, left + right == 0)
+    }
+}
+
+
+/// Population counts of set operations on bit vectors, and the Jaccard
+/// similarity built on them.
+///
+/// As with the logic operators, the shorter bit vector counts as padded with
+/// zeros to the length of the longer one.
+impl BitVec {
+    /// Count up the bits where `op` of the two blocks is set, a block at a
+    /// time.
+    #[inline]
+    fn count_with<F>(&self, other: &BitVec, op: F) -> usize where F: Fn(u128, u128) -> u128 {
+        let blocks = usize::max(self.blocks.len(), other.blocks.len());
+
+        (0..blocks)
+            .map(|i| {
+                let left = self.blocks.get(i).map_or(0, |block| block.data);
+                let right = other.blocks.get(i).map_or(0, |block| block.data);
+
+                op(left, right).count_ones() as usize
+            })
+            .sum()
+    }
+
+    /// Count up the number of bits set to `true` in both bit vectors.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use bitset::{
+    /// #     BitVec,
+    /// # };
+    /// #
+    /// let a: BitVec = (0..300).map(|i| i % 2 == 0).collect();
+    /// let b: BitVec = (0..300).map(|i| i % 3 == 0).collect();
+    ///
+    /// assert_eq!(a.and_count(&b), 50);
+    /// assert_eq!(a.or_count(&b), 200);
+    /// assert_eq!(a.jaccard_similarity(&b), 0.25);
+    /// ```
+    #[inline]
+    pub fn and_count(&self, other: &BitVec) -> usize {
+        self.count_with(other, |left, right| left & right)
+    }
+
+    /// Count up the number of bits set to `true` in either bit vector.
+    #[inline]
+    pub fn or_count(&self, other: &BitVec) -> usize {
+        self.count_with(other, |left, right| left | right)
+    }
+
+    /// Count up the number of bits set to `true` in exactly one of the bit
+    /// vectors.
+    #[inline]
+    pub fn xor_count(&self, other: &BitVec) -> usize {
+        self.count_with(other, |left, right| left ^ right)
+    }
+
+    /// Count up the number of bits set to `true` in this bit vector and
+    /// `false` in `other`.
+    #[inline]
+    pub fn andnot_count(&self, other: &BitVec) -> usize {
+        self.count_with(other, |left, right| left & !right)
+    }
+
+    /// Compute the Hamming distance between the bit vectors, which is the
+    /// number of positions where they differ.
+    #[inline]
+    pub fn hamming_distance(&self, other: &BitVec) -> usize {
+        self.xor_count(other)
+    }
+
+    /// Compute the Jaccard similarity `|A ∩ B| / |A ∪ B|` of the bit vectors,
+    /// which is `1.0` when neither has a bit set.
+    #[inline]
+    pub fn jaccard_similarity(&self, other: &BitVec) -> f64 {
+        let union = self.or_count(other);
+
+        ratio(self.and_count(other) as f64, union as f64, union == 0)
+    }
+}
@@ -0,0 +1,371 @@
+use crate::{
+    BitSet,
+    BitVec,
+};
+use std::ops::Range;
+
+
+/// The number of bits in a bit set, and in each block of a bit vector.
+const WORD_BITS: usize = 128;
+
+/// Return the mask of the bits in `start..end` of a word, where
+/// `start <= end <= 128`.
+#[inline]
+fn range_mask(start: usize, end: usize) -> u128 {
+    if start >= end {
+        0
+    } else if end - start == WORD_BITS {
+        u128::MAX
+    } else {
+        ((1 << (end - start)) - 1) << start
+    }
+}
+
+/// Return the mask of the positions of a word that are multiples of `align`.
+fn alignment_mask(align: usize) -> u128 {
+    (0..WORD_BITS).step_by(align).fold(0, |mask, position| mask | (1 << position))
+}
+
+/// Round `position` up to a multiple of `align`, returning `None` on overflow.
+#[inline]
+fn align_up(position: usize, align: usize) -> Option<usize> {
+    position.checked_add(align - 1).map(|position| position - position % align)
+}
+
+
+/// Slot allocation on bit sets, where each bit is a slot that is occupied when
+/// the bit is set to `true` and free otherwise.
+///
+/// The searches work on whole words. A free run of length `len` is found by
+/// folding the free mask onto itself with shifts that double in length, so a
+/// search takes a logarithmic number of word operations.
+impl BitSet {
+    /// Find the position of the first bit set to `true` at or after `from`.
+    #[inline]
+    pub fn next_one(&self, from: usize) -> Option<usize> {
+        if from >= WORD_BITS {
+            return None;
+        }
+        let word = self.data & (u128::MAX << from);
+        if word != 0 {
+            Some(word.trailing_zeros() as usize)
+        } else {
+            None
+        }
+    }
+
+    /// Find the position of the first bit set to `false` at or after `from`.
+    #[inline]
+    pub fn next_zero(&self, from: usize) -> Option<usize> {
+        (!self).next_one(from)
+    }
+
+    /// Compute the mask of the positions where a free run of `len` slots
+    /// starts.
+    fn free_run_starts(&self, len: usize) -> u128 {
+        let mut starts = !self.data;
+        let mut covered = 1;
+        while covered < len {
+            let step = usize::min(covered, len - covered);
+            starts &= starts >> step;
+            covered += step;
+        }
+
+        starts
+    }
+
+    /// Find the first position of a run of `len` free slots.
+    ///
+    /// A run of zero slots is found at position zero. The function returns
+    /// `None` if no such run exists.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use bitset::{
+    /// #     BitSet,
+    /// # };
+    /// #
+    /// let calendar = BitSet::from_u64(0b1100_0110_0001);
+    ///
+    /// assert_eq!(calendar.find_free_run(3), Some(1));
+    /// assert_eq!(calendar.find_free_run(5), Some(12));
+    /// assert_eq!(calendar.find_free_run(129), None);
+    /// ```
+    pub fn find_free_run(&self, len: usize) -> Option<usize> {
+        if len == 0 {
+            return Some(0);
+        }
+        if len > WORD_BITS {
+            return None;
+        }
+        let starts = self.free_run_starts(len);
+        if starts != 0 {
+            Some(starts.trailing_zeros() as usize)
+        } else {
+            None
+        }
+    }
+
+    /// Find the first position that is a multiple of `align` and starts a run
+    /// of `len` free slots.
+    ///
+    /// The function returns `None` if `align` is zero or no such run exists.
+    pub fn find_free_run_aligned(&self, len: usize, align: usize) -> Option<usize> {
+        if align == 0 || len > WORD_BITS {
+            return None;
+        }
+        if len == 0 {
+            return Some(0);
+        }
+        let starts = self.free_run_starts(len) & alignment_mask(align);
+        if starts != 0 {
+            Some(starts.trailing_zeros() as usize)
+        } else {
+            None
+        }
+    }
+
+    /// Find the longest run of free slots, preferring the first one among
+    /// runs of equal length.
+    ///
+    /// The function returns `None` if every slot is occupied.
+    pub fn longest_free_run(&self) -> Option<Range<usize>> {
+        self.zero_runs().fold(None, |longest: Option<Range<usize>>, run| {
+            match longest {
+                Some(longest) if longest.len() >= run.len() => Some(longest),
+                _ => Some(run),
+            }
+        })
+    }
+
+    /// Determine whether the `len` slots starting at `start` are all free.
+    ///
+    /// Slots past the capacity of the bit set count as occupied.
+    #[inline]
+    pub fn is_free_run(&self, start: usize, len: usize) -> bool {
+        match start.checked_add(len) {
+            Some(end) if end <= WORD_BITS => self.data & range_mask(start, end) == 0,
+            _ => false,
+        }
+    }
+
+    /// Occupy the first run of `len` free slots, returning its position.
+    ///
+    /// The function returns `None`, and leaves the bit set unchanged, if no
+    /// such run exists.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use bitset::{
+    /// #     BitSet,
+    /// # };
+    /// #
+    /// let mut calendar = BitSet::new();
+    ///
+    /// assert_eq!(calendar.allocate_run(3), Some(0));
+    /// assert_eq!(calendar.allocate_run(2), Some(3));
+    /// calendar.release_run(0, 3);
+    /// assert_eq!(calendar.allocate_run(1), Some(0));
+    /// ```
+    #[inline]
+    pub fn allocate_run(&mut self, len: usize) -> Option<usize> {
+        let start = self.find_free_run(len)?;
+        self.data |= range_mask(start, start + len);
+
+        Some(start)
+    }
+
+    /// Occupy the first run of `len` free slots starting at a multiple of
+    /// `align`, returning its position.
+    #[inline]
+    pub fn allocate_run_aligned(&mut self, len: usize, align: usize) -> Option<usize> {
+        let start = self.find_free_run_aligned(len, align)?;
+        self.data |= range_mask(start, start + len);
+
+        Some(start)
+    }
+
+    /// Free the `len` slots starting at `start`.
+    ///
+    /// The function returns `None`, and leaves the bit set unchanged, if the
+    /// run extends past the capacity of the bit set.
+    #[inline]
+    pub fn release_run(&mut self, start: usize, len: usize) -> Option<()> {
+        let end = start.checked_add(len).filter(|&end| end <= WORD_BITS)?;
+        self.data &= !range_mask(start, end);
+
+        Some(())
+    }
+}
+
+
+/// Slot allocation on bit vectors, where each bit is a slot that is occupied
+/// when the bit is set to `true` and free otherwise.
+///
+/// The searches skip from one run to the next a block at a time, so their cost
+/// depends on the number of runs rather than the number of slots.
+impl BitVec {
+    /// Find the position of the first bit set to `true` at or after `from`.
+    pub fn next_one(&self, from: usize) -> Option<usize> {
+        if from >= self.length {
+            return None;
+        }
+        let first = from / WORD_BITS;
+        let mut word = self.blocks[first].data & (u128::MAX << (from % WORD_BITS));
+        for block in first.. {
+            if word != 0 {
+                return Some(block * WORD_BITS + word.trailing_zeros() as usize);
+            }
+            word = self.blocks.get(block + 1)?.data;
+        }
+
+        None
+    }
+
+    /// Find the position of the first bit set to `false` at or after `from`.
+    pub fn next_zero(&self, from: usize) -> Option<usize> {
+        if from >= self.length {
+            return None;
+        }
+        let first = from / WORD_BITS;
+        let mut word = !self.blocks[first].data & (u128::MAX << (from % WORD_BITS));
+        for block in first.. {
+            if word != 0 {
+                let position = block * WORD_BITS + word.trailing_zeros() as usize;
+                // The bits past the length are zero, so they must be excluded.
+                return if position < self.length { Some(position) } else { None };
+            }
+            word = !self.blocks.get(block + 1)?.data;
+        }
+
+        None
+    }
+
+    /// Find the first position of a run of `len` free slots.
+    ///
+    /// A run of zero slots is found at position zero. The function returns
+    /// `None` if no such run exists.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use bitset::{
+    /// #     BitVec,
+    /// # };
+    /// #
+    /// let mut pages = BitVec::zeros(1000);
+    /// pages.allocate_run(300);
+    /// pages.release_run(100, 50);
+    ///
+    /// assert_eq!(pages.find_free_run(40), Some(100));
+    /// assert_eq!(pages.find_free_run(60), Some(300));
+    /// ```
+    pub fn find_free_run(&self, len: usize) -> Option<usize> {
+        if len == 0 {
+            return Some(0);
+        }
+        self.zero_runs()
+            .find(|run| run.len() >= len)
+            .map(|run| run.start)
+    }
+
+    /// Find the first position that is a multiple of `align` and starts a run
+    /// of `len` free slots.
+    ///
+    /// The function returns `None` if `align` is zero or no such run exists.
+    pub fn find_free_run_aligned(&self, len: usize, align: usize) -> Option<usize> {
+        if align == 0 {
+            return None;
+        }
+        if len == 0 {
+            return Some(0);
+        }
+        self.zero_runs().find_map(|run| {
+            let start = align_up(run.start, align)?;
+            match start.checked_add(len) {
+                Some(end) if end <= run.end => Some(start),
+                _ => None,
+            }
+        })
+    }
+
+    /// Find the longest run of free slots, preferring the first one among
+    /// runs of equal length.
+    ///
+    /// The function returns `None` if every slot is occupied.
+    pub fn longest_free_run(&self) -> Option<Range<usize>> {
+        self.zero_runs().fold(None, |longest: Option<Range<usize>>, run| {
+            match longest {
+                Some(longest) if longest.len() >= run.len() => Some(longest),
+                _ => Some(run),
+            }
+        })
+    }
+
+    /// Determine whether the `len` slots starting at `start` are all free.
+    ///
+    /// Slots past the length of the bit vector count as occupied.
+    #[inline]
+    pub fn is_free_run(&self, start: usize, len: usize) -> bool {
+        match start.checked_add(len) {
+            Some(end) if end <= self.length => {
+                len == 0 || !matches!(self.next_one(start), Some(one) if one < end)
+            }
+            _ => false,
+        }
+    }
+
+    /// Occupy the first run of `len` free slots, returning its position.
+    ///
+    /// The function returns `None`, and leaves the bit vector unchanged, if no
+    /// such run exists.
+    #[inline]
+    pub fn allocate_run(&mut self, len: usize) -> Option<usize> {
+        let start = self.find_free_run(len)?;
+        self.fill_range(start, start + len, true);
+
+        Some(start)
+    }
+
+    /// Occupy the first run of `len` free slots starting at a multiple of
+    /// `align`, returning its position.
+    #[inline]
+    pub fn allocate_run_aligned(&mut self, len: usize, align: usize) -> Option<usize> {
+        let start = self.find_free_run_aligned(len, align)?;
+        self.fill_range(start, start + len, true);
+
+        Some(start)
+    }
+
+    /// Free the `len` slots starting at `start`.
+    ///
+    /// The function returns `None`, and leaves the bit vector unchanged, if
+    /// the run extends past the length of the bit vector.
+    #[inline]
+    pub fn release_run(&mut self, start: usize, len: usize) -> Option<()> {
+        let end = start.checked_add(len).filter(|&end| end <= self.length)?;
+        self.fill_range(start, end, false);
+
+        Some(())
+    }
+
+    /// Set the bits in `start..end` to `value` a block at a time, where
+    /// `end` is at most the length of the bit vector.
+    pub(crate) fn fill_range(&mut self, start: usize, end: usize, value: bool) {
+        let mut position = start;
+        while position < end {
+            let block = position / WORD_BITS;
+            let offset = position % WORD_BITS;
+            let stop = usize::min(end - block * WORD_BITS, WORD_BITS);
+            let mask = range_mask(offset, stop);
+            if value {
+                self.blocks[block].data |= mask;
+            } else {
+                self.blocks[block].data &= !mask;
+            }
+            position = block * WORD_BITS + stop;
+        }
+    }
+}
@@ -0,0 +1,433 @@
+use crate::{
+    BitSet,
+    BitVec,
+};
+use std::iter::FusedIterator;
+
+
+/// Return the next larger integer with the same number of set bits as `x`, or
+/// `None` if there is none. This is Gosper's hack.
+#[inline]
+fn next_same_popcount(x: u128) -> Option<u128> {
+    if x == 0 {
+        return None;
+    }
+    let lowest = x & x.wrapping_neg();
+    let ripple = x.checked_add(lowest)?;
+    // The ones of the lowest run that did not move to the carry position are
+    // moved down to the bottom of the word.
+    let ones = ((ripple ^ x) >> 2) / lowest;
+
+    Some(ripple | ones)
+}
+
+/// Return the next smaller integer with the same number of set bits as `x`,
+/// or `None` if there is none.
+#[inline]
+fn prev_same_popcount(x: u128) -> Option<u128> {
+    // Complementing reverses the order of the integers and keeps the number
+    // of set bits equal among them.
+    next_same_popcount(!x).map(|complement| !complement)
+}
+
+
+impl BitSet {
+    /// Construct an iterator over every subset of the bit set, in decreasing
+    /// numeric order from the bit set itself down to the empty set.
+    ///
+    /// The iterator visits `2^k` subsets for a bit set of `k` bits, and runs
+    /// in increasing order when reversed.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use bitset::{
+    /// #     BitSet,
+    /// # };
+    /// #
+    /// let bitset = BitSet::from_u64(0b1010);
+    /// let subsets: Vec<u64> = bitset.subsets()
+    ///     .map(|subset| subset.to_u64().unwrap())
+    ///     .collect();
+    ///
+    /// assert_eq!(subsets, vec![0b1010, 0b1000, 0b0010, 0b0000]);
+    /// ```
+    #[inline]
+    pub fn subsets(&self) -> Subsets {
+        Subsets {
+            mask: self.data,
+            front: self.data,
+            back: 0,
+            done: false,
+        }
+    }
+
+    /// Construct an iterator over the subsets of `0..n` with exactly `k`
+    /// elements, in increasing numeric order.
+    ///
+    /// The iterator is empty if `k` exceeds `n` or `n` exceeds the capacity of
+    /// a bit set.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use bitset::{
+    /// #     BitSet,
+    /// # };
+    /// #
+    /// let pairs: Vec<Vec<usize>> = BitSet::combinations(4, 2)
+    ///     .map(|pair| pair.iter().collect())
+    ///     .collect();
+    ///
+    /// assert_eq!(pairs, vec![
+    ///     vec![0, 1], vec![0, 2], vec![1, 2], vec![0, 3], vec![1, 3], vec![2, 3],
+    /// ]);
+    /// ```
+    pub fn combinations(n: usize, k: usize) -> Combinations {
+        if k > n || n > BitSet::new().capacity() {
+            return Combinations {
+                front: 0,
+                back: 0,
+                done: true,
+            };
+        }
+        let first = if k == 128 { u128::MAX } else { (1 << k) - 1 };
+        // Shifting by the full width is only possible for the empty set.
+        let last = if k == 0 { 0 } else { first << (n - k) };
+
+        Combinations {
+            front: first,
+            back: last,
+            done: false,
+        }
+    }
+
+    /// Find the next larger bit set, in numeric order, with the same number of
+    /// bits set to `true`.
+    ///
+    /// The function returns `None` if the bit set is empty or no larger bit set
+    /// with the same number of bits exists.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use bitset::{
+    /// #     BitSet,
+    /// # };
+    /// #
+    /// let bitset = BitSet::from_u64(0b0110);
+    ///
+    /// assert_eq!(bitset.next_same_popcount(), Some(BitSet::from_u64(0b1001)));
+    /// ```
+    #[inline]
+    pub fn next_same_popcount(&self) -> Option<BitSet> {
+        next_same_popcount(self.data).map(BitSet::from_u128)
+    }
+}
+
+/// An iterator over the subsets of a bit set, in decreasing numeric order.
+#[derive(Clone, Debug)]
+pub struct Subsets {
+    mask: u128,
+    front: u128,
+    back: u128,
+    done: bool,
+}
+
+impl Iterator for Subsets {
+    type Item = BitSet;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let subset = self.front;
+        if self.front == self.back {
+            self.done = true;
+        } else {
+            self.front = self.front.wrapping_sub(1) & self.mask;
+        }
+
+        Some(BitSet::from_u128(subset))
+    }
+}
+
+impl DoubleEndedIterator for Subsets {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let subset = self.back;
+        if self.back == self.front {
+            self.done = true;
+        } else {
+            // Filling the bits outside the mask lets the carry of the increment
+            // skip over them.
+            self.back = (self.back | !self.mask).wrapping_add(1) & self.mask;
+        }
+
+        Some(BitSet::from_u128(subset))
+    }
+}
+
+impl FusedIterator for Subsets {}
+
+/// An iterator over the subsets of `0..n` with `k` elements, in increasing
+/// numeric order.
+#[derive(Clone, Debug)]
+pub struct Combinations {
+    front: u128,
+    back: u128,
+    done: bool,
+}
+
+impl Iterator for Combinations {
+    type Item = BitSet;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let combination = self.front;
+        match next_same_popcount(self.front) {
+            Some(next) if combination != self.back => self.front = next,
+            _ => self.done = true,
+        }
+
+        Some(BitSet::from_u128(combination))
+    }
+}
+
+impl DoubleEndedIterator for Combinations {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let combination = self.back;
+        match prev_same_popcount(self.back) {
+            Some(prev) if combination != self.front => self.back = prev,
+            _ => self.done = true,
+        }
+
+        Some(BitSet::from_u128(combination))
+    }
+}
+
+impl FusedIterator for Combinations {}
+
+
+impl BitVec {
+    /// Construct an iterator over every subset of the bit vector as bit
+    /// vectors of the same length, in decreasing numeric order from the bit
+    /// vector itself down to the empty set.
+    ///
+    /// Each step is `(x - 1) & mask` with the borrow carried across the blocks,
+    /// so it takes one pass over the blocks. The iterator visits `2^k` subsets
+    /// for a bit vector of `k` bits, and runs in increasing order when
+    /// reversed.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use bitset::{
+    /// #     BitVec,
+    /// # };
+    /// #
+    /// let mut bitvec = BitVec::zeros(200);
+    /// bitvec.set(3, true);
+    /// bitvec.set(150, true);
+    /// let subsets: Vec<Vec<usize>> = bitvec.subsets()
+    ///     .map(|subset| subset.iter().collect())
+    ///     .collect();
+    ///
+    /// assert_eq!(subsets, vec![vec![3, 150], vec![150], vec![3], vec![]]);
+    /// ```
+    pub fn subsets(&self) -> BitVecSubsets {
+        BitVecSubsets {
+            mask: self.clone(),
+            front: self.clone(),
+            back: BitVec::zeros(self.len()),
+            done: false,
+        }
+    }
+
+    /// Construct an iterator over the subsets of `0..n` with exactly `k`
+    /// elements as bit vectors of length `n`, in increasing numeric order.
+    ///
+    /// Unlike [`BitSet::combinations`], `n` is not limited to the capacity of
+    /// a bit set. The iterator is empty if `k` exceeds `n`.
+    pub fn combinations(n: usize, k: usize) -> BitVecCombinations {
+        if k > n {
+            return BitVecCombinations {
+                front: BitVec::new(),
+                back: BitVec::new(),
+                done: true,
+            };
+        }
+        let mut front = BitVec::zeros(n);
+        front.fill_range(0, k, true);
+        let mut back = BitVec::zeros(n);
+        back.fill_range(n - k, n, true);
+
+        BitVecCombinations {
+            front,
+            back,
+            done: false,
+        }
+    }
+
+    /// Find the next larger bit vector of the same length, in numeric order,
+    /// with the same number of bits set to `true`.
+    ///
+    /// The function returns `None` if the bit vector has no bits set or no
+    /// larger bit vector with the same number of bits exists.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use bitset::{
+    /// #     BitVec,
+    /// # };
+    /// #
+    /// let mut bitvec = BitVec::zeros(200);
+    /// bitvec.set(127, true);
+    /// bitvec.set(128, true);
+    /// let next = bitvec.next_same_popcount().unwrap();
+    ///
+    /// assert_eq!(next.iter().collect::<Vec<_>>(), vec![0, 129]);
+    /// ```
+    pub fn next_same_popcount(&self) -> Option<BitVec> {
+        // Move the top bit of the lowest run up by one, and the rest of the run
+        // down to the bottom of the bit vector.
+        let start = self.next_one(0)?;
+        let end = self.next_zero(start)?;
+        let mut next = self.clone();
+        next.fill_range(start, end, false);
+        next.fill_range(0, end - start - 1, true);
+        next.set(end, true);
+
+        Some(next)
+    }
+
+    /// Find the next smaller bit vector of the same length with the same
+    /// number of bits set to `true`.
+    fn prev_same_popcount(&self) -> Option<BitVec> {
+        (!self).next_same_popcount().map(|complement| !complement)
+    }
+}
+
+/// Replace a subset of `mask` with the next smaller subset, `(x - 1) & mask`,
+/// borrowing from one block to the next.
+fn prev_subset(subset: &mut BitVec, mask: &BitVec) {
+    let mut borrow = true;
+    for (block, mask) in subset.blocks.iter_mut().zip(mask.blocks.iter()) {
+        let (difference, overflow) = block.data.overflowing_sub(borrow as u128);
+        block.data = difference & mask.data;
+        borrow = overflow;
+    }
+}
+
+/// Replace a subset of `mask` with the next larger subset,
+/// `((x | !mask) + 1) & mask`, carrying from one block to the next.
+fn next_subset(subset: &mut BitVec, mask: &BitVec) {
+    // Filling the bits outside the mask lets the carry of the increment skip
+    // over them.
+    let mut carry = true;
+    for (block, mask) in subset.blocks.iter_mut().zip(mask.blocks.iter()) {
+        let (sum, overflow) = (block.data | !mask.data).overflowing_add(carry as u128);
+        block.data = sum & mask.data;
+        carry = overflow;
+    }
+}
+
+/// An iterator over the subsets of a bit vector, in decreasing numeric order.
+#[derive(Clone, Debug)]
+pub struct BitVecSubsets {
+    mask: BitVec,
+    front: BitVec,
+    back: BitVec,
+    done: bool,
+}
+
+impl Iterator for BitVecSubsets {
+    type Item = BitVec;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let subset = self.front.clone();
+        if self.front == self.back {
+            self.done = true;
+        } else {
+            prev_subset(&mut self.front, &self.mask);
+        }
+
+        Some(subset)
+    }
+}
+
+impl DoubleEndedIterator for BitVecSubsets {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let subset = self.back.clone();
+        if self.back == self.front {
+            self.done = true;
+        } else {
+            next_subset(&mut self.back, &self.mask);
+        }
+
+        Some(subset)
+    }
+}
+
+impl FusedIterator for BitVecSubsets {}
+
+/// An iterator over the subsets of `0..n` with `k` elements as bit vectors, in
+/// increasing numeric order.
+#[derive(Clone, Debug)]
+pub struct BitVecCombinations {
+    front: BitVec,
+    back: BitVec,
+    done: bool,
+}
+
+impl Iterator for BitVecCombinations {
+    type Item = BitVec;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        match self.front.next_same_popcount() {
+            Some(next) if self.front != self.back => Some(std::mem::replace(&mut self.front, next)),
+            _ => {
+                self.done = true;
+                Some(self.front.clone())
+            }
+        }
+    }
+}
+
+impl DoubleEndedIterator for BitVecCombinations {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        match self.back.prev_same_popcount() {
+            Some(prev) if self.back != self.front => Some(std::mem::replace(&mut self.back, prev)),
+            _ => {
+                self.done = true;
+                Some(self.back.clone())
+            }
+        }
+    }
+}
+
+impl FusedIterator for BitVecCombinations {}
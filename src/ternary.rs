@@ -0,0 +1,355 @@
+use crate::BitSet;
+use std::error;
+use std::fmt;
+use std::str;
+
+
+/// The error returned when parsing a ternary bit set from a string fails.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ParseTernaryError {
+    /// The string has more characters than the capacity of a bit set.
+    TooLong { length: usize },
+    /// The string has a character other than `0`, `1`, `x`, `X`, or `*`.
+    InvalidCharacter { position: usize, character: char },
+}
+
+impl fmt::Display for ParseTernaryError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseTernaryError::TooLong { length } => {
+                write!(formatter, "a ternary bit set has at most 128 digits, but the string has {}", length)
+            }
+            ParseTernaryError::InvalidCharacter { position, character } => {
+                write!(formatter, "invalid ternary digit {:?} at position {}", character, position)
+            }
+        }
+    }
+}
+
+impl error::Error for ParseTernaryError {}
+
+
+/// A sequence of ternary digits, each of which is `0`, `1`, or don't care.
+///
+/// The digits are stored as a value bit set and a care mask. A bit set to
+/// `true` in the mask marks a digit that must equal the corresponding bit of
+/// the value, and a bit set to `false` marks a don't care digit. The value bits
+/// of don't care digits are always `false`. Ternary bit sets parse from and
+/// format to strings such as `10x1xx`, where the leftmost digit is the highest
+/// bit.
+///
+/// ## Example
+///
+/// ```
+/// # use bitset::{
+/// #     BitSet,
+/// #     TernaryBitSet,
+/// # };
+/// #
+/// let pattern: TernaryBitSet = "10x1xx".parse().unwrap();
+///
+/// assert!(pattern.matches(&BitSet::from_u64(0b101100)));
+/// assert!(pattern.matches(&BitSet::from_u64(0b100111)));
+/// assert!(!pattern.matches(&BitSet::from_u64(0b110100)));
+/// assert_eq!(pattern.as_string_width(6), "10x1xx");
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct TernaryBitSet {
+    value: BitSet,
+    care: BitSet,
+}
+
+impl TernaryBitSet {
+    /// Construct a ternary bit set from the value `value` of the digits set in
+    /// the care mask `care`.
+    ///
+    /// The bits of `value` outside of `care` are discarded.
+    #[inline]
+    pub fn new(value: BitSet, care: BitSet) -> TernaryBitSet {
+        TernaryBitSet {
+            value: value & care,
+            care,
+        }
+    }
+
+    /// Construct the ternary bit set where every digit is don't care, which
+    /// matches every bit set.
+    #[inline]
+    pub fn any() -> TernaryBitSet {
+        TernaryBitSet::new(BitSet::new(), BitSet::new())
+    }
+
+    /// Construct the ternary bit set that matches exactly the bit set
+    /// `bitset`.
+    #[inline]
+    pub fn exact(bitset: BitSet) -> TernaryBitSet {
+        TernaryBitSet::new(bitset, !BitSet::new())
+    }
+
+    /// Return the value bits of the ternary bit set.
+    #[inline]
+    pub fn value(&self) -> BitSet {
+        self.value
+    }
+
+    /// Return the care mask of the ternary bit set.
+    #[inline]
+    pub fn care(&self) -> BitSet {
+        self.care
+    }
+
+    /// Get the digit at position `position`, where `None` is don't care.
+    ///
+    /// Positions outside the capacity of a bit set are don't care.
+    #[inline]
+    pub fn digit(&self, position: usize) -> Option<bool> {
+        if self.care.test(position) {
+            Some(self.value.test(position))
+        } else {
+            None
+        }
+    }
+
+    /// Set the digit at position `position` to `digit`, where `None` is don't
+    /// care.
+    ///
+    /// The function returns `None` if `position` is outside the capacity of a
+    /// bit set.
+    pub fn set_digit(&mut self, position: usize, digit: Option<bool>) -> Option<()> {
+        self.care.set(position, digit.is_some())?;
+        self.value.set(position, digit.unwrap_or(false))
+    }
+
+    /// Determine whether the bit set `bitset` agrees with every digit that is
+    /// not don't care.
+    #[inline]
+    pub fn matches(&self, bitset: &BitSet) -> bool {
+        ((bitset ^ self.value) & self.care).none()
+    }
+
+    /// Determine whether some bit set matches both this ternary bit set and
+    /// `other`.
+    #[inline]
+    pub fn overlaps(&self, other: &TernaryBitSet) -> bool {
+        ((self.value ^ other.value) & self.care & other.care).none()
+    }
+
+    /// Compute the ternary bit set matching exactly the bit sets that match
+    /// both this ternary bit set and `other`.
+    ///
+    /// The function returns `None` if the two ternary bit sets do not overlap.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use bitset::{
+    /// #     TernaryBitSet,
+    /// # };
+    /// #
+    /// let left: TernaryBitSet = "1x0x".parse().unwrap();
+    /// let right: TernaryBitSet = "xx01".parse().unwrap();
+    /// let disjoint: TernaryBitSet = "0xxx".parse().unwrap();
+    ///
+    /// assert_eq!(left.intersect(&right).unwrap().as_string_width(4), "1x01");
+    /// assert_eq!(left.intersect(&disjoint), None);
+    /// ```
+    #[inline]
+    pub fn intersect(&self, other: &TernaryBitSet) -> Option<TernaryBitSet> {
+        if self.overlaps(other) {
+            Some(TernaryBitSet::new(self.value | other.value, self.care | other.care))
+        } else {
+            None
+        }
+    }
+
+    /// Determine whether every bit set that matches `other` also matches this
+    /// ternary bit set.
+    #[inline]
+    pub fn subsumes(&self, other: &TernaryBitSet) -> bool {
+        (self.care & !other.care).none() && ((self.value ^ other.value) & self.care).none()
+    }
+
+    /// Count up the number of digits that are don't care.
+    #[inline]
+    pub fn dont_care_count(&self) -> usize {
+        (!self.care).count()
+    }
+
+    /// Convert the ternary bit set to a string of `0`, `1`, and `x` digits
+    /// covering the full capacity of a bit set.
+    #[inline]
+    pub fn as_string(&self) -> String {
+        self.as_string_width(self.care.capacity())
+    }
+
+    /// Convert the lowest `width` digits of the ternary bit set to a string of
+    /// `0`, `1`, and `x` digits, starting with the highest digit.
+    pub fn as_string_width(&self, width: usize) -> String {
+        (0..usize::min(width, self.care.capacity()))
+            .rev()
+            .map(|position| match self.digit(position) {
+                Some(true) => '1',
+                Some(false) => '0',
+                None => 'x',
+            })
+            .collect()
+    }
+}
+
+impl str::FromStr for TernaryBitSet {
+    type Err = ParseTernaryError;
+
+    /// Parse a string of ternary digits, where the leftmost digit is the
+    /// highest bit. The digits `x`, `X`, and `*` are don't care, and the digits
+    /// past the length of the string are don't care as well.
+    fn from_str(string: &str) -> Result<TernaryBitSet, ParseTernaryError> {
+        let length = string.chars().count();
+        if length > BitSet::new().capacity() {
+            return Err(ParseTernaryError::TooLong { length });
+        }
+
+        let mut ternary = TernaryBitSet::any();
+        for (i, character) in string.chars().enumerate() {
+            let digit = match character {
+                '0' => Some(false),
+                '1' => Some(true),
+                'x' | 'X' | '*' => None,
+                _ => return Err(ParseTernaryError::InvalidCharacter { position: i, character }),
+            };
+            ternary.set_digit(length - 1 - i, digit);
+        }
+
+        Ok(ternary)
+    }
+}
+
+impl fmt::Display for TernaryBitSet {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "TernaryBitSet [{}]", self.as_string())
+    }
+}
+
+
+/// A rule of a classifier, made of a pattern, a priority, and the action taken
+/// by the keys that select the rule.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Rule<T> {
+    pattern: TernaryBitSet,
+    priority: u32,
+    action: T,
+}
+
+impl<T> Rule<T> {
+    /// Return the pattern that keys match against.
+    #[inline]
+    pub fn pattern(&self) -> &TernaryBitSet {
+        &self.pattern
+    }
+
+    /// Return the priority of the rule.
+    #[inline]
+    pub fn priority(&self) -> u32 {
+        self.priority
+    }
+
+    /// Return the action of the rule.
+    #[inline]
+    pub fn action(&self) -> &T {
+        &self.action
+    }
+}
+
+/// A classifier in the style of a ternary content-addressable memory, which
+/// selects the highest priority rule whose pattern matches a key.
+///
+/// Among rules of equal priority, the rule inserted first wins.
+///
+/// ## Example
+///
+/// ```
+/// # use bitset::{
+/// #     BitSet,
+/// #     Classifier,
+/// # };
+/// #
+/// let mut classifier = Classifier::new();
+/// classifier.insert("xxxx".parse().unwrap(), 0, "default");
+/// classifier.insert("10xx".parse().unwrap(), 10, "subnet");
+/// classifier.insert("1011".parse().unwrap(), 20, "host");
+///
+/// assert_eq!(classifier.classify(&BitSet::from_u64(0b1011)), Some(&"host"));
+/// assert_eq!(classifier.classify(&BitSet::from_u64(0b1001)), Some(&"subnet"));
+/// assert_eq!(classifier.classify(&BitSet::from_u64(0b0001)), Some(&"default"));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Classifier<T> {
+    rules: Vec<Rule<T>>,
+}
+
+impl<T> Classifier<T> {
+    /// Construct a new classifier with no rules.
+    #[inline]
+    pub fn new() -> Classifier<T> {
+        Classifier {
+            rules: Vec::new(),
+        }
+    }
+
+    /// Return the number of rules in the classifier.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    /// Determine whether the classifier has no rules.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Insert a rule with the pattern `pattern`, the priority `priority`, and
+    /// the action `action`.
+    pub fn insert(&mut self, pattern: TernaryBitSet, priority: u32, action: T) {
+        // Keep the rules ordered by decreasing priority, placing the new rule
+        // after the rules of equal priority.
+        let index = self.rules.partition_point(|rule| rule.priority >= priority);
+        self.rules.insert(index, Rule { pattern, priority, action });
+    }
+
+    /// Construct an iterator over the rules, in the order in which they are
+    /// tried.
+    #[inline]
+    pub fn rules(&self) -> std::slice::Iter<'_, Rule<T>> {
+        self.rules.iter()
+    }
+
+    /// Find the highest priority rule whose pattern matches the key `key`.
+    #[inline]
+    pub fn lookup(&self, key: &BitSet) -> Option<&Rule<T>> {
+        self.rules.iter().find(|rule| rule.pattern.matches(key))
+    }
+
+    /// Find the action of the highest priority rule whose pattern matches the
+    /// key `key`.
+    #[inline]
+    pub fn classify(&self, key: &BitSet) -> Option<&T> {
+        self.lookup(key).map(|rule| &rule.action)
+    }
+
+    /// Find the rules that no key can ever select, because rules tried before
+    /// them subsume their patterns.
+    pub fn shadowed(&self) -> Vec<&Rule<T>> {
+        self.rules.iter()
+            .enumerate()
+            .filter(|&(i, rule)| self.rules[..i].iter().any(|earlier| earlier.pattern.subsumes(&rule.pattern)))
+            .map(|(_, rule)| rule)
+            .collect()
+    }
+}
+
+impl<T> Default for Classifier<T> {
+    #[inline]
+    fn default() -> Classifier<T> {
+        Classifier::new()
+    }
+}
@@ -0,0 +1,85 @@
+use crate::BitSet;
+
+
+/// Transpose a `128 × 128` block of bits stored as 128 bit sets in place.
+///
+/// Bit `j` of row `i` is the entry at row `i` and column `j`, and the transpose
+/// moves it to bit `i` of row `j`. The transpose uses the recursive block-swap
+/// method: the block is split into four quadrants, the two off-diagonal
+/// quadrants are exchanged, and the same step is repeated on quadrants half
+/// the size. All quadrants of one size are exchanged at once with masks, so
+/// the transpose takes `128 log 128` word operations instead of `128²` bit
+/// operations.
+///
+/// ## Example
+///
+/// ```
+/// # use bitset::{
+/// #     BitSet,
+/// #     transpose_128,
+/// # };
+/// #
+/// // Row i holds the components of entity i.
+/// let mut masks = [BitSet::new(); 128];
+/// masks[0] = BitSet::from_u64(0b101);
+/// masks[1] = BitSet::from_u64(0b100);
+/// transpose_128(&mut masks);
+///
+/// // Row j now holds the entities with component j.
+/// assert_eq!(masks[0], BitSet::from_u64(0b01));
+/// assert_eq!(masks[1], BitSet::new());
+/// assert_eq!(masks[2], BitSet::from_u64(0b11));
+/// ```
+pub fn transpose_128(block: &mut [BitSet; 128]) {
+    let mut width = 64;
+    let mut mask: u128 = u128::MAX >> 64;
+    while width > 0 {
+        for row in 0..128 {
+            if row & width == 0 {
+                // Exchange the upper quadrant of this row with the lower
+                // quadrant of the row `width` below it.
+                let swapped = ((block[row].data >> width) ^ block[row + width].data) & mask;
+                block[row].data ^= swapped << width;
+                block[row + width].data ^= swapped;
+            }
+        }
+        width >>= 1;
+        mask ^= mask << width;
+    }
+}
+
+/// Transpose a `64 × 64` block of bits stored as 64 words in place, by the
+/// same method as [`transpose_128`].
+pub fn transpose_64(block: &mut [u64; 64]) {
+    let mut width = 32;
+    let mut mask: u64 = u64::MAX >> 32;
+    while width > 0 {
+        for row in 0..64 {
+            if row & width == 0 {
+                let swapped = ((block[row] >> width) ^ block[row + width]) & mask;
+                block[row] ^= swapped << width;
+                block[row + width] ^= swapped;
+            }
+        }
+        width >>= 1;
+        mask ^= mask << width;
+    }
+}
+
+/// Transpose an `8 × 8` block of bits stored as 8 bytes in place.
+///
+/// The whole block fits in one 64-bit word, so each quadrant exchange is a
+/// single masked swap within the word.
+pub fn transpose_8(block: &mut [u8; 8]) {
+    let mut word = u64::from_le_bytes(*block);
+    word = (word & 0xAA55_AA55_AA55_AA55)
+        | ((word & 0x00AA_00AA_00AA_00AA) << 7)
+        | ((word >> 7) & 0x00AA_00AA_00AA_00AA);
+    word = (word & 0xCCCC_3333_CCCC_3333)
+        | ((word & 0x0000_CCCC_0000_CCCC) << 14)
+        | ((word >> 14) & 0x0000_CCCC_0000_CCCC);
+    word = (word & 0xF0F0_F0F0_0F0F_0F0F)
+        | ((word & 0x0000_0000_F0F0_F0F0) << 28)
+        | ((word >> 28) & 0x0000_0000_F0F0_F0F0);
+    *block = word.to_le_bytes();
+}
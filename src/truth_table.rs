@@ -0,0 +1,494 @@
+use crate::BitSet;
+use std::fmt;
+use std::ops;
+
+
+/// The truth tables of the projection functions of the seven variables. Entry
+/// `k` of a truth table holds the value of the function for the assignment
+/// whose bit `i` is the value of variable `i`.
+pub(crate) const VAR_MASKS: [u128; 7] = [
+    0xAAAA_AAAA_AAAA_AAAA_AAAA_AAAA_AAAA_AAAA,
+    0xCCCC_CCCC_CCCC_CCCC_CCCC_CCCC_CCCC_CCCC,
+    0xF0F0_F0F0_F0F0_F0F0_F0F0_F0F0_F0F0_F0F0,
+    0xFF00_FF00_FF00_FF00_FF00_FF00_FF00_FF00,
+    0xFFFF_0000_FFFF_0000_FFFF_0000_FFFF_0000,
+    0xFFFF_FFFF_0000_0000_FFFF_FFFF_0000_0000,
+    0xFFFF_FFFF_FFFF_FFFF_0000_0000_0000_0000,
+];
+
+/// Return the mask of the entries of a truth table with `vars` variables.
+#[inline]
+pub(crate) fn entries_mask(vars: usize) -> u128 {
+    if vars >= 7 {
+        u128::MAX
+    } else {
+        (1 << (1 << vars)) - 1
+    }
+}
+
+/// Swap the variables `i` and `j` of the truth table `table`, where `i < j`.
+#[inline]
+fn swap_vars(table: u128, i: usize, j: usize) -> u128 {
+    let shift = (1 << j) - (1 << i);
+    // The entries where variable `i` is set and variable `j` is clear trade
+    // places with the entries where variable `i` is clear and variable `j` is
+    // set.
+    let mask = VAR_MASKS[i] & !VAR_MASKS[j];
+    let t = ((table >> shift) ^ table) & mask;
+
+    table ^ t ^ (t << shift)
+}
+
+/// Negate the variable `i` of the truth table `table`.
+#[inline]
+fn negate_var(table: u128, i: usize) -> u128 {
+    let shift = 1 << i;
+
+    ((table & VAR_MASKS[i]) >> shift) | ((table & !VAR_MASKS[i]) << shift)
+}
+
+
+/// The truth table of a Boolean function of up to seven variables, stored in a
+/// bit set.
+///
+/// Bit `k` of the table is the value of the function on the assignment whose
+/// bit `i` is the value of variable `i`. A function of `n` variables uses the
+/// lowest `2^n` bits of the table. Truth tables with the same number of
+/// variables can be combined with the `&`, `|`, `^`, and `!` operators.
+///
+/// ## Example
+///
+/// ```
+/// # use bitset::{
+/// #     TruthTable,
+/// # };
+/// #
+/// let a = TruthTable::var(3, 0).unwrap();
+/// let b = TruthTable::var(3, 1).unwrap();
+/// let c = TruthTable::var(3, 2).unwrap();
+/// let majority = (a & b) | (a & c) | (b & c);
+///
+/// assert_eq!(majority.evaluate(0b011), true);
+/// assert_eq!(majority.evaluate(0b100), false);
+/// assert_eq!(majority.to_string(), "E8");
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct TruthTable {
+    vars: usize,
+    table: BitSet,
+}
+
+impl TruthTable {
+    /// The maximum number of variables of a truth table.
+    pub const MAX_VARS: usize = 7;
+
+    /// Construct the truth table of a function of `vars` variables from the
+    /// bits of `table`.
+    ///
+    /// The bits of `table` past the `2^vars` entries of the truth table are
+    /// discarded. The function returns `None` if `vars` exceeds
+    /// [`TruthTable::MAX_VARS`].
+    #[inline]
+    pub fn new(vars: usize, table: BitSet) -> Option<TruthTable> {
+        if vars <= TruthTable::MAX_VARS {
+            Some(TruthTable::from_raw(vars, table.data))
+        } else {
+            None
+        }
+    }
+
+    /// Construct the constant function of `vars` variables with the value
+    /// `value`.
+    ///
+    /// The function returns `None` if `vars` exceeds [`TruthTable::MAX_VARS`].
+    #[inline]
+    pub fn constant(vars: usize, value: bool) -> Option<TruthTable> {
+        let table = if value { u128::MAX } else { 0 };
+
+        TruthTable::new(vars, BitSet::from_u128(table))
+    }
+
+    /// Construct the projection function of `vars` variables that returns the
+    /// value of variable `var`.
+    ///
+    /// The function returns `None` if `vars` exceeds [`TruthTable::MAX_VARS`]
+    /// or `var` is not less than `vars`.
+    #[inline]
+    pub fn var(vars: usize, var: usize) -> Option<TruthTable> {
+        if var < vars {
+            TruthTable::new(vars, BitSet::from_u128(VAR_MASKS[var]))
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    fn from_raw(vars: usize, table: u128) -> TruthTable {
+        TruthTable {
+            vars,
+            table: BitSet::from_u128(table & entries_mask(vars)),
+        }
+    }
+
+    /// Return the number of variables of the function.
+    #[inline]
+    pub const fn vars(&self) -> usize {
+        self.vars
+    }
+
+    /// Return the entries of the truth table as a bit set.
+    #[inline]
+    pub const fn table(&self) -> BitSet {
+        self.table
+    }
+
+    /// Evaluate the function on an assignment, where bit `i` of `assignment`
+    /// is the value of variable `i`.
+    ///
+    /// Bits of `assignment` past the number of variables are ignored.
+    #[inline]
+    pub fn evaluate(&self, assignment: usize) -> bool {
+        self.table.test(assignment & ((1 << self.vars) - 1))
+    }
+
+    /// Compute the cofactor of the function with respect to variable `var`
+    /// having the value `value`.
+    ///
+    /// The cofactor has the same number of variables as the function, and does
+    /// not depend on variable `var`. The function returns `None` if `var` is
+    /// not a variable of the function.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use bitset::{
+    /// #     TruthTable,
+    /// # };
+    /// #
+    /// let a = TruthTable::var(2, 0).unwrap();
+    /// let b = TruthTable::var(2, 1).unwrap();
+    /// let f = a & b;
+    ///
+    /// assert_eq!(f.cofactor(0, true), Some(b));
+    /// assert_eq!(f.cofactor(0, false), TruthTable::constant(2, false));
+    /// ```
+    pub fn cofactor(&self, var: usize, value: bool) -> Option<TruthTable> {
+        if var >= self.vars {
+            return None;
+        }
+        let shift = 1 << var;
+        let table = self.table.data;
+        let cofactor = if value {
+            let half = table & VAR_MASKS[var];
+            half | (half >> shift)
+        } else {
+            let half = table & !VAR_MASKS[var];
+            half | (half << shift)
+        };
+
+        Some(TruthTable::from_raw(self.vars, cofactor))
+    }
+
+    /// Determine whether the value of the function depends on variable `var`.
+    #[inline]
+    pub fn depends_on(&self, var: usize) -> bool {
+        self.cofactor(var, false) != self.cofactor(var, true)
+    }
+
+    /// Compute the set of variables the function depends on.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use bitset::{
+    /// #     BitSet,
+    /// #     TruthTable,
+    /// # };
+    /// #
+    /// let a = TruthTable::var(4, 0).unwrap();
+    /// let c = TruthTable::var(4, 2).unwrap();
+    ///
+    /// assert_eq!((a ^ c).support(), BitSet::from_u64(0b0101));
+    /// ```
+    pub fn support(&self) -> BitSet {
+        (0..self.vars).filter(|&var| self.depends_on(var)).collect()
+    }
+
+    /// Exchange the variables `i` and `j` of the function.
+    ///
+    /// The function returns `None` if either variable is not a variable of the
+    /// function.
+    pub fn swap(&self, i: usize, j: usize) -> Option<TruthTable> {
+        if i >= self.vars || j >= self.vars {
+            return None;
+        }
+        let table = match i.cmp(&j) {
+            std::cmp::Ordering::Less => swap_vars(self.table.data, i, j),
+            std::cmp::Ordering::Greater => swap_vars(self.table.data, j, i),
+            std::cmp::Ordering::Equal => self.table.data,
+        };
+
+        Some(TruthTable::from_raw(self.vars, table))
+    }
+
+    /// Rename the variables of the function, so that variable `i` of the
+    /// function becomes variable `permutation[i]` of the result.
+    ///
+    /// The function returns `None` if `permutation` is not a permutation of
+    /// the variables of the function.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use bitset::{
+    /// #     TruthTable,
+    /// # };
+    /// #
+    /// let a = TruthTable::var(3, 0).unwrap();
+    /// let c = TruthTable::var(3, 2).unwrap();
+    ///
+    /// assert_eq!(a.permute(&[2, 0, 1]), Some(c));
+    /// ```
+    pub fn permute(&self, permutation: &[usize]) -> Option<TruthTable> {
+        if !is_permutation(permutation, self.vars) {
+            return None;
+        }
+        let mut table = 0;
+        for target in 0..(1 << self.vars) {
+            let source = (0..self.vars)
+                .filter(|&var| target & (1 << permutation[var]) != 0)
+                .fold(0, |source, var| source | (1 << var));
+            if self.table.test(source) {
+                table |= 1 << target;
+            }
+        }
+
+        Some(TruthTable::from_raw(self.vars, table))
+    }
+
+    /// Negate the input variable `var` of the function.
+    ///
+    /// The function returns `None` if `var` is not a variable of the function.
+    pub fn negate_input(&self, var: usize) -> Option<TruthTable> {
+        if var < self.vars {
+            Some(TruthTable::from_raw(self.vars, negate_var(self.table.data, var)))
+        } else {
+            None
+        }
+    }
+
+    /// Compute the NPN canonical form of the function, along with the
+    /// transformation that maps the function to it.
+    ///
+    /// Two functions are NPN equivalent when one can be obtained from the other
+    /// by negating inputs, permuting inputs, and negating the output. The
+    /// canonical form is the equivalent function with the numerically smallest
+    /// truth table. The search is exhaustive, so functions of seven variables
+    /// take a noticeable amount of time.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use bitset::{
+    /// #     TruthTable,
+    /// # };
+    /// #
+    /// let a = TruthTable::var(2, 0).unwrap();
+    /// let b = TruthTable::var(2, 1).unwrap();
+    /// let (and_form, _) = (a & b).npn_canonical();
+    /// let (nor_form, transform) = (!(a | b)).npn_canonical();
+    ///
+    /// assert_eq!(and_form, nor_form);
+    /// assert_eq!(transform.apply(&!(a | b)), Some(nor_form));
+    /// ```
+    pub fn npn_canonical(&self) -> (TruthTable, NpnTransform) {
+        let vars = self.vars;
+        let mask = entries_mask(vars);
+        let mut best = self.table.data;
+        let mut best_transform = NpnTransform::identity(vars);
+        // The original variable found at each position of the permuted table.
+        let mut arrangement: Vec<usize> = (0..vars).collect();
+        let mut counters = vec![0; vars];
+        let mut permuted = self.table.data;
+        let mut level = 0;
+        loop {
+            // Visit every input negation of the permuted table in Gray code order,
+            // so that each step negates a single variable.
+            let mut negated = permuted;
+            let mut negations = 0_usize;
+            for step in 0..(1_usize << vars) {
+                if step > 0 {
+                    let var = step.trailing_zeros() as usize;
+                    negated = negate_var(negated, var);
+                    negations ^= 1 << var;
+                }
+                for &output_negated in [false, true].iter() {
+                    let candidate = if output_negated { !negated & mask } else { negated };
+                    if candidate < best {
+                        best = candidate;
+                        best_transform = NpnTransform::from_arrangement(&arrangement, negations, output_negated);
+                    }
+                }
+            }
+
+            // Advance to the next permutation with Heap's algorithm, where each
+            // step exchanges two variables.
+            while level < vars && counters[level] >= level {
+                counters[level] = 0;
+                level += 1;
+            }
+            if level >= vars {
+                break;
+            }
+            let other = if level % 2 == 0 { 0 } else { counters[level] };
+            permuted = swap_vars(permuted, usize::min(other, level), usize::max(other, level));
+            arrangement.swap(other, level);
+            counters[level] += 1;
+            level = 0;
+        }
+
+        (TruthTable::from_raw(vars, best), best_transform)
+    }
+}
+
+/// Determine whether `permutation` is a permutation of `0..length`.
+fn is_permutation(permutation: &[usize], length: usize) -> bool {
+    let mut seen = BitSet::new();
+    permutation.len() == length && permutation.iter().all(|&target| {
+        let fresh = target < length && !seen.test(target);
+        seen.set(target, true);
+        fresh
+    })
+}
+
+impl fmt::Display for TruthTable {
+    /// Format the truth table in hexadecimal, with the entry of the highest
+    /// assignment first.
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let digits = usize::max(1, (1 << self.vars) / 4);
+        write!(formatter, "{:0width$X}", self.table.data, width = digits)
+    }
+}
+
+
+/// A transformation of a Boolean function made of input negations, a
+/// permutation of the inputs, and an output negation, applied in that order.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct NpnTransform {
+    input_negations: BitSet,
+    permutation: Vec<usize>,
+    output_negated: bool,
+}
+
+impl NpnTransform {
+    /// Construct the transformation of functions of `vars` variables that
+    /// changes nothing.
+    pub fn identity(vars: usize) -> NpnTransform {
+        NpnTransform {
+            input_negations: BitSet::new(),
+            permutation: (0..vars).collect(),
+            output_negated: false,
+        }
+    }
+
+    /// Construct a transformation from the permuted order of the original
+    /// variables and the negations of the permuted variables.
+    fn from_arrangement(arrangement: &[usize], negations: usize, output_negated: bool) -> NpnTransform {
+        let mut permutation = vec![0; arrangement.len()];
+        let mut input_negations = BitSet::new();
+        for (position, &var) in arrangement.iter().enumerate() {
+            permutation[var] = position;
+            if negations & (1 << position) != 0 {
+                input_negations.set(var, true);
+            }
+        }
+
+        NpnTransform {
+            input_negations,
+            permutation,
+            output_negated,
+        }
+    }
+
+    /// Return the set of input variables that are negated.
+    #[inline]
+    pub fn input_negations(&self) -> BitSet {
+        self.input_negations
+    }
+
+    /// Return the permutation of the inputs, which sends variable `i` to
+    /// variable `permutation()[i]`.
+    #[inline]
+    pub fn permutation(&self) -> &[usize] {
+        &self.permutation
+    }
+
+    /// Determine whether the output is negated.
+    #[inline]
+    pub fn output_negated(&self) -> bool {
+        self.output_negated
+    }
+
+    /// Apply the transformation to the function `function`.
+    ///
+    /// The function returns `None` if the number of variables of `function`
+    /// differs from the number of variables of the transformation.
+    pub fn apply(&self, function: &TruthTable) -> Option<TruthTable> {
+        let mut result = *function;
+        for var in self.input_negations.iter() {
+            result = result.negate_input(var)?;
+        }
+        result = result.permute(&self.permutation)?;
+        if self.output_negated {
+            result = !result;
+        }
+
+        Some(result)
+    }
+}
+
+
+/// Check that two truth tables have the same number of variables.
+#[inline]
+fn assert_same_vars(left: &TruthTable, right: &TruthTable) {
+    assert_eq!(left.vars, right.vars, "truth tables must have the same number of variables");
+}
+
+impl ops::BitAnd<TruthTable> for TruthTable {
+    type Output = TruthTable;
+
+    #[inline]
+    fn bitand(self, other: TruthTable) -> Self::Output {
+        assert_same_vars(&self, &other);
+        TruthTable::from_raw(self.vars, self.table.data & other.table.data)
+    }
+}
+
+impl ops::BitOr<TruthTable> for TruthTable {
+    type Output = TruthTable;
+
+    #[inline]
+    fn bitor(self, other: TruthTable) -> Self::Output {
+        assert_same_vars(&self, &other);
+        TruthTable::from_raw(self.vars, self.table.data | other.table.data)
+    }
+}
+
+impl ops::BitXor<TruthTable> for TruthTable {
+    type Output = TruthTable;
+
+    #[inline]
+    fn bitxor(self, other: TruthTable) -> Self::Output {
+        assert_same_vars(&self, &other);
+        TruthTable::from_raw(self.vars, self.table.data ^ other.table.data)
+    }
+}
+
+impl ops::Not for TruthTable {
+    type Output = TruthTable;
+
+    #[inline]
+    fn not(self) -> Self::Output {
+        TruthTable::from_raw(self.vars, !self.table.data)
+    }
+}
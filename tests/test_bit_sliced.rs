@@ -0,0 +1,130 @@
+extern crate bitset;
+
+
+use bitset::{
+    BitSlicedIndex,
+    BitVec,
+    RangeEncodedIndex,
+};
+
+
+/// Generate a column of pseudo-random values below `bound` with a linear
+/// congruential generator.
+fn column(len: usize, bound: u64, seed: u64) -> Vec<u64> {
+    let mut state = seed;
+    (0..len)
+        .map(|_| {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            (state >> 33) % bound
+        })
+        .collect()
+}
+
+fn scan<F>(values: &[u64], predicate: F) -> Vec<usize> where F: Fn(u64) -> bool {
+    (0..values.len()).filter(|&row| predicate(values[row])).collect()
+}
+
+fn rows(bitvec: &BitVec) -> Vec<usize> {
+    bitvec.iter().collect()
+}
+
+#[test]
+fn test_bit_sliced_comparisons_match_scan() {
+    let values = column(700, 1000, 1);
+    let index: BitSlicedIndex = values.iter().copied().collect();
+
+    assert_eq!(index.len(), 700);
+    assert_eq!(index.slice_count(), 10);
+    for &constant in [0, 1, 17, 500, 998, 999, 1000, 5000, u64::MAX].iter() {
+        assert_eq!(rows(&index.lt(constant)), scan(&values, |v| v < constant));
+        assert_eq!(rows(&index.le(constant)), scan(&values, |v| v <= constant));
+        assert_eq!(rows(&index.eq(constant)), scan(&values, |v| v == constant));
+        assert_eq!(rows(&index.gt(constant)), scan(&values, |v| v > constant));
+        assert_eq!(rows(&index.ge(constant)), scan(&values, |v| v >= constant));
+    }
+    assert_eq!(rows(&index.between(100, 300)), scan(&values, |v| (100..=300).contains(&v)));
+    assert!(index.between(300, 100).none());
+}
+
+#[test]
+fn test_bit_sliced_get_and_sum() {
+    let values = column(300, 1 << 40, 2);
+    let mut index = BitSlicedIndex::new();
+    index.extend(values.iter().copied());
+    index.push(u64::MAX);
+
+    for (row, &value) in values.iter().enumerate() {
+        assert_eq!(index.get(row), Some(value));
+    }
+    assert_eq!(index.get(300), Some(u64::MAX));
+    assert_eq!(index.get(301), None);
+    let total: u128 = values.iter().map(|&value| value as u128).sum();
+    assert_eq!(index.sum(&index.lt(u64::MAX)), total);
+    assert_eq!(index.sum(&BitVec::ones(301)), total + u64::MAX as u128);
+}
+
+#[test]
+fn test_bit_sliced_top_k() {
+    let values = column(500, 50, 3);
+    let index: BitSlicedIndex = values.iter().copied().collect();
+    let mut ranked: Vec<usize> = (0..values.len()).collect();
+    ranked.sort_by_key(|&row| (std::cmp::Reverse(values[row]), row));
+    for &k in [0, 1, 7, 100, 499, 500, 600].iter() {
+        let mut expected: Vec<usize> = ranked.iter().copied().take(k).collect();
+        expected.sort_unstable();
+        assert_eq!(rows(&index.top_k(k)), expected);
+    }
+}
+
+#[test]
+fn test_empty_bit_sliced_index() {
+    let index = BitSlicedIndex::new();
+
+    assert!(index.is_empty());
+    assert_eq!(index.eq(0).len(), 0);
+    assert_eq!(index.top_k(3).len(), 0);
+    assert_eq!(index.sum(&BitVec::new()), 0);
+}
+
+#[test]
+fn test_range_encoded_matches_bit_sliced() {
+    let values = column(400, 10, 4);
+    let mut range = RangeEncodedIndex::new(9).unwrap();
+    for &value in values.iter() {
+        range.push(value).unwrap();
+    }
+    let sliced: BitSlicedIndex = values.iter().copied().collect();
+
+    assert_eq!(range.len(), 400);
+    for constant in 0..12 {
+        assert_eq!(range.lt(constant), sliced.lt(constant));
+        assert_eq!(range.le(constant), sliced.le(constant));
+        assert_eq!(range.eq(constant), sliced.eq(constant));
+        assert_eq!(range.gt(constant), sliced.gt(constant));
+        assert_eq!(range.ge(constant), sliced.ge(constant));
+        for high in 0..12 {
+            assert_eq!(range.between(constant, high), sliced.between(constant, high));
+        }
+    }
+}
+
+#[test]
+fn test_range_encoded_rejects_large_values() {
+    let mut index = RangeEncodedIndex::new(3).unwrap();
+
+    assert_eq!(index.push(3), Some(0));
+    assert_eq!(index.push(4), None);
+    assert_eq!(index.len(), 1);
+    assert_eq!(index.max_value(), 3);
+    assert_eq!(rows(&index.eq(3)), vec![0]);
+}
+
+#[test]
+fn test_range_encoded_max_value_limit() {
+    let limit = RangeEncodedIndex::MAX_VALUE_LIMIT;
+
+    assert_eq!(RangeEncodedIndex::new(limit).map(|index| index.max_value()), Some(limit));
+    assert!(RangeEncodedIndex::new(limit + 1).is_none());
+    assert!(RangeEncodedIndex::new(u64::MAX).is_none());
+    assert_eq!(RangeEncodedIndex::new(0).unwrap().push(0), Some(0));
+}
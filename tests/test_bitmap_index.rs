@@ -0,0 +1,70 @@
+extern crate bitset;
+
+
+use bitset::{
+    BitmapIndex,
+    BitVec,
+};
+
+
+fn rows(bitvec: &BitVec) -> Vec<usize> {
+    bitvec.iter().collect()
+}
+
+#[test]
+fn test_predicates_match_scan() {
+    let column: Vec<u32> = (0..1000).map(|i| (i * i + 3) % 7).collect();
+    let index: BitmapIndex<u32> = column.iter().copied().collect();
+
+    assert_eq!(index.len(), 1000);
+    assert_eq!(index.cardinality(), column.iter().collect::<std::collections::HashSet<_>>().len());
+    for value in 0..8 {
+        let expected: Vec<usize> = (0..1000).filter(|&row| column[row] == value).collect();
+        let others: Vec<usize> = (0..1000).filter(|&row| column[row] != value).collect();
+        assert_eq!(rows(&index.eq(&value)), expected);
+        assert_eq!(rows(&index.not_eq(&value)), others);
+        assert_eq!(index.count(&value), expected.len());
+        assert_eq!(index.eq(&value).len(), 1000);
+    }
+    let expected: Vec<usize> = (0..1000).filter(|&row| column[row] == 3 || column[row] == 4).collect();
+    assert_eq!(rows(&index.in_set(&[3, 4, 100])), expected);
+    assert_eq!(index.not_in_set(&[3, 4]).count(), 1000 - expected.len());
+}
+
+#[test]
+fn test_push_extends_every_predicate() {
+    let mut index = BitmapIndex::new();
+    assert_eq!(index.push(String::from("red")), 0);
+    assert_eq!(index.push(String::from("blue")), 1);
+    index.extend(vec![String::from("red"), String::from("green")]);
+
+    // Values that stopped appearing still answer over every row.
+    assert_eq!(index.eq("blue").len(), 4);
+    assert_eq!(rows(&index.eq("red")), vec![0, 2]);
+    assert_eq!(rows(&index.not_eq("blue")), vec![0, 2, 3]);
+    assert_eq!(rows(&index.not_eq("purple")), vec![0, 1, 2, 3]);
+    assert_eq!(rows(&index.in_set(vec!["green", "blue"])), vec![1, 3]);
+}
+
+#[test]
+fn test_combinations_across_indexes() {
+    let kinds: BitmapIndex<char> = "abcabcabca".chars().collect();
+    let flags: BitmapIndex<bool> = (0..10).map(|i| i % 2 == 0).collect();
+    let either = &kinds.eq(&'a') | &flags.eq(&true);
+    let both = &kinds.eq(&'a') & &flags.eq(&true);
+    let exclusive = &kinds.eq(&'a') ^ &flags.eq(&true);
+
+    assert_eq!(rows(&either), vec![0, 2, 3, 4, 6, 8, 9]);
+    assert_eq!(rows(&both), vec![0, 6]);
+    assert_eq!(rows(&exclusive), vec![2, 3, 4, 8, 9]);
+}
+
+#[test]
+fn test_empty_index() {
+    let index: BitmapIndex<u8> = BitmapIndex::default();
+
+    assert!(index.is_empty());
+    assert_eq!(index.eq(&1).len(), 0);
+    assert_eq!(index.not_eq(&1).len(), 0);
+    assert_eq!(index.values().count(), 0);
+}
@@ -0,0 +1,178 @@
+extern crate bitset;
+
+
+use bitset::{
+    BitMatrix,
+    BitVec,
+};
+
+
+fn matrix_from_edges(size: usize, edges: &[(usize, usize)]) -> BitMatrix {
+    let mut matrix = BitMatrix::new(size, size);
+    for &(row, col) in edges {
+        matrix.set(row, col, true);
+    }
+
+    matrix
+}
+
+
+#[test]
+fn test_new() {
+    let matrix = BitMatrix::new(3, 300);
+
+    assert_eq!(matrix.rows(), 3);
+    assert_eq!(matrix.cols(), 300);
+    assert_eq!(matrix.count(), 0);
+    assert!(!matrix.is_square());
+}
+
+#[test]
+fn test_set_and_get() {
+    let mut matrix = BitMatrix::new(2, 200);
+    matrix.set(1, 150, true);
+
+    assert_eq!(matrix.get(1, 150), Some(true));
+    assert_eq!(matrix.get(0, 150), Some(false));
+    assert_eq!(matrix.get(2, 0), None);
+    assert_eq!(matrix.get(0, 200), None);
+    assert_eq!(matrix.set(0, 200, true), None);
+}
+
+#[test]
+fn test_identity() {
+    let matrix = BitMatrix::identity(130);
+
+    assert_eq!(matrix.count(), 130);
+    assert_eq!(matrix.get(129, 129), Some(true));
+    assert_eq!(matrix.transpose(), matrix);
+}
+
+#[test]
+fn test_from_rows() {
+    let rows = vec![BitVec::zeros(4), BitVec::ones(4)];
+    let matrix = BitMatrix::from_rows(rows, 4).unwrap();
+
+    assert_eq!(matrix.rows(), 2);
+    assert_eq!(matrix.row(1), Some(&BitVec::ones(4)));
+    assert!(BitMatrix::from_rows(vec![BitVec::zeros(3)], 4).is_none());
+}
+
+#[test]
+fn test_column() {
+    let matrix = matrix_from_edges(4, &[(0, 2), (3, 2), (1, 1)]);
+    let column = matrix.column(2).unwrap();
+
+    assert_eq!(column.len(), 4);
+    assert_eq!(column.iter().collect::<Vec<_>>(), vec![0, 3]);
+    assert!(matrix.column(4).is_none());
+}
+
+#[test]
+fn test_transpose() {
+    let mut matrix = BitMatrix::new(2, 3);
+    matrix.set(0, 2, true);
+    matrix.set(1, 0, true);
+    let transpose = matrix.transpose();
+
+    assert_eq!(transpose.rows(), 3);
+    assert_eq!(transpose.cols(), 2);
+    assert_eq!(transpose.get(2, 0), Some(true));
+    assert_eq!(transpose.get(0, 1), Some(true));
+    assert_eq!(transpose.count(), 2);
+    assert_eq!(transpose.transpose(), matrix);
+}
+
+#[test]
+fn test_transpose_across_tiles() {
+    let (rows, cols) = (300, 170);
+    let mut matrix = BitMatrix::new(rows, cols);
+    for i in 0..rows {
+        for j in 0..cols {
+            if (i * 7 + j * 13) % 5 == 0 {
+                matrix.set(i, j, true);
+            }
+        }
+    }
+    let transpose = matrix.transpose();
+
+    assert_eq!((transpose.rows(), transpose.cols()), (cols, rows));
+    for i in 0..rows {
+        for j in 0..cols {
+            assert_eq!(transpose.get(j, i), matrix.get(i, j));
+        }
+    }
+    assert_eq!(transpose.count(), matrix.count());
+    assert_eq!(transpose.transpose(), matrix);
+}
+
+#[test]
+fn test_multiply() {
+    let matrix = matrix_from_edges(4, &[(0, 1), (1, 2), (2, 3)]);
+    let square = &matrix * &matrix;
+    let expected = matrix_from_edges(4, &[(0, 2), (1, 3)]);
+
+    assert_eq!(square, expected);
+}
+
+#[test]
+fn test_multiply_identity() {
+    let matrix = matrix_from_edges(200, &[(0, 199), (150, 3), (199, 199)]);
+    let identity = BitMatrix::identity(200);
+
+    assert_eq!(&matrix * &identity, matrix);
+    assert_eq!(&identity * &matrix, matrix);
+}
+
+#[test]
+fn test_multiply_dimension_mismatch() {
+    let left = BitMatrix::new(2, 3);
+    let right = BitMatrix::new(2, 3);
+
+    assert!(left.multiply(&right).is_none());
+    assert_eq!(left.multiply(&right.transpose()).map(|m| (m.rows(), m.cols())), Some((2, 2)));
+}
+
+#[test]
+fn test_elementwise_ops() {
+    let matrix1 = matrix_from_edges(2, &[(0, 0), (0, 1)]);
+    let matrix2 = matrix_from_edges(2, &[(0, 1), (1, 1)]);
+
+    assert_eq!(&matrix1 & &matrix2, matrix_from_edges(2, &[(0, 1)]));
+    assert_eq!(&matrix1 | &matrix2, matrix_from_edges(2, &[(0, 0), (0, 1), (1, 1)]));
+    assert_eq!(&matrix1 ^ &matrix2, matrix_from_edges(2, &[(0, 0), (1, 1)]));
+    assert_eq!(!&matrix1, matrix_from_edges(2, &[(1, 0), (1, 1)]));
+}
+
+#[test]
+fn test_elementwise_methods() {
+    let matrix1 = matrix_from_edges(2, &[(0, 0), (0, 1)]);
+    let matrix2 = matrix_from_edges(2, &[(0, 1), (1, 1)]);
+
+    assert_eq!(matrix1.and(&matrix2), Some(&matrix1 & &matrix2));
+    assert_eq!(matrix1.or(&matrix2), Some(&matrix1 | &matrix2));
+    assert_eq!(matrix1.xor(&matrix2), Some(&matrix1 ^ &matrix2));
+}
+
+#[test]
+fn test_elementwise_methods_shape_mismatch() {
+    let left = BitMatrix::new(2, 3);
+    let right = BitMatrix::new(3, 2);
+
+    assert!(left.and(&right).is_none());
+    assert!(left.or(&right).is_none());
+    assert!(left.xor(&right).is_none());
+}
+
+#[test]
+#[should_panic(expected = "bit matrices must have the same shape")]
+fn test_elementwise_ops_shape_mismatch() {
+    let _ = &BitMatrix::new(2, 3) & &BitMatrix::new(3, 2);
+}
+
+#[test]
+fn test_display() {
+    let matrix = matrix_from_edges(3, &[(0, 0), (1, 2), (2, 1)]);
+
+    assert_eq!(matrix.to_string(), "100\n001\n010");
+}
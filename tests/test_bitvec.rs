@@ -0,0 +1,199 @@
+extern crate bitset;
+
+
+use bitset::{
+    BitSet,
+    BitVec,
+};
+
+
+#[test]
+fn test_new_is_empty() {
+    let bitvec = BitVec::new();
+
+    assert!(bitvec.is_empty());
+    assert_eq!(bitvec.len(), 0);
+    assert!(bitvec.none());
+    assert!(bitvec.all());
+}
+
+#[test]
+fn test_zeros() {
+    let bitvec = BitVec::zeros(257);
+
+    assert_eq!(bitvec.len(), 257);
+    assert_eq!(bitvec.count(), 0);
+    assert_eq!(bitvec.blocks().len(), 3);
+}
+
+#[test]
+fn test_ones() {
+    let bitvec = BitVec::ones(257);
+
+    assert_eq!(bitvec.count(), 257);
+    assert!(bitvec.all());
+    assert_eq!(bitvec.get(257), None);
+}
+
+#[test]
+fn test_set_and_get() {
+    let mut bitvec = BitVec::zeros(300);
+    bitvec.set(0, true);
+    bitvec.set(128, true);
+    bitvec.set(299, true);
+
+    assert_eq!(bitvec.get(0), Some(true));
+    assert_eq!(bitvec.get(1), Some(false));
+    assert_eq!(bitvec.get(128), Some(true));
+    assert_eq!(bitvec.get(299), Some(true));
+    assert_eq!(bitvec.set(300, true), None);
+    assert!(!bitvec.test(300));
+}
+
+#[test]
+fn test_flip() {
+    let mut bitvec = BitVec::zeros(130);
+    bitvec.flip(129);
+
+    assert_eq!(bitvec.get(129), Some(true));
+    bitvec.flip(129);
+    assert_eq!(bitvec.get(129), Some(false));
+    assert_eq!(bitvec.flip(130), None);
+}
+
+#[test]
+fn test_push_across_blocks() {
+    let mut bitvec = BitVec::new();
+    for i in 0..300 {
+        bitvec.push(i % 3 == 0);
+    }
+
+    assert_eq!(bitvec.len(), 300);
+    assert_eq!(bitvec.count(), 100);
+    assert!(bitvec.iter().all(|i| i % 3 == 0));
+}
+
+#[test]
+fn test_resize() {
+    let mut bitvec = BitVec::ones(200);
+    bitvec.resize(100, false);
+
+    assert_eq!(bitvec.len(), 100);
+    assert_eq!(bitvec.count(), 100);
+
+    bitvec.resize(300, true);
+    assert_eq!(bitvec.count(), 300);
+
+    bitvec.resize(400, false);
+    assert_eq!(bitvec.count(), 300);
+}
+
+#[test]
+fn test_from_blocks_discards_bits_past_length() {
+    let blocks = [BitSet::from_u128(!0), BitSet::from_u128(!0)];
+    let bitvec = BitVec::from_blocks(&blocks, 130);
+
+    assert_eq!(bitvec.count(), 130);
+    assert_eq!(bitvec, BitVec::ones(130));
+}
+
+#[test]
+fn test_from_bitset() {
+    let bitvec = BitVec::from(BitSet::from_u64(0b101));
+
+    assert_eq!(bitvec.len(), 128);
+    assert_eq!(bitvec.iter().collect::<Vec<_>>(), vec![0, 2]);
+}
+
+#[test]
+fn test_iter() {
+    let bitvec: BitVec = (0..400).map(|i| i == 5 || i == 127 || i == 128 || i == 399).collect();
+    let result: Vec<usize> = bitvec.iter().collect();
+
+    assert_eq!(result, vec![5, 127, 128, 399]);
+}
+
+#[test]
+fn test_not_keeps_length() {
+    let bitvec = BitVec::zeros(130);
+    let result = !&bitvec;
+
+    assert_eq!(result.len(), 130);
+    assert_eq!(result.count(), 130);
+    assert_eq!(!result, bitvec);
+}
+
+#[test]
+fn test_binary_ops() {
+    let bitvec1: BitVec = vec![true, true, false, false].into_iter().collect();
+    let bitvec2: BitVec = vec![true, false, true, false].into_iter().collect();
+
+    assert_eq!(&bitvec1 & &bitvec2, vec![true, false, false, false].into_iter().collect());
+    assert_eq!(&bitvec1 | &bitvec2, vec![true, true, true, false].into_iter().collect());
+    assert_eq!(&bitvec1 ^ &bitvec2, vec![false, true, true, false].into_iter().collect());
+}
+
+#[test]
+fn test_binary_ops_pad_shorter_operand() {
+    let short = BitVec::ones(10);
+    let long = BitVec::ones(200);
+    let and = &short & &long;
+    let or = &short | &long;
+
+    assert_eq!(and.len(), 200);
+    assert_eq!(and.count(), 10);
+    assert_eq!(or.count(), 200);
+}
+
+#[test]
+fn test_assign_ops() {
+    let mut result = BitVec::ones(10);
+    result &= BitVec::zeros(5);
+    assert_eq!(result.count(), 0);
+
+    result |= BitVec::ones(200);
+    assert_eq!(result.len(), 200);
+    assert_eq!(result.count(), 200);
+
+    result ^= BitVec::ones(100);
+    assert_eq!(result.count(), 100);
+}
+
+#[test]
+fn test_display() {
+    let bitvec: BitVec = vec![true, false, false, true, true].into_iter().collect();
+
+    assert_eq!(bitvec.to_string(), "10011");
+}
+
+#[test]
+fn test_shl_matches_naive() {
+    let bitvec: BitVec = (0..300).map(|i| i % 7 == 0 || i % 11 == 3).collect();
+    for &amount in [0, 1, 5, 127, 128, 129, 200, 299, 300, 1000].iter() {
+        let expected: BitVec = (0..300).map(|i| i >= amount && bitvec.test(i - amount)).collect();
+
+        assert_eq!(&bitvec << amount, expected, "amount {}", amount);
+    }
+}
+
+#[test]
+fn test_shr_matches_naive() {
+    let bitvec: BitVec = (0..300).map(|i| i % 7 == 0 || i % 11 == 3).collect();
+    for &amount in [0, 1, 5, 127, 128, 129, 200, 299, 300, 1000].iter() {
+        let expected: BitVec = (0..300).map(|i| bitvec.test(i + amount)).collect();
+
+        assert_eq!(&bitvec >> amount, expected, "amount {}", amount);
+    }
+}
+
+#[test]
+fn test_shift_assign_keeps_length() {
+    let mut bitvec = BitVec::ones(130);
+    bitvec <<= 129;
+
+    assert_eq!(bitvec.len(), 130);
+    assert_eq!(bitvec.iter().collect::<Vec<_>>(), vec![129]);
+
+    bitvec >>= 129;
+    assert_eq!(bitvec.iter().collect::<Vec<_>>(), vec![0]);
+}
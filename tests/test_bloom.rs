@@ -0,0 +1,202 @@
+extern crate bitset;
+
+
+use bitset::{
+    BlockedBloomFilter,
+    BloomFilter,
+    CountingBloomFilter,
+    InvalidBloomFilter,
+};
+
+
+#[test]
+fn test_no_false_negatives() {
+    let mut filter = BloomFilter::new(1000, 0.01);
+    for item in 0..1000_u32 {
+        filter.insert(&item);
+    }
+
+    assert!((0..1000_u32).all(|item| filter.contains(&item)));
+}
+
+#[test]
+fn test_false_positive_rate_is_near_target() {
+    let mut filter = BloomFilter::new(1000, 0.01);
+    for item in 0..1000_u32 {
+        filter.insert(&item);
+    }
+    let false_positives = (1000..101_000_u32).filter(|item| filter.contains(item)).count();
+
+    assert!(false_positives < 2000, "{} false positives", false_positives);
+}
+
+#[test]
+fn test_parameters() {
+    let filter = BloomFilter::new(1000, 0.01);
+
+    // About 9.6 bits per item and 7 hash functions for a 1% rate.
+    assert_eq!(filter.bit_len(), 9586);
+    assert_eq!(filter.hashes(), 7);
+    assert!(filter.is_empty());
+
+    let filter = BloomFilter::with_params(0, 0);
+
+    assert_eq!(filter.bit_len(), 1);
+    assert_eq!(filter.hashes(), 1);
+
+    let filter = BloomFilter::with_params(100, 1000);
+
+    assert_eq!(filter.hashes(), BloomFilter::MAX_HASHES);
+    assert_eq!(BloomFilter::new(10, 1e-300).hashes(), BloomFilter::MAX_HASHES);
+}
+
+#[test]
+fn test_insert_reports_new_items() {
+    let mut filter = BloomFilter::new(100, 0.001);
+
+    assert!(filter.insert("item"));
+    assert!(!filter.insert("item"));
+}
+
+#[test]
+fn test_union() {
+    let mut left = BloomFilter::new(100, 0.01);
+    let mut right = BloomFilter::new(100, 0.01);
+    left.extend(0..50_u32);
+    right.extend(50..100_u32);
+    let union = &left | &right;
+
+    assert!((0..100_u32).all(|item| union.contains(&item)));
+    assert_eq!(left.union(&right), Some(union.clone()));
+
+    left |= &right;
+    assert_eq!(left, union);
+}
+
+#[test]
+fn test_intersection() {
+    let mut left = BloomFilter::new(100, 0.01);
+    let mut right = BloomFilter::new(100, 0.01);
+    left.extend(0..60_u32);
+    right.extend(40..100_u32);
+    let intersection = &left & &right;
+
+    assert!((40..60_u32).all(|item| intersection.contains(&item)));
+    assert!(intersection.bits().count() <= left.bits().count());
+}
+
+#[test]
+fn test_incompatible_filters() {
+    let left = BloomFilter::new(100, 0.01);
+    let right = BloomFilter::new(200, 0.01);
+
+    assert!(left.union(&right).is_none());
+    assert!(left.intersection(&right).is_none());
+}
+
+#[test]
+#[should_panic]
+fn test_union_operator_rejects_incompatible_filters() {
+    let _ = &BloomFilter::new(100, 0.01) | &BloomFilter::new(200, 0.01);
+}
+
+#[test]
+fn test_estimated_len() {
+    let mut filter = BloomFilter::new(10_000, 0.01);
+    filter.extend(0..5000_u32);
+    let estimate = filter.estimated_len();
+
+    assert!((4750.0..5250.0).contains(&estimate), "estimate {}", estimate);
+    assert_eq!(BloomFilter::new(10, 0.01).estimated_len(), 0.0);
+}
+
+#[test]
+fn test_bytes_round_trip() {
+    let mut filter = BloomFilter::new(300, 0.05);
+    filter.extend(["a", "b", "c"].iter());
+    let bytes = filter.to_bytes();
+    let decoded = BloomFilter::from_bytes(&bytes).unwrap();
+
+    assert_eq!(decoded, filter);
+    assert_eq!(decoded.hashes(), filter.hashes());
+    assert!(decoded.contains(&"b"));
+}
+
+#[test]
+fn test_from_bytes_rejects_invalid_input() {
+    let filter = BloomFilter::with_params(12, 3);
+    let bytes = filter.to_bytes();
+
+    assert_eq!(bytes.len(), 12 + 2);
+    assert_eq!(BloomFilter::from_bytes(&bytes[..13]), Err(InvalidBloomFilter));
+    assert_eq!(BloomFilter::from_bytes(&bytes[..4]), Err(InvalidBloomFilter));
+
+    // A bit set past the length of the filter.
+    let mut stray = bytes.clone();
+    stray[13] = 0x80;
+    assert_eq!(BloomFilter::from_bytes(&stray), Err(InvalidBloomFilter));
+
+    let mut trailing = bytes.clone();
+    trailing.push(0);
+    assert_eq!(BloomFilter::from_bytes(&trailing), Err(InvalidBloomFilter));
+
+    let mut no_hashes = bytes.clone();
+    no_hashes[..4].copy_from_slice(&0_u32.to_le_bytes());
+    assert_eq!(BloomFilter::from_bytes(&no_hashes), Err(InvalidBloomFilter));
+
+    let mut most_hashes = bytes.clone();
+    most_hashes[..4].copy_from_slice(&(BloomFilter::MAX_HASHES as u32).to_le_bytes());
+    assert_eq!(BloomFilter::from_bytes(&most_hashes).map(|filter| filter.hashes()), Ok(BloomFilter::MAX_HASHES));
+
+    let mut too_many_hashes = bytes;
+    too_many_hashes[..4].copy_from_slice(&(BloomFilter::MAX_HASHES as u32 + 1).to_le_bytes());
+    assert_eq!(BloomFilter::from_bytes(&too_many_hashes), Err(InvalidBloomFilter));
+}
+
+#[test]
+fn test_blocked_filter() {
+    let mut filter = BlockedBloomFilter::new(1000, 0.01);
+    for item in 0..1000_u32 {
+        filter.insert(&item);
+    }
+    let false_positives = (1000..101_000_u32).filter(|item| filter.contains(item)).count();
+
+    assert_eq!(filter.bit_len() % 512, 0);
+    assert!((0..1000_u32).all(|item| filter.contains(&item)));
+    assert!(false_positives < 3000, "{} false positives", false_positives);
+
+    filter.clear();
+    assert!(filter.bits().none());
+}
+
+#[test]
+fn test_counting_filter_remove() {
+    let mut filter = CountingBloomFilter::new(100, 0.01);
+    for item in 0..100_u32 {
+        filter.insert(&item);
+    }
+    for item in 0..50_u32 {
+        assert!(filter.remove(&item));
+    }
+
+    assert!((50..100_u32).all(|item| filter.contains(&item)));
+
+    for item in 50..100_u32 {
+        assert!(filter.remove(&item));
+    }
+    assert!((0..100_u32).all(|item| !filter.contains(&item)));
+    assert!(!filter.remove(&0_u32));
+}
+
+#[test]
+fn test_counting_filter_to_bloom_filter() {
+    let mut counting = CountingBloomFilter::new(100, 0.01);
+    let mut plain = BloomFilter::new(100, 0.01);
+    for item in 0..40_u32 {
+        counting.insert(&item);
+        plain.insert(&item);
+    }
+
+    assert_eq!(counting.counter_len(), plain.bit_len());
+    assert_eq!(counting.to_bloom_filter(), plain);
+}
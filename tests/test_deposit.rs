@@ -0,0 +1,126 @@
+extern crate bitset;
+
+
+use bitset::{
+    BitSet,
+};
+
+
+/// Gather the bits selected by a mask by testing every position.
+fn naive_extract(value: &BitSet, mask: &BitSet) -> BitSet {
+    mask.iter()
+        .enumerate()
+        .filter(|&(_, position)| value.test(position))
+        .map(|(target, _)| target)
+        .collect()
+}
+
+/// Scatter the low bits to the positions of a mask by testing every position.
+fn naive_deposit(value: &BitSet, mask: &BitSet) -> BitSet {
+    mask.iter()
+        .enumerate()
+        .filter(|&(source, _)| value.test(source))
+        .map(|(_, position)| position)
+        .collect()
+}
+
+fn samples() -> Vec<BitSet> {
+    let mut state: u128 = 0x9E37_79B9_7F4A_7C15_F39C_C060_5CED_C834;
+    let mut samples = vec![
+        BitSet::new(),
+        !BitSet::new(),
+        BitSet::from_u128(1),
+        BitSet::from_u128(1 << 127),
+        BitSet::from_u128(0xFFFF_FFFF_FFFF_FFFF),
+        BitSet::from_u128(0xFFFF_FFFF_FFFF_FFFF_0000_0000_0000_0000),
+        BitSet::from_u128(0xAAAA_AAAA_AAAA_AAAA_AAAA_AAAA_AAAA_AAAA),
+    ];
+    for _ in 0..32 {
+        state = state.wrapping_mul(0x2360_ED05_1FC6_5DA4_4385_DF64_9FCC_F645).wrapping_add(1);
+        samples.push(BitSet::from_u128(state));
+        samples.push(BitSet::from_u128(state & state.rotate_left(41)));
+    }
+
+    samples
+}
+
+#[test]
+fn test_extract_matches_naive() {
+    let samples = samples();
+    for value in samples.iter() {
+        for mask in samples.iter() {
+            assert_eq!(value.extract(mask), naive_extract(value, mask), "{} {}", value, mask);
+        }
+    }
+}
+
+#[test]
+fn test_deposit_matches_naive() {
+    let samples = samples();
+    for value in samples.iter() {
+        for mask in samples.iter() {
+            assert_eq!(value.deposit(mask), naive_deposit(value, mask), "{} {}", value, mask);
+        }
+    }
+}
+
+#[test]
+fn test_portable_paths_match_naive() {
+    let samples = samples();
+    for value in samples.iter() {
+        for mask in samples.iter() {
+            assert_eq!(value.extract_portable(mask), naive_extract(value, mask), "{} {}", value, mask);
+            assert_eq!(value.deposit_portable(mask), naive_deposit(value, mask), "{} {}", value, mask);
+        }
+    }
+}
+
+#[test]
+fn test_portable_paths_match_dispatched_paths() {
+    let masks = [
+        BitSet::new(),
+        !BitSet::new(),
+        BitSet::from_u128(0xAAAA_AAAA_AAAA_AAAA_AAAA_AAAA_AAAA_AAAA),
+        BitSet::from_u128(0x5555_5555_5555_5555_5555_5555_5555_5555),
+        BitSet::from_u128((1 << 64) | (1 << 63)),
+        BitSet::from_u128((1 << 127) | 1),
+    ];
+    for value in samples().iter() {
+        for mask in masks.iter() {
+            assert_eq!(value.extract_portable(mask), value.extract(mask), "{} {}", value, mask);
+            assert_eq!(value.extract_portable(mask), naive_extract(value, mask), "{} {}", value, mask);
+            assert_eq!(value.deposit_portable(mask), value.deposit(mask), "{} {}", value, mask);
+            assert_eq!(value.deposit_portable(mask), naive_deposit(value, mask), "{} {}", value, mask);
+        }
+    }
+}
+
+#[test]
+fn test_deposit_inverts_extract() {
+    let samples = samples();
+    for value in samples.iter() {
+        for mask in samples.iter() {
+            assert_eq!(value.extract(mask).deposit(mask), value & mask);
+        }
+    }
+}
+
+#[test]
+fn test_full_and_empty_masks() {
+    let value = BitSet::from_u128(0x0123_4567_89AB_CDEF_FEDC_BA98_7654_3210);
+
+    assert_eq!(value.extract(&!BitSet::new()), value);
+    assert_eq!(value.deposit(&!BitSet::new()), value);
+    assert_eq!(value.extract(&BitSet::new()), BitSet::new());
+    assert_eq!(value.deposit(&BitSet::new()), BitSet::new());
+}
+
+#[test]
+fn test_extract_across_halves() {
+    let value = BitSet::from_u128((1 << 65) | (1 << 3));
+    let mask = BitSet::from_u128((0b111 << 63) | (1 << 3));
+
+    // The mask selects positions 3, 63, 64 and 65.
+    assert_eq!(value.extract(&mask), BitSet::from_u64(0b1001));
+    assert_eq!(BitSet::from_u64(0b1001).deposit(&mask), value);
+}
@@ -0,0 +1,165 @@
+extern crate bitset;
+
+
+use bitset::{
+    BitSet,
+    BitVec,
+    InconsistentSystem,
+    XorBasis,
+    gf2_nullspace,
+    gf2_rank,
+    gf2_row_reduce,
+    gf2_solve,
+};
+
+
+/// Multiply a matrix by a vector over GF(2).
+fn multiply(rows: &[BitSet], vector: &BitSet) -> BitVec {
+    rows.iter()
+        .map(|row| (row & vector).count() % 2 == 1)
+        .collect()
+}
+
+fn sample_rows() -> Vec<BitSet> {
+    vec![
+        BitSet::from_u128(0x8000_0000_0000_0000_0000_0000_0000_0013),
+        BitSet::from_u128(0x0000_0000_0000_0001_0000_0000_0000_0006),
+        BitSet::from_u128(0x8000_0000_0000_0001_0000_0000_0000_0015),
+        BitSet::from_u128(0x0000_0000_0000_0000_0000_0000_0000_0108),
+    ]
+}
+
+
+#[test]
+fn test_row_reduce_identity() {
+    let mut rows = [BitSet::from_u64(0b01), BitSet::from_u64(0b10)];
+    let rank = gf2_row_reduce(&mut rows);
+
+    assert_eq!(rank, 2);
+    assert_eq!(rows, [BitSet::from_u64(0b01), BitSet::from_u64(0b10)]);
+}
+
+#[test]
+fn test_row_reduce_is_reduced() {
+    let mut rows = sample_rows();
+    let rank = gf2_row_reduce(&mut rows);
+    let leading: Vec<usize> = rows[..rank].iter().map(|row| row.iter().next().unwrap()).collect();
+
+    for (i, &col) in leading.iter().enumerate() {
+        for (j, row) in rows.iter().enumerate() {
+            assert_eq!(row.test(col), i == j);
+        }
+    }
+    assert!(leading.windows(2).all(|pair| pair[0] < pair[1]));
+    assert!(rows[rank..].iter().all(|row| row.none()));
+}
+
+#[test]
+fn test_rank() {
+    assert_eq!(gf2_rank(&[]), 0);
+    assert_eq!(gf2_rank(&[BitSet::new()]), 0);
+    assert_eq!(gf2_rank(&sample_rows()), 3);
+}
+
+#[test]
+fn test_solve() {
+    let rows = sample_rows();
+    let expected_rhs = multiply(&rows, &BitSet::from_u128(0x8000_0000_0000_0000_0000_0000_0000_0101));
+    let solution = gf2_solve(&rows, &expected_rhs).unwrap();
+
+    assert_eq!(multiply(&rows, &solution), expected_rhs);
+}
+
+#[test]
+fn test_solve_inconsistent() {
+    // The third row is the sum of the first two, but its right hand side is not.
+    let rows = sample_rows();
+    let rhs: BitVec = vec![false, false, true, false].into_iter().collect();
+
+    assert_eq!(gf2_solve(&rows, &rhs), Err(InconsistentSystem));
+}
+
+#[test]
+fn test_solve_more_than_128_equations() {
+    // Every equation appears twice, 150 rows apart.
+    let rows: Vec<BitSet> = (0..300).map(|i| BitSet::from_u128(1 << (i % 150 % 128))).collect();
+    let solution = BitSet::from_u128(0x0123_4567_89AB_CDEF_FEDC_BA98_7654_3210);
+    let mut rhs = multiply(&rows, &solution);
+
+    assert_eq!(gf2_solve(&rows, &rhs), Ok(solution));
+
+    rhs.flip(290);
+
+    assert_eq!(gf2_solve(&rows, &rhs), Err(InconsistentSystem));
+}
+
+#[test]
+#[should_panic]
+fn test_solve_mismatched_rhs() {
+    let _ = gf2_solve(&sample_rows(), &BitVec::zeros(3));
+}
+
+#[test]
+fn test_nullspace() {
+    let rows = sample_rows();
+    let basis = gf2_nullspace(&rows, 128);
+
+    assert_eq!(basis.len(), 128 - gf2_rank(&rows));
+    assert_eq!(gf2_rank(&basis), basis.len());
+    for vector in basis.iter() {
+        assert!(multiply(&rows, vector).none());
+    }
+}
+
+#[test]
+fn test_nullspace_of_empty_matrix() {
+    let basis = gf2_nullspace(&[], 3);
+
+    assert_eq!(basis, vec![BitSet::from_u64(1), BitSet::from_u64(2), BitSet::from_u64(4)]);
+}
+
+#[test]
+#[should_panic]
+fn test_nullspace_with_too_many_columns() {
+    let _ = gf2_nullspace(&[], 129);
+}
+
+#[test]
+fn test_xor_basis_insert() {
+    let mut basis = XorBasis::new();
+
+    assert!(basis.is_empty());
+    assert!(!basis.insert(BitSet::new()));
+    assert!(basis.insert(BitSet::from_u64(0b101)));
+    assert!(basis.insert(BitSet::from_u64(0b011)));
+    assert!(!basis.insert(BitSet::from_u64(0b110)));
+    assert_eq!(basis.rank(), 2);
+    assert_eq!(basis.iter().count(), 2);
+}
+
+#[test]
+fn test_xor_basis_contains_span() {
+    let mut basis = XorBasis::new();
+    for row in sample_rows() {
+        basis.insert(row);
+    }
+
+    for row in sample_rows() {
+        assert!(basis.contains_span(&row));
+    }
+    assert!(basis.contains_span(&(sample_rows()[0] ^ sample_rows()[3])));
+    assert!(!basis.contains_span(&BitSet::from_u64(1 << 40)));
+}
+
+#[test]
+fn test_xor_basis_reduce() {
+    let mut basis = XorBasis::new();
+    basis.insert(BitSet::from_u64(0b1100));
+    basis.insert(BitSet::from_u64(0b0110));
+    let vector = BitSet::from_u64(0b1011);
+    let reduced = basis.reduce(&vector);
+
+    assert!(!reduced.test(3));
+    assert!(!reduced.test(2));
+    assert!(basis.contains_span(&(reduced ^ vector)));
+}
@@ -0,0 +1,132 @@
+extern crate bitset;
+
+
+use bitset::{
+    BitMatrix,
+};
+
+
+fn graph_from_edges(size: usize, edges: &[(usize, usize)]) -> BitMatrix {
+    let mut graph = BitMatrix::new(size, size);
+    for &(from, to) in edges {
+        graph.set(from, to, true);
+    }
+
+    graph
+}
+
+/// Compute the transitive closure by repeated squaring, for comparison.
+fn naive_closure(graph: &BitMatrix) -> BitMatrix {
+    let mut closure = graph.clone();
+    loop {
+        let next = &closure | &(&closure * graph);
+        if next == closure {
+            return closure;
+        }
+        closure = next;
+    }
+}
+
+
+#[test]
+fn test_transitive_closure_chain() {
+    let graph = graph_from_edges(4, &[(0, 1), (1, 2), (2, 3)]);
+    let closure = graph.transitive_closure();
+    let expected = graph_from_edges(4, &[(0, 1), (0, 2), (0, 3), (1, 2), (1, 3), (2, 3)]);
+
+    assert_eq!(closure, expected);
+}
+
+#[test]
+fn test_transitive_closure_cycle_is_reflexive_on_cycle() {
+    let graph = graph_from_edges(3, &[(0, 1), (1, 0)]);
+    let closure = graph.transitive_closure();
+
+    assert_eq!(closure.get(0, 0), Some(true));
+    assert_eq!(closure.get(1, 1), Some(true));
+    assert_eq!(closure.get(2, 2), Some(false));
+}
+
+#[test]
+fn test_transitive_closure_matches_naive() {
+    let edges: Vec<(usize, usize)> = (0..300)
+        .map(|i| (i, (i * 7 + 3) % 300))
+        .filter(|&(i, j)| i % 5 != 0 && i != j)
+        .collect();
+    let graph = graph_from_edges(300, &edges);
+
+    assert_eq!(graph.transitive_closure(), naive_closure(&graph));
+}
+
+#[test]
+fn test_reflexive_transitive_closure() {
+    let graph = graph_from_edges(3, &[(0, 1)]);
+    let closure = graph.reflexive_transitive_closure();
+    let expected = graph_from_edges(3, &[(0, 0), (0, 1), (1, 1), (2, 2)]);
+
+    assert_eq!(closure, expected);
+}
+
+#[test]
+fn test_reachable_from() {
+    let graph = graph_from_edges(200, &[(0, 150), (150, 199), (199, 3), (4, 0)]);
+    let reachable = graph.reachable_from(0).unwrap();
+
+    assert_eq!(reachable.iter().collect::<Vec<_>>(), vec![0, 3, 150, 199]);
+    assert!(graph.reachable_from(200).is_none());
+}
+
+#[test]
+fn test_reachable_from_agrees_with_closure() {
+    let graph = graph_from_edges(10, &[(0, 1), (1, 2), (2, 0), (3, 4), (5, 9), (9, 5)]);
+    let closure = graph.reflexive_transitive_closure();
+
+    for node in 0..10 {
+        assert_eq!(graph.reachable_from(node).as_ref(), closure.row(node));
+    }
+}
+
+#[test]
+fn test_has_cycle() {
+    let acyclic = graph_from_edges(3, &[(0, 1), (0, 2), (1, 2)]);
+    let cyclic = graph_from_edges(3, &[(0, 1), (1, 2), (2, 1)]);
+    let self_loop = graph_from_edges(3, &[(1, 1)]);
+
+    assert!(!acyclic.has_cycle());
+    assert!(cyclic.has_cycle());
+    assert!(self_loop.has_cycle());
+}
+
+#[test]
+fn test_topological_order() {
+    let graph = graph_from_edges(5, &[(3, 1), (1, 0), (4, 0), (2, 4)]);
+    let order = graph.topological_order().unwrap();
+
+    assert_eq!(order, vec![2, 3, 1, 4, 0]);
+}
+
+#[test]
+fn test_topological_order_respects_edges() {
+    let edges: Vec<(usize, usize)> = (0..250)
+        .flat_map(|i| vec![(i, (i * 3 + 1) % 250), (i, (i + 17) % 250)])
+        .filter(|&(i, j)| i < j)
+        .collect();
+    let graph = graph_from_edges(250, &edges);
+    let order = graph.topological_order().unwrap();
+    let mut position = vec![0; 250];
+    for (index, &node) in order.iter().enumerate() {
+        position[node] = index;
+    }
+
+    for &(from, to) in edges.iter() {
+        assert!(position[from] < position[to]);
+    }
+}
+
+#[test]
+fn test_topological_order_reports_cycle_nodes() {
+    let graph = graph_from_edges(6, &[(0, 1), (1, 2), (2, 1), (2, 3), (4, 4), (3, 5)]);
+    let error = graph.topological_order().unwrap_err();
+
+    assert_eq!(error.nodes().iter().collect::<Vec<_>>(), vec![1, 2, 4]);
+}
@@ -0,0 +1,98 @@
+extern crate bitset;
+
+
+use bitset::{
+    BitSet,
+    HammingIndex,
+};
+
+
+/// Generate pseudo-random fingerprints with a linear congruential generator,
+/// in clusters of near duplicates.
+fn fingerprints(count: usize, seed: u64) -> Vec<BitSet> {
+    let mut state = seed;
+    let mut next = move || {
+        state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        state ^ (state >> 29)
+    };
+    let mut result = Vec::with_capacity(count);
+    while result.len() < count {
+        let center = ((next() as u128) << 64) | next() as u128;
+        for _ in 0..4 {
+            let noise = 1_u128 << (next() % 128);
+            result.push(BitSet::from_u128(center ^ noise));
+        }
+    }
+    result.truncate(count);
+
+    result
+}
+
+/// Rank the fingerprints by distance from the query, then by id.
+fn brute_force(fingerprints: &[Option<BitSet>], query: &BitSet) -> Vec<(usize, usize)> {
+    let mut ranked: Vec<(usize, usize)> = fingerprints.iter()
+        .enumerate()
+        .filter_map(|(id, fingerprint)| fingerprint.map(|f| (id, f.hamming_distance(query))))
+        .collect();
+    ranked.sort_unstable_by_key(|&(id, distance)| (distance, id));
+
+    ranked
+}
+
+#[test]
+fn test_queries_match_brute_force() {
+    let data = fingerprints(400, 1);
+    let index = HammingIndex::build(&data);
+    let all: Vec<Option<BitSet>> = data.iter().copied().map(Some).collect();
+
+    assert_eq!(index.len(), 400);
+    for query in fingerprints(20, 2).iter().chain(data.iter().take(5)) {
+        let ranked = brute_force(&all, query);
+        for &k in [1, 3, 10].iter() {
+            assert_eq!(index.nearest(query, k), ranked[..k].to_vec());
+        }
+        for &radius in [0, 2, 60].iter() {
+            let expected: Vec<_> = ranked.iter().copied().filter(|&(_, d)| d <= radius).collect();
+            assert_eq!(index.within(query, radius), expected);
+        }
+    }
+}
+
+#[test]
+fn test_remove() {
+    let data = fingerprints(200, 3);
+    let mut index: HammingIndex = data.iter().copied().collect();
+    let mut all: Vec<Option<BitSet>> = data.iter().copied().map(Some).collect();
+    for id in (0..200).filter(|id| id % 3 != 0) {
+        assert_eq!(index.remove(id), Some(data[id]));
+        all[id] = None;
+    }
+
+    assert_eq!(index.remove(1), None);
+    assert_eq!(index.get(1), None);
+    assert_eq!(index.get(3), Some(&data[3]));
+    assert_eq!(index.len(), 67);
+    for query in fingerprints(10, 4).iter() {
+        let ranked = brute_force(&all, query);
+        assert_eq!(index.nearest(query, 5), ranked[..5].to_vec());
+        let expected: Vec<_> = ranked.iter().copied().filter(|&(_, d)| d <= 50).collect();
+        assert_eq!(index.within(query, 50), expected);
+    }
+}
+
+#[test]
+fn test_duplicates_and_reinsert() {
+    let fingerprint = BitSet::from_u64(0xABCD);
+    let mut index = HammingIndex::new();
+    let first = index.insert(fingerprint);
+    let second = index.insert(fingerprint);
+
+    assert_eq!(index.within(&fingerprint, 0), vec![(first, 0), (second, 0)]);
+    index.remove(first);
+    index.remove(second);
+    assert!(index.is_empty());
+    assert!(index.nearest(&fingerprint, 3).is_empty());
+    let third = index.insert(fingerprint);
+    assert_eq!(third, 2);
+    assert_eq!(index.nearest(&fingerprint, 3), vec![(third, 0)]);
+}
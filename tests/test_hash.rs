@@ -0,0 +1,83 @@
+extern crate bitset;
+
+
+use bitset::{
+    StableHasher,
+};
+use std::hash::{
+    Hasher,
+};
+
+
+/// Hash the writes made by `write` on a new hasher.
+fn stable_hash<F>(write: F) -> u64 where F: FnOnce(&mut StableHasher) {
+    let mut hasher = StableHasher::new();
+    write(&mut hasher);
+
+    hasher.finish()
+}
+
+#[test]
+fn test_known_hashes() {
+    let bytes: Vec<u8> = (0..15).collect();
+
+    assert_eq!(StableHasher::new().finish(), 0xD1FB_A762_150C_532C);
+    assert_eq!(stable_hash(|hasher| hasher.write_u64(42)), 0x7B3E_724B_36EB_DF51);
+    assert_eq!(stable_hash(|hasher| hasher.write(b"alpha")), 0x58F0_F39F_63F3_CF42);
+    assert_eq!(stable_hash(|hasher| hasher.write(&bytes)), 0xF30E_B725_BB91_C9EA);
+    assert_eq!(
+        stable_hash(|hasher| {
+            hasher.write_u8(1);
+            hasher.write_u16(2);
+            hasher.write_u32(3);
+            hasher.write_i64(-4);
+            hasher.write_usize(5);
+        }),
+        0x9DD7_696E_3D82_F65B
+    );
+    assert_eq!(
+        stable_hash(|hasher| hasher.write_u128(0x0123_4567_89AB_CDEF_FEDC_BA98_7654_3210)),
+        0x56A3_751D_AE54_8740
+    );
+}
+
+#[test]
+fn test_integers_hash_as_little_endian_bytes() {
+    assert_eq!(
+        stable_hash(|hasher| hasher.write_u32(0x0403_0201)),
+        stable_hash(|hasher| hasher.write(&[1, 2, 3, 4]))
+    );
+    assert_eq!(
+        stable_hash(|hasher| hasher.write_usize(7)),
+        stable_hash(|hasher| hasher.write_u64(7))
+    );
+    assert_eq!(
+        stable_hash(|hasher| hasher.write_isize(-7)),
+        stable_hash(|hasher| hasher.write_i64(-7))
+    );
+}
+
+#[test]
+fn test_split_writes() {
+    let bytes: Vec<u8> = (0..40).collect();
+    let mut whole = StableHasher::new();
+    whole.write(&bytes);
+    for split in 0..bytes.len() {
+        let mut parts = StableHasher::new();
+        parts.write(&bytes[..split]);
+        parts.write(&bytes[split..]);
+
+        assert_eq!(parts.finish(), whole.finish(), "split at {}", split);
+    }
+}
+
+#[test]
+fn test_finish_does_not_consume() {
+    let mut hasher = StableHasher::new();
+    hasher.write_u32(7);
+    let first = hasher.finish();
+
+    assert_eq!(hasher.finish(), first);
+    hasher.write_u32(7);
+    assert_ne!(hasher.finish(), first);
+}
@@ -0,0 +1,151 @@
+extern crate bitset;
+
+
+use bitset::{
+    HierarchicalBitSet,
+    LayeredBitSet,
+};
+
+
+#[test]
+fn test_new_is_empty() {
+    let bitset = HierarchicalBitSet::new();
+
+    assert!(bitset.is_empty());
+    assert_eq!(bitset.count(), 0);
+    assert_eq!(bitset.iter().next(), None);
+}
+
+#[test]
+fn test_insert_contains() {
+    let mut bitset = HierarchicalBitSet::new();
+    bitset.insert(0);
+    bitset.insert(127);
+    bitset.insert(128);
+    bitset.insert(1 << 24);
+
+    assert!(bitset.contains(0));
+    assert!(bitset.contains(127));
+    assert!(bitset.contains(128));
+    assert!(bitset.contains(1 << 24));
+    assert!(!bitset.contains(1));
+    assert!(!bitset.contains((1 << 24) + 1));
+}
+
+#[test]
+fn test_insert_out_of_bounds() {
+    let mut bitset = HierarchicalBitSet::new();
+
+    assert_eq!(bitset.insert(bitset.capacity()), None);
+    assert!(!bitset.contains(bitset.capacity()));
+}
+
+#[test]
+fn test_insert_twice() {
+    let mut bitset = HierarchicalBitSet::new();
+
+    assert_eq!(bitset.insert(0), Some(true));
+    assert_eq!(bitset.insert(0), Some(false));
+    assert_eq!(bitset.count(), 1);
+}
+
+#[test]
+fn test_remove_clears_summaries() {
+    let mut bitset = HierarchicalBitSet::new();
+    bitset.insert(5_000_000);
+
+    assert!(bitset.remove(5_000_000));
+    assert!(bitset.is_empty());
+    assert_eq!(bitset.first_one(), None);
+    assert_eq!(bitset, HierarchicalBitSet::new());
+}
+
+#[test]
+fn test_iter_in_increasing_order() {
+    let positions = vec![16_000_000, 3, 1 << 20, 130, 129, 0];
+    let bitset: HierarchicalBitSet = positions.iter().copied().collect();
+    let mut expected = positions;
+    expected.sort_unstable();
+    let result: Vec<usize> = bitset.iter().collect();
+
+    assert_eq!(result, expected);
+}
+
+#[test]
+fn test_next_one_after() {
+    let bitset: HierarchicalBitSet = vec![10, 20_000, 10_000_000].into_iter().collect();
+
+    assert_eq!(bitset.first_one(), Some(10));
+    assert_eq!(bitset.next_one_after(0), Some(10));
+    assert_eq!(bitset.next_one_after(10), Some(20_000));
+    assert_eq!(bitset.next_one_after(20_000), Some(10_000_000));
+    assert_eq!(bitset.next_one_after(10_000_000), None);
+    assert_eq!(bitset.next_one_after(usize::MAX), None);
+}
+
+#[test]
+fn test_join_and() {
+    let bitset1: HierarchicalBitSet = vec![1, 200, 70_000, 9_000_000].into_iter().collect();
+    let bitset2: HierarchicalBitSet = vec![2, 200, 70_001, 9_000_000].into_iter().collect();
+    let result: Vec<usize> = (&bitset1 & &bitset2).iter().collect();
+
+    assert_eq!(result, vec![200, 9_000_000]);
+}
+
+#[test]
+fn test_join_and_summaries_overlap_without_common_bits() {
+    let bitset1: HierarchicalBitSet = vec![0, 1 << 21].into_iter().collect();
+    let bitset2: HierarchicalBitSet = vec![1, (1 << 21) + 5].into_iter().collect();
+    let join = &bitset1 & &bitset2;
+
+    assert_eq!(join.first_one(), None);
+}
+
+#[test]
+fn test_join_or() {
+    let bitset1: HierarchicalBitSet = vec![1, 70_000].into_iter().collect();
+    let bitset2: HierarchicalBitSet = vec![2, 70_000, 9_000_000].into_iter().collect();
+    let result: Vec<usize> = (&bitset1 | &bitset2).iter().collect();
+
+    assert_eq!(result, vec![1, 2, 70_000, 9_000_000]);
+}
+
+#[test]
+fn test_join_and_not() {
+    let bitset1: HierarchicalBitSet = (0..300).collect();
+    let bitset2: HierarchicalBitSet = (1..300).collect();
+    let result: Vec<usize> = (&bitset1 & !&bitset2).iter().collect();
+
+    assert_eq!(result, vec![0]);
+}
+
+#[test]
+fn test_join_not_skips_full_words() {
+    let bitset: HierarchicalBitSet = (0..1000).collect();
+    let not = !&bitset;
+
+    assert_eq!(not.first_one(), Some(1000));
+    assert!(!not.contains(999));
+    assert!(!not.contains(bitset.capacity()));
+}
+
+#[test]
+fn test_join_nested() {
+    let bitset1: HierarchicalBitSet = (0..100).collect();
+    let bitset2: HierarchicalBitSet = (50..150).collect();
+    let bitset3: HierarchicalBitSet = (0..100).filter(|i| i % 2 == 0).collect();
+    let result: Vec<usize> = (&bitset1 & &bitset2 & !&bitset3).iter().collect();
+    let expected: Vec<usize> = (50..100).filter(|i| i % 2 == 1).collect();
+
+    assert_eq!(result, expected);
+}
+
+#[test]
+fn test_join_matches_naive_filter() {
+    let bitset1: HierarchicalBitSet = (0..50_000).filter(|i| i % 3 == 0).collect();
+    let bitset2: HierarchicalBitSet = (0..50_000).filter(|i| i % 5 == 0).collect();
+    let result: Vec<usize> = (&bitset1 & &bitset2).iter().collect();
+    let expected: Vec<usize> = (0..50_000).filter(|i| i % 15 == 0).collect();
+
+    assert_eq!(result, expected);
+}
@@ -1,3 +1,5 @@
+#![allow(clippy::bool_assert_comparison, clippy::op_ref)]
+
 extern crate bitset;
 
 
@@ -592,6 +594,25 @@ fn test_bitset_shr_capacity() {
     assert_eq!(result, expected);
 }
 
+#[test]
+fn test_bitset_shift_by_capacity_or_more() {
+    let bitset = BitSet::from_u128(0xFFFF_FFFF_FFFF_FFFF_FFFF_FFFF_FFFF_FFFF);
+    for &amount in [128, 129, 200, usize::MAX].iter() {
+        assert_eq!(bitset << amount, BitSet::new());
+        assert_eq!(bitset >> amount, BitSet::new());
+        assert_eq!(&bitset << amount, BitSet::new());
+        assert_eq!(&bitset >> amount, BitSet::new());
+
+        let mut result = bitset;
+        result <<= amount;
+        assert_eq!(result, BitSet::new());
+
+        let mut result = bitset;
+        result >>= amount;
+        assert_eq!(result, BitSet::new());
+    }
+}
+
 #[test]
 fn test_bitset_and_assign() {
     let bitset = BitSet::from_u64(0xDEAD_0000);
@@ -650,3 +671,41 @@ fn test_bitset_shr_assign() {
     assert_eq!(bitset, BitSet::from_u64(0x0000_DEAD));
 }
 
+
+#[test]
+fn test_bitset_iter() {
+    let bitset = BitSet::from_u128(0x8000_0000_0000_0001_0000_0000_0000_0102);
+    let result: Vec<usize> = bitset.iter().collect();
+
+    assert_eq!(result, vec![1, 8, 64, 127]);
+}
+
+#[test]
+fn test_bitset_iter_empty() {
+    let bitset = BitSet::new();
+
+    assert_eq!(bitset.iter().next(), None);
+}
+
+#[test]
+fn test_bitset_iter_rev() {
+    let bitset = BitSet::from_u128(0x8000_0000_0000_0001_0000_0000_0000_0102);
+    let result: Vec<usize> = bitset.iter().rev().collect();
+
+    assert_eq!(result, vec![127, 64, 8, 1]);
+}
+
+#[test]
+fn test_bitset_iter_len() {
+    let bitset = BitSet::from_u64(0xDEAD_BEEF);
+
+    assert_eq!(bitset.iter().len(), bitset.count());
+}
+
+#[test]
+fn test_bitset_from_iter() {
+    let bitset: BitSet = vec![0, 3, 127, 128].into_iter().collect();
+    let expected = BitSet::from_u128((1 << 127) | 0b1001);
+
+    assert_eq!(bitset, expected);
+}
@@ -0,0 +1,100 @@
+extern crate bitset;
+
+
+use bitset::{
+    BitSet,
+    BitVec,
+    LshIndex,
+    MinHasher,
+};
+
+
+#[test]
+fn test_signatures_are_deterministic() {
+    let set: BitSet = [3, 17, 90, 127].iter().copied().collect();
+    let first = MinHasher::new(64, 1);
+    let second = MinHasher::new(64, 1);
+    let other_seed = MinHasher::new(64, 2);
+
+    assert_eq!(first.hashes(), 64);
+    assert_eq!(first.bitset_signature(&set), second.bitset_signature(&set));
+    assert_ne!(first.bitset_signature(&set), other_seed.bitset_signature(&set));
+    assert_eq!(first.bitset_signature(&set), first.signature(vec![127, 90, 17, 3]));
+}
+
+#[test]
+fn test_signatures_are_stable() {
+    let signature = MinHasher::new(3, 1).signature(vec![1, 2, 3]);
+
+    assert_eq!(signature.values(), &[0x558D_D6FF_CD6B_1FA6, 0x9FFD_FB32_9572_049B, 0x5384_22B1_FAD5_343C]);
+}
+
+#[test]
+fn test_signature_of_empty_set() {
+    let hasher = MinHasher::new(16, 0);
+    let signature = hasher.bitset_signature(&BitSet::new());
+
+    assert!(signature.values().iter().all(|&value| value == u64::MAX));
+    assert_eq!(signature.estimated_jaccard(&hasher.signature(vec![1])), Some(0.0));
+}
+
+#[test]
+fn test_estimated_jaccard() {
+    let hasher = MinHasher::new(512, 3);
+    let a: BitVec = (0..2000).map(|i| i < 1000).collect();
+    let b: BitVec = (0..2000).map(|i| (250..1250).contains(&i)).collect();
+    let estimate = hasher.bitvec_signature(&a)
+        .estimated_jaccard(&hasher.bitvec_signature(&b))
+        .unwrap();
+
+    // The exact similarity is 750 / 1250.
+    assert_eq!(a.jaccard_similarity(&b), 0.6);
+    assert!((estimate - 0.6).abs() < 0.08);
+    assert_eq!(hasher.bitvec_signature(&a).estimated_jaccard(&MinHasher::new(8, 3).bitvec_signature(&a)), None);
+}
+
+#[test]
+fn test_lsh_parameters() {
+    let index = LshIndex::with_threshold(100, 0.5).unwrap();
+
+    assert!(index.bands() * index.rows() <= 100);
+    assert!((index.threshold() - 0.5).abs() < 0.05);
+    assert!(LshIndex::new(0, 4).is_none());
+    assert!(LshIndex::with_threshold(0, 0.5).is_none());
+    assert!(LshIndex::with_threshold(100, 1.5).is_none());
+}
+
+#[test]
+fn test_lsh_finds_near_duplicates() {
+    let hasher = MinHasher::new(128, 11);
+    let mut index = LshIndex::new(32, 4).unwrap();
+    // Groups of three near duplicates, each group far from the others.
+    let sets: Vec<BitVec> = (0..30)
+        .map(|i| {
+            let (group, member) = (i / 3, i % 3);
+            (0..10_000).map(|bit| bit / 100 == group * 10 && bit % 100 != member).collect()
+        })
+        .collect();
+    for (id, set) in sets.iter().enumerate() {
+        assert_eq!(index.insert(&hasher.bitvec_signature(set)), Some(id));
+    }
+    let pairs = index.similar_pairs(0.9, |i, j| sets[i].jaccard_similarity(&sets[j]));
+    let expected: Vec<(usize, usize)> = (0..10)
+        .flat_map(|group| vec![(3 * group, 3 * group + 1), (3 * group, 3 * group + 2), (3 * group + 1, 3 * group + 2)])
+        .collect();
+
+    assert_eq!(pairs.iter().map(|&(i, j, _)| (i, j)).collect::<Vec<_>>(), expected);
+    assert!(pairs.iter().all(|&(i, j, value)| value == sets[i].jaccard_similarity(&sets[j])));
+    assert_eq!(index.candidates(&hasher.bitvec_signature(&sets[4])), vec![3, 4, 5]);
+    assert_eq!(index.len(), 30);
+}
+
+#[test]
+fn test_lsh_rejects_short_signatures() {
+    let mut index = LshIndex::new(8, 8).unwrap();
+    let signature = MinHasher::new(32, 0).signature(vec![1, 2, 3]);
+
+    assert_eq!(index.insert(&signature), None);
+    assert!(index.candidates(&signature).is_empty());
+    assert!(index.is_empty());
+}
@@ -0,0 +1,133 @@
+extern crate bitset;
+
+
+use bitset::{
+    BitSet,
+    Cover,
+    MinimizeMode,
+    TernaryBitSet,
+    TruthTable,
+};
+
+
+const MODES: [MinimizeMode; 2] = [MinimizeMode::Exact, MinimizeMode::Heuristic];
+
+/// Compute the entries of the truth table of a cube over `vars` variables.
+fn product_table(vars: usize, cube: &TernaryBitSet) -> BitSet {
+    let function = cube.care().iter().fold(TruthTable::constant(vars, true).unwrap(), |function, var| {
+        let literal = TruthTable::var(vars, var).unwrap();
+        if cube.value().test(var) {
+            function & literal
+        } else {
+            function & !literal
+        }
+    });
+
+    function.table()
+}
+
+/// Check that a cover agrees with the on-set everywhere outside the don't-care
+/// set, and that every cube is a prime implicant.
+fn assert_valid_cover(cover: &Cover, on_set: BitSet, dont_care: BitSet) {
+    let vars = cover.vars();
+    let table = cover.truth_table().table();
+    for assignment in 0..(1 << vars) {
+        if !dont_care.test(assignment) {
+            assert_eq!(table.test(assignment), on_set.test(assignment), "assignment {}", assignment);
+        }
+    }
+    let allowed = on_set | dont_care;
+    for cube in cover.cubes() {
+        let cube_table = product_table(vars, cube);
+        assert!((cube_table & !allowed).none(), "{} is not an implicant", cube);
+        for var in cube.care().iter() {
+            let mut wider = *cube;
+            wider.set_digit(var, None);
+            let wider_table = product_table(vars, &wider);
+            assert!((wider_table & !allowed).any(), "{} is not prime", cube);
+        }
+    }
+}
+
+#[test]
+fn test_cubes_are_ternary_bit_sets() {
+    // The on-set of x0 & !x2 | x1 over three variables.
+    let on_set = BitSet::from_u64(0b1100_1110);
+    let cover = Cover::minimize(3, on_set, BitSet::new(), MinimizeMode::Exact).unwrap();
+    let expected: Vec<TernaryBitSet> = vec!["0x1".parse().unwrap(), "x1x".parse().unwrap()];
+
+    assert_eq!(cover.cubes(), &expected[..]);
+    assert!(cover.evaluate(0b011));
+    assert!(!cover.evaluate(0b101));
+}
+
+#[test]
+fn test_constants() {
+    for &mode in MODES.iter() {
+        let zero = Cover::minimize(3, BitSet::new(), BitSet::new(), mode).unwrap();
+        let one = Cover::minimize(3, BitSet::from_u64(0xFF), BitSet::new(), mode).unwrap();
+
+        assert_eq!(zero.to_string(), "0");
+        assert_eq!(one.to_string(), "1");
+    }
+}
+
+#[test]
+fn test_dont_cares_enlarge_cubes() {
+    // Entries 0..10 are the decimal digits, and 10..16 never occur.
+    let on_set = BitSet::from_u64(0b10_0001_0010);
+    let dont_care = BitSet::from_u64(0xFC00);
+    for &mode in MODES.iter() {
+        let cover = Cover::minimize(4, on_set, dont_care, mode).unwrap();
+
+        assert_valid_cover(&cover, on_set, dont_care);
+        assert_eq!(cover.to_string(), "x0 & !x1 & !x2 | !x0 & !x1 & x2");
+    }
+}
+
+#[test]
+fn test_exact_finds_minimum_of_cyclic_function() {
+    // A cyclic cover problem with no essential primes, where the exact
+    // minimum has three cubes.
+    let on_set = BitSet::from_u64(0b0111_1110);
+    let cover = Cover::minimize(3, on_set, BitSet::new(), MinimizeMode::Exact).unwrap();
+
+    assert_valid_cover(&cover, on_set, BitSet::new());
+    assert_eq!(cover.cubes().len(), 3);
+    assert_eq!(cover.literals(), 6);
+}
+
+#[test]
+fn test_covers_match_function() {
+    let mut state: u128 = 0x9E37_79B9_7F4A_7C15_F39C_C060_5CED_C834;
+    for vars in 1..=5 {
+        for _ in 0..8 {
+            state = state.wrapping_mul(0x2360_ED05_1FC6_5DA4_4385_DF64_9FCC_F645).wrapping_add(1);
+            let on_set = BitSet::from_u128(state);
+            let dont_care = BitSet::from_u128(state.rotate_left(37) & state.rotate_left(71));
+            let exact = Cover::minimize(vars, on_set, dont_care, MinimizeMode::Exact).unwrap();
+            let heuristic = Cover::minimize(vars, on_set, dont_care, MinimizeMode::Heuristic).unwrap();
+
+            assert_valid_cover(&exact, on_set & !dont_care, dont_care);
+            assert_valid_cover(&heuristic, on_set & !dont_care, dont_care);
+            assert!(exact.cubes().len() <= heuristic.cubes().len());
+        }
+    }
+}
+
+#[test]
+fn test_from_truth_table() {
+    let a = TruthTable::var(4, 0).unwrap();
+    let b = TruthTable::var(4, 1).unwrap();
+    let c = TruthTable::var(4, 2).unwrap();
+    let function = (a & b) | (a & c) | (b & c);
+    let cover = Cover::from_truth_table(&function, MinimizeMode::Exact);
+
+    assert_eq!(cover.truth_table(), function);
+    assert_eq!(cover.to_string(), "x0 & x1 | x0 & x2 | x1 & x2");
+}
+
+#[test]
+fn test_too_many_variables() {
+    assert!(Cover::minimize(8, BitSet::new(), BitSet::new(), MinimizeMode::Exact).is_none());
+}
@@ -0,0 +1,199 @@
+extern crate bitset;
+
+
+use bitset::{
+    BitSet,
+};
+
+
+/// Collect the codes of the two-dimensional points of the box from `min` to
+/// `max`, in increasing order.
+fn box_codes2(min: (u64, u64), max: (u64, u64)) -> Vec<u128> {
+    let mut codes: Vec<u128> = (min.0..=max.0)
+        .flat_map(|x| (min.1..=max.1).map(move |y| BitSet::morton_encode2(x, y).to_u128().unwrap()))
+        .collect();
+    codes.sort_unstable();
+
+    codes
+}
+
+#[test]
+fn test_encode2_decode2_round_trip() {
+    let points = [(0, 0), (1, 0), (0, 1), (12345, 67890), (u64::MAX, 0), (0, u64::MAX), (u64::MAX, u64::MAX)];
+    for &(x, y) in points.iter() {
+        let code = BitSet::morton_encode2(x, y);
+        assert_eq!(code.morton_decode2(), (x, y));
+    }
+    assert_eq!(BitSet::morton_encode2(u64::MAX, u64::MAX), BitSet::from_u128(u128::MAX));
+}
+
+#[test]
+fn test_encode2_follows_z_order() {
+    let codes: Vec<u128> = [(0, 0), (1, 0), (0, 1), (1, 1), (2, 0), (3, 0), (2, 1), (3, 1)]
+        .iter()
+        .map(|&(x, y)| BitSet::morton_encode2(x, y).to_u128().unwrap())
+        .collect();
+
+    assert_eq!(codes, vec![0, 1, 2, 3, 4, 5, 6, 7]);
+}
+
+#[test]
+fn test_encode3_decode3_round_trip() {
+    let max = (1 << 42) - 1;
+    let points = [(0, 0, 0), (1, 2, 3), (max, 0, max), (max, max, max)];
+    for &(x, y, z) in points.iter() {
+        let code = BitSet::morton_encode3(x, y, z).unwrap();
+        assert_eq!(code.morton_decode3(), (x, y, z));
+    }
+    assert_eq!(BitSet::morton_encode3(max, max, max), Some(BitSet::from_u128(u128::MAX >> 2)));
+    assert!(BitSet::morton_encode3(0, 1 << 42, 0).is_none());
+}
+
+#[test]
+fn test_neighbor2_matches_decoded_arithmetic() {
+    let code = BitSet::morton_encode2(100, 200);
+    for dx in -3..=3 {
+        for dy in -3..=3 {
+            let expected = BitSet::morton_encode2((100 + dx) as u64, (200 + dy) as u64);
+            assert_eq!(code.morton_neighbor2(dx, dy), Some(expected));
+        }
+    }
+}
+
+#[test]
+fn test_neighbor2_out_of_range() {
+    let corner = BitSet::morton_encode2(u64::MAX, 0);
+
+    assert_eq!(corner.morton_neighbor2(1, 0), None);
+    assert_eq!(corner.morton_neighbor2(0, -1), None);
+    assert_eq!(corner.morton_neighbor2(-1, 1), Some(BitSet::morton_encode2(u64::MAX - 1, 1)));
+}
+
+#[test]
+fn test_neighbor3() {
+    let max = (1 << 42) - 1;
+    let code = BitSet::morton_encode3(5, 6, 7).unwrap();
+
+    assert_eq!(code.morton_neighbor3(-5, 1, 10), BitSet::morton_encode3(0, 7, 17));
+    assert_eq!(code.morton_neighbor3(-6, 0, 0), None);
+    let corner = BitSet::morton_encode3(max, 0, 0).unwrap();
+    assert_eq!(corner.morton_neighbor3(1, 0, 0), None);
+}
+
+#[test]
+fn test_bigmin2_litmax2_match_brute_force() {
+    let (min, max) = ((2, 3), (5, 6));
+    let inside = box_codes2(min, max);
+    let min = BitSet::morton_encode2(min.0, min.1);
+    let max = BitSet::morton_encode2(max.0, max.1);
+    for code in 0..64_u128 {
+        let bitset = BitSet::from_u128(code);
+        let next = inside.iter().find(|&&inner| inner > code).map(|&inner| BitSet::from_u128(inner));
+        let prev = inside.iter().rev().find(|&&inner| inner < code).map(|&inner| BitSet::from_u128(inner));
+
+        assert_eq!(bitset.morton_bigmin2(&min, &max), next);
+        assert_eq!(bitset.morton_litmax2(&min, &max), prev);
+        assert_eq!(bitset.morton_in_box2(&min, &max), inside.contains(&code));
+    }
+}
+
+#[test]
+fn test_bigmin3_litmax3_match_brute_force() {
+    let min = BitSet::morton_encode3(1, 0, 2).unwrap();
+    let max = BitSet::morton_encode3(2, 3, 3).unwrap();
+    let inside: Vec<u128> = (0..64_u128)
+        .filter(|&code| BitSet::from_u128(code).morton_in_box3(&min, &max))
+        .collect();
+
+    assert_eq!(inside.len(), 2 * 4 * 2);
+    for code in 0..64_u128 {
+        let bitset = BitSet::from_u128(code);
+        let next = inside.iter().find(|&&inner| inner > code).map(|&inner| BitSet::from_u128(inner));
+        let prev = inside.iter().rev().find(|&&inner| inner < code).map(|&inner| BitSet::from_u128(inner));
+
+        assert_eq!(bitset.morton_bigmin3(&min, &max), next);
+        assert_eq!(bitset.morton_litmax3(&min, &max), prev);
+    }
+}
+
+#[test]
+fn test_decompose2_covers_box_exactly() {
+    let (min, max) = ((3, 1), (10, 6));
+    let inside = box_codes2(min, max);
+    let ranges = BitSet::morton_decompose2(&BitSet::morton_encode2(min.0, min.1), &BitSet::morton_encode2(max.0, max.1), 64);
+    let covered: Vec<u128> = ranges.iter()
+        .flat_map(|range| range.start().to_u128().unwrap()..=range.end().to_u128().unwrap())
+        .collect();
+
+    assert_eq!(covered, inside);
+    // The ranges are maximal, so no two of them touch.
+    for pair in ranges.windows(2) {
+        assert!(pair[0].end().to_u128().unwrap() + 1 < pair[1].start().to_u128().unwrap());
+    }
+}
+
+#[test]
+fn test_decompose2_whole_space() {
+    let ranges = BitSet::morton_decompose2(&BitSet::morton_encode2(0, 0), &BitSet::morton_encode2(u64::MAX, u64::MAX), 1);
+
+    assert_eq!(ranges, vec![BitSet::from_u128(0)..=BitSet::from_u128(u128::MAX)]);
+}
+
+#[test]
+fn test_decompose3_covers_box_exactly() {
+    let min = BitSet::morton_encode3(1, 2, 0).unwrap();
+    let max = BitSet::morton_encode3(4, 3, 5).unwrap();
+    let inside: Vec<u128> = (0..512_u128)
+        .filter(|&code| BitSet::from_u128(code).morton_in_box3(&min, &max))
+        .collect();
+    let covered: Vec<u128> = BitSet::morton_decompose3(&min, &max, 64)
+        .iter()
+        .flat_map(|range| range.start().to_u128().unwrap()..=range.end().to_u128().unwrap())
+        .collect();
+
+    assert_eq!(covered, inside);
+}
+
+#[test]
+fn test_decompose2_limits_ranges() {
+    let (min, max) = ((3, 1), (10, 6));
+    let inside = box_codes2(min, max);
+    let (min, max) = (BitSet::morton_encode2(min.0, min.1), BitSet::morton_encode2(max.0, max.1));
+    let exact = BitSet::morton_decompose2(&min, &max, 64);
+    for max_ranges in 0..exact.len() {
+        let ranges = BitSet::morton_decompose2(&min, &max, max_ranges);
+
+        assert!(!ranges.is_empty() && ranges.len() <= usize::max(max_ranges, 1));
+        assert_eq!(ranges[0].start(), &min);
+        assert_eq!(ranges[ranges.len() - 1].end(), &max);
+        for &code in inside.iter() {
+            assert!(ranges.iter().any(|range| {
+                (range.start().to_u128().unwrap()..=range.end().to_u128().unwrap()).contains(&code)
+            }));
+        }
+    }
+}
+
+#[test]
+fn test_decompose2_huge_box() {
+    // The exact decomposition of this box has about 2^63 intervals.
+    let min = BitSet::morton_encode2(1, 0);
+    let max = BitSet::morton_encode2(u64::MAX - 1, u64::MAX);
+    let ranges = BitSet::morton_decompose2(&min, &max, 100);
+
+    assert!(ranges.len() <= 100);
+    assert_eq!(ranges[0].start(), &min);
+    assert_eq!(ranges[ranges.len() - 1].end(), &max);
+    for pair in ranges.windows(2) {
+        assert!(pair[0].end().to_u128().unwrap() < pair[1].start().to_u128().unwrap());
+    }
+}
+
+#[test]
+fn test_decompose_empty_box() {
+    let min = BitSet::morton_encode2(5, 0);
+    let max = BitSet::morton_encode2(4, 9);
+
+    assert!(BitSet::morton_decompose2(&min, &max, 8).is_empty());
+    assert!(BitSet::morton_decompose3(&BitSet::morton_encode3(0, 2, 0).unwrap(), &BitSet::morton_encode3(1, 1, 1).unwrap(), 8).is_empty());
+}
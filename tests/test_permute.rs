@@ -0,0 +1,130 @@
+extern crate bitset;
+
+
+use bitset::{
+    BitPermutation,
+    BitSet,
+};
+
+
+/// Apply a permutation one bit at a time.
+fn apply_naive(destinations: &[usize], bitset: &BitSet) -> BitSet {
+    bitset.iter().map(|position| destinations[position]).collect()
+}
+
+/// Generate a pseudo-random permutation of `0..128` with a Fisher-Yates
+/// shuffle driven by a linear congruential generator.
+fn shuffled(seed: u64) -> Vec<usize> {
+    let mut state = seed;
+    let mut destinations: Vec<usize> = (0..128).collect();
+    for i in (1..128).rev() {
+        state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        let j = (state >> 33) as usize % (i + 1);
+        destinations.swap(i, j);
+    }
+
+    destinations
+}
+
+fn samples() -> Vec<BitSet> {
+    vec![
+        BitSet::new(),
+        BitSet::from_u128(u128::MAX),
+        BitSet::from_u128(0x0123_4567_89AB_CDEF_FEDC_BA98_7654_3210),
+        BitSet::from_u128(1),
+        BitSet::from_u128(1 << 127),
+        BitSet::from_u128(0xAAAA_AAAA_AAAA_AAAA_5555_5555_5555_5555),
+    ]
+}
+
+#[test]
+fn test_random_permutations_match_naive() {
+    for seed in 0..50 {
+        let destinations = shuffled(seed);
+        let permutation = BitPermutation::new(&destinations).unwrap();
+        for bitset in samples() {
+            assert_eq!(permutation.apply(&bitset), apply_naive(&destinations, &bitset));
+        }
+    }
+}
+
+#[test]
+fn test_every_single_bit_reaches_its_destination() {
+    let destinations = shuffled(7);
+    let permutation = BitPermutation::new(&destinations).unwrap();
+    for position in 0..128 {
+        let bit = BitSet::from_u128(1 << position);
+        assert_eq!(permutation.apply(&bit), BitSet::from_u128(1 << destinations[position]));
+        assert_eq!(permutation[position], destinations[position]);
+    }
+}
+
+#[test]
+fn test_identity() {
+    let permutation = BitPermutation::new(&(0..128).collect::<Vec<_>>()).unwrap();
+
+    assert_eq!(permutation, BitPermutation::identity());
+    for bitset in samples() {
+        assert_eq!(permutation.apply(&bitset), bitset);
+    }
+}
+
+#[test]
+fn test_invalid_permutations() {
+    let mut repeated: Vec<usize> = (0..128).collect();
+    repeated[5] = 6;
+    let mut out_of_range: Vec<usize> = (0..128).collect();
+    out_of_range[0] = 128;
+
+    assert!(BitPermutation::new(&repeated).is_none());
+    assert!(BitPermutation::new(&out_of_range).is_none());
+    assert!(BitPermutation::new(&[0, 1, 2]).is_none());
+}
+
+#[test]
+fn test_inverse_and_then() {
+    let first = BitPermutation::new(&shuffled(1)).unwrap();
+    let second = BitPermutation::new(&shuffled(2)).unwrap();
+    let composed = first.then(&second);
+    for bitset in samples() {
+        assert_eq!(first.inverse().apply(&first.apply(&bitset)), bitset);
+        assert_eq!(composed.apply(&bitset), second.apply(&first.apply(&bitset)));
+    }
+    assert_eq!(first.then(&first.inverse()).apply(&samples()[2]), samples()[2]);
+}
+
+#[test]
+fn test_equality_ignores_routing() {
+    for seed in 1..5 {
+        let permutation = BitPermutation::new(&shuffled(seed)).unwrap();
+        let inverse = permutation.inverse();
+
+        assert_eq!(inverse, BitPermutation::new(inverse.destinations()).unwrap());
+        assert_eq!(permutation.then(&inverse), BitPermutation::identity());
+        assert_ne!(permutation, inverse);
+    }
+}
+
+#[test]
+fn test_fixed_permutations_match_networks() {
+    let reverse = BitPermutation::new(&(0..128).map(|i| 127 - i).collect::<Vec<_>>()).unwrap();
+    let bytes = BitPermutation::new(&(0..128).map(|i| (15 - i / 8) * 8 + i % 8).collect::<Vec<_>>()).unwrap();
+    let nibbles = BitPermutation::new(&(0..128).map(|i| i ^ 4).collect::<Vec<_>>()).unwrap();
+    for bitset in samples() {
+        assert_eq!(bitset.reverse_bits(), reverse.apply(&bitset));
+        assert_eq!(bitset.swap_bytes(), bytes.apply(&bitset));
+        assert_eq!(bitset.swap_nibbles(), nibbles.apply(&bitset));
+    }
+}
+
+#[test]
+fn test_swap_bits() {
+    let mut bitset = BitSet::from_u128(1 << 127);
+    bitset.swap_bits(127, 0);
+
+    assert_eq!(bitset, BitSet::from_u128(1));
+    bitset.swap_bits(5, 6);
+    assert_eq!(bitset, BitSet::from_u128(1));
+    assert_eq!(bitset.swap_bits(128, 0), None);
+    assert_eq!(bitset, BitSet::from_u128(1));
+}
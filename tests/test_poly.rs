@@ -0,0 +1,168 @@
+extern crate bitset;
+
+
+use bitset::{
+    Gf2Field128,
+    Gf2Poly,
+};
+
+
+/// Multiply two polynomials bit by bit into a 256-bit product.
+fn naive_mul(left: u128, right: u128) -> (u128, u128) {
+    let mut product = [0_u128; 2];
+    for i in 0..128 {
+        for j in 0..128 {
+            if (left >> i) & 1 == 1 && (right >> j) & 1 == 1 {
+                product[(i + j) / 128] ^= 1 << ((i + j) % 128);
+            }
+        }
+    }
+
+    (product[0], product[1])
+}
+
+fn samples() -> Vec<u128> {
+    vec![
+        0,
+        1,
+        0b1011,
+        0xDEAD_BEEF,
+        0x8000_0000_0000_0000_0000_0000_0000_0001,
+        0x0123_4567_89AB_CDEF_FEDC_BA98_7654_3210,
+        u128::MAX,
+    ]
+}
+
+
+#[test]
+fn test_degree() {
+    assert_eq!(Gf2Poly::zero().degree(), None);
+    assert_eq!(Gf2Poly::one().degree(), Some(0));
+    assert_eq!(Gf2Poly::from_u128(1 << 127).degree(), Some(127));
+}
+
+#[test]
+fn test_widening_mul_matches_naive() {
+    for &left in samples().iter() {
+        for &right in samples().iter() {
+            let (low, high) = Gf2Poly::from_u128(left).widening_mul(&Gf2Poly::from_u128(right));
+            let expected = naive_mul(left, right);
+
+            assert_eq!((low.to_u128(), high.to_u128()), expected);
+        }
+    }
+}
+
+#[test]
+fn test_checked_mul() {
+    let x64 = Gf2Poly::from_u128(1 << 64);
+
+    assert_eq!(x64.checked_mul(&Gf2Poly::from_u128(1 << 63)), Some(Gf2Poly::from_u128(1 << 127)));
+    assert_eq!(x64.checked_mul(&x64), None);
+}
+
+#[test]
+fn test_div_rem_reconstructs_dividend() {
+    for &dividend in samples().iter() {
+        for &divisor in samples().iter().filter(|&&divisor| divisor != 0) {
+            let dividend = Gf2Poly::from_u128(dividend);
+            let divisor = Gf2Poly::from_u128(divisor);
+            let (quotient, remainder) = dividend.div_rem(&divisor).unwrap();
+            let product = quotient.checked_mul(&divisor).unwrap();
+
+            assert_eq!(product + remainder, dividend);
+            assert!(remainder.degree() < divisor.degree());
+        }
+    }
+}
+
+#[test]
+fn test_div_rem_by_zero() {
+    assert_eq!(Gf2Poly::one().div_rem(&Gf2Poly::zero()), None);
+}
+
+#[test]
+fn test_gcd() {
+    // (x + 1)(x^2 + x + 1) and (x + 1)^2 share the factor x + 1.
+    let left = Gf2Poly::from_u128(0b11).checked_mul(&Gf2Poly::from_u128(0b111)).unwrap();
+    let right = Gf2Poly::from_u128(0b101);
+
+    assert_eq!(left.gcd(&right), Gf2Poly::from_u128(0b11));
+    assert_eq!(left.gcd(&Gf2Poly::zero()), left);
+}
+
+#[test]
+fn test_mul_mod() {
+    let modulus = Gf2Poly::from_u128(0b1_0001_1011);
+    for &left in samples().iter() {
+        for &right in samples().iter() {
+            let left = Gf2Poly::from_u128(left);
+            let right = Gf2Poly::from_u128(right);
+            let left_reduced = left.div_rem(&modulus).unwrap().1;
+            let right_reduced = right.div_rem(&modulus).unwrap().1;
+            let expected = left_reduced.checked_mul(&right_reduced).unwrap().div_rem(&modulus).unwrap().1;
+
+            assert_eq!(left.mul_mod(&right, &modulus), Some(expected));
+        }
+    }
+}
+
+#[test]
+fn test_pow_mod_fermat() {
+    // x^8 + x^4 + x^3 + x + 1 is irreducible, so every non-zero element has an
+    // order dividing 255.
+    let modulus = Gf2Poly::from_u128(0b1_0001_1011);
+    for value in 1..256 {
+        let element = Gf2Poly::from_u128(value);
+
+        assert_eq!(element.pow_mod(255, &modulus), Some(Gf2Poly::one()));
+    }
+    assert_eq!(Gf2Poly::one().pow_mod(3, &Gf2Poly::zero()), None);
+}
+
+#[test]
+fn test_display() {
+    assert_eq!(Gf2Poly::zero().to_string(), "0");
+    assert_eq!(Gf2Poly::from_u128(0b1011).to_string(), "x^3 + x + 1");
+}
+
+#[test]
+fn test_field_mul_ghash_test_vector() {
+    // Test case 2 of the GCM specification: GHASH of a single ciphertext block.
+    let field = Gf2Field128::ghash();
+    let to_field = |block: u128| Gf2Poly::from_u128(block.reverse_bits());
+    let h = to_field(0x66E9_4BD4_EF8A_2C3B_884C_FA59_CA34_2B2E);
+    let ciphertext = to_field(0x0388_DACE_60B6_A392_F328_C2B9_71B2_FE78);
+    let lengths = to_field(0x0000_0000_0000_0000_0000_0000_0000_0080);
+    let x1 = field.mul(&ciphertext, &h);
+    let x2 = field.mul(&(x1 + lengths), &h);
+
+    assert_eq!(x2.to_u128().reverse_bits(), 0xF38C_BB1A_D692_23DC_C345_7AE5_B6B0_F885);
+}
+
+#[test]
+fn test_field_mul_is_commutative_and_distributive() {
+    let field = Gf2Field128::ghash();
+    for &a in samples().iter() {
+        for &b in samples().iter() {
+            let a = Gf2Poly::from_u128(a);
+            let b = Gf2Poly::from_u128(b);
+            let c = Gf2Poly::from_u128(0x0F0F_1234);
+
+            assert_eq!(field.mul(&a, &b), field.mul(&b, &a));
+            assert_eq!(field.mul(&a, &(b + c)), field.mul(&a, &b) + field.mul(&a, &c));
+        }
+    }
+}
+
+#[test]
+fn test_field_inverse() {
+    let field = Gf2Field128::ghash();
+    for &value in samples().iter().filter(|&&value| value != 0) {
+        let element = Gf2Poly::from_u128(value);
+        let inverse = field.inverse(&element).unwrap();
+
+        assert_eq!(field.mul(&element, &inverse), Gf2Poly::one());
+    }
+    assert_eq!(field.inverse(&Gf2Poly::zero()), None);
+}
@@ -0,0 +1,115 @@
+extern crate bitset;
+
+
+use bitset::{
+    BitSet,
+    ComponentRegistry,
+    Query,
+};
+
+
+#[test]
+fn test_empty_query_matches_everything() {
+    let query = Query::new();
+
+    assert!(query.matches(&BitSet::new()));
+    assert!(query.matches(&BitSet::from_u128(!0)));
+}
+
+#[test]
+fn test_matches_all() {
+    let query = Query::new().with_all(BitSet::from_u64(0b0110));
+
+    assert!(query.matches(&BitSet::from_u64(0b0110)));
+    assert!(query.matches(&BitSet::from_u64(0b1111)));
+    assert!(!query.matches(&BitSet::from_u64(0b0100)));
+}
+
+#[test]
+fn test_matches_any() {
+    let query = Query::new().with_any(BitSet::from_u64(0b0110));
+
+    assert!(query.matches(&BitSet::from_u64(0b0010)));
+    assert!(query.matches(&BitSet::from_u64(0b0100)));
+    assert!(!query.matches(&BitSet::from_u64(0b1001)));
+}
+
+#[test]
+fn test_matches_none() {
+    let query = Query::new().with_none(BitSet::from_u64(0b0110));
+
+    assert!(query.matches(&BitSet::from_u64(0b1001)));
+    assert!(!query.matches(&BitSet::from_u64(0b0010)));
+}
+
+#[test]
+fn test_matches_combined() {
+    let query = Query::from_masks(
+        BitSet::from_u64(0b0001),
+        BitSet::from_u64(0b0110),
+        BitSet::from_u64(0b1000),
+    );
+
+    assert!(query.matches(&BitSet::from_u64(0b0011)));
+    assert!(!query.matches(&BitSet::from_u64(0b0001)));
+    assert!(!query.matches(&BitSet::from_u64(0b1011)));
+    assert!(!query.matches(&BitSet::from_u64(0b0110)));
+}
+
+#[test]
+fn test_builder_accumulates_masks() {
+    let query = Query::new()
+        .with_all(BitSet::from_u64(0b01))
+        .with_all(BitSet::from_u64(0b10));
+
+    assert_eq!(query.all(), BitSet::from_u64(0b11));
+    assert_eq!(query.any(), BitSet::new());
+    assert_eq!(query.none(), BitSet::new());
+}
+
+#[test]
+fn test_filter() {
+    let signatures = vec![
+        BitSet::from_u64(0b0001),
+        BitSet::from_u64(0b0011),
+        BitSet::from_u64(0b1011),
+        BitSet::from_u64(0b0111),
+    ];
+    let query = Query::new()
+        .with_all(BitSet::from_u64(0b0011))
+        .with_none(BitSet::from_u64(0b1000));
+
+    assert_eq!(query.filter(&signatures), vec![1, 3]);
+}
+
+#[test]
+fn test_filter_into_appends() {
+    let signatures = vec![BitSet::from_u64(0b1), BitSet::from_u64(0b0)];
+    let query = Query::new().with_all(BitSet::from_u64(0b1));
+    let mut indices = vec![42];
+    query.filter_into(&signatures, &mut indices);
+
+    assert_eq!(indices, vec![42, 0]);
+}
+
+#[test]
+fn test_display_positions() {
+    let query = Query::new()
+        .with_all(BitSet::from_u64(0b101))
+        .with_none(BitSet::from_u64(0b1000));
+
+    assert_eq!(query.to_string(), "Query { all: [0, 2], any: [], none: [3] }");
+}
+
+#[test]
+fn test_display_with_registry() {
+    let mut registry = ComponentRegistry::new();
+    registry.register_name("position").unwrap();
+    registry.register_name("velocity").unwrap();
+    let query = Query::new()
+        .with_any(BitSet::from_u64(0b011))
+        .with_none(BitSet::from_u64(0b100));
+    let result = query.display(&registry).to_string();
+
+    assert_eq!(result, "Query { all: [], any: [position, velocity], none: [2] }");
+}
@@ -0,0 +1,197 @@
+extern crate bitset;
+
+
+use bitset::{
+    BitSet,
+    ComponentRegistry,
+    ComponentSet,
+    RegistryError,
+    RegistrySnapshot,
+};
+use std::any::TypeId;
+
+
+struct Position;
+struct Velocity;
+struct Health;
+
+
+#[test]
+fn test_register_assigns_consecutive_indices() {
+    let mut registry = ComponentRegistry::new();
+
+    assert_eq!(registry.register::<Position>(), Ok(0));
+    assert_eq!(registry.register::<Velocity>(), Ok(1));
+    assert_eq!(registry.register::<Health>(), Ok(2));
+    assert_eq!(registry.len(), 3);
+}
+
+#[test]
+fn test_register_twice_returns_same_index() {
+    let mut registry = ComponentRegistry::new();
+    let index = registry.register::<Position>().unwrap();
+
+    assert_eq!(registry.register::<Position>(), Ok(index));
+    assert_eq!(registry.len(), 1);
+}
+
+#[test]
+fn test_register_name_and_type_share_index() {
+    let mut registry = ComponentRegistry::new();
+    let index = registry.register_name(std::any::type_name::<Health>()).unwrap();
+
+    assert_eq!(registry.register::<Health>(), Ok(index));
+    assert_eq!(registry.index_of::<Health>(), Some(index));
+}
+
+#[test]
+fn test_register_types_sharing_a_name() {
+    // A collection that reports a different type under the name of `Position`,
+    // as two types with the same type name would.
+    struct Impostor;
+
+    impl ComponentSet for Impostor {
+        fn components() -> Vec<(TypeId, &'static str)> {
+            vec![(TypeId::of::<Velocity>(), std::any::type_name::<Position>())]
+        }
+    }
+
+    let mut registry = ComponentRegistry::new();
+    let index = registry.register::<Position>().unwrap();
+    let expected = Err(RegistryError::NameConflict { name: String::from(std::any::type_name::<Position>()) });
+
+    assert_eq!(registry.register_set::<Impostor>(), expected);
+    assert_eq!(registry.index_of_type_id(TypeId::of::<Velocity>()), None);
+    assert_eq!(registry.register::<Position>(), Ok(index));
+    assert_eq!(registry.len(), 1);
+}
+
+#[test]
+fn test_register_past_capacity() {
+    let mut registry = ComponentRegistry::new();
+    for i in 0..registry.capacity() {
+        registry.register_name(&format!("component{}", i)).unwrap();
+    }
+    let expected = Err(RegistryError::CapacityExceeded { capacity: registry.capacity() });
+
+    assert_eq!(registry.register::<Position>(), expected);
+    assert_eq!(registry.register_name("one too many"), expected);
+}
+
+#[test]
+fn test_signature() {
+    let mut registry = ComponentRegistry::new();
+    registry.register::<Position>().unwrap();
+    registry.register::<Velocity>().unwrap();
+    registry.register::<Health>().unwrap();
+    let expected = BitSet::from_u64(0b110);
+    let result = registry.signature::<(Velocity, Health)>();
+
+    assert_eq!(result, Ok(expected));
+}
+
+#[test]
+fn test_signature_not_registered() {
+    let mut registry = ComponentRegistry::new();
+    registry.register::<Position>().unwrap();
+    let result = registry.signature::<(Position, Velocity)>();
+
+    assert!(matches!(result, Err(RegistryError::NotRegistered { .. })));
+}
+
+#[test]
+fn test_register_set() {
+    let mut registry = ComponentRegistry::new();
+    let signature = registry.register_set::<(Position, Velocity)>().unwrap();
+
+    assert_eq!(signature, BitSet::from_u64(0b11));
+    assert_eq!(registry.signature::<(Velocity, Position)>(), Ok(signature));
+}
+
+#[test]
+fn test_signature_of_type_ids_and_names() {
+    let mut registry = ComponentRegistry::new();
+    registry.register::<Position>().unwrap();
+    registry.register_name("tag").unwrap();
+    let by_type_id = registry.signature_of_type_ids(vec![TypeId::of::<Position>()]);
+    let by_name = registry.signature_of_names(vec!["tag"]);
+
+    assert_eq!(by_type_id, Ok(BitSet::from_u64(0b01)));
+    assert_eq!(by_name, Ok(BitSet::from_u64(0b10)));
+}
+
+#[test]
+fn test_name_of() {
+    let mut registry = ComponentRegistry::new();
+    registry.register_name("position").unwrap();
+
+    assert_eq!(registry.name_of(0), Some("position"));
+    assert_eq!(registry.name_of(1), None);
+}
+
+#[test]
+fn test_snapshot_round_trip() {
+    let mut registry = ComponentRegistry::new();
+    registry.register::<Position>().unwrap();
+    registry.register_name("name with spaces").unwrap();
+    let snapshot = registry.snapshot();
+    let result = snapshot.to_string().parse::<RegistrySnapshot>();
+
+    assert_eq!(result, Ok(snapshot));
+}
+
+#[test]
+fn test_register_name_rejects_names_a_snapshot_cannot_hold() {
+    let mut registry = ComponentRegistry::new();
+    let names = ["", "two\nlines", "carriage\rreturn", "trailing\n", " leading", "\tleading"];
+    for name in names.iter() {
+        let expected = Err(RegistryError::InvalidName { name: String::from(*name) });
+        assert_eq!(registry.register_name(name), expected);
+    }
+
+    assert!(registry.is_empty());
+}
+
+#[test]
+fn test_snapshot_round_trip_of_unusual_names() {
+    let mut registry = ComponentRegistry::new();
+    let names = ["trailing space ", "tab\tinside", "0 looks like an entry", "ünïcödé", "x"];
+    for name in names.iter() {
+        registry.register_name(name).unwrap();
+    }
+    let snapshot = registry.snapshot();
+    let parsed = snapshot.to_string().parse::<RegistrySnapshot>().unwrap();
+    let restored = ComponentRegistry::from_snapshot(&parsed);
+
+    assert_eq!(parsed, snapshot);
+    for name in names.iter() {
+        assert_eq!(restored.index_of_name(name), registry.index_of_name(name));
+    }
+}
+
+#[test]
+fn test_from_snapshot_keeps_indices_stable() {
+    let snapshot: RegistrySnapshot = "5 velocity\n2 position\n".parse().unwrap();
+    let mut registry = ComponentRegistry::from_snapshot(&snapshot);
+
+    assert_eq!(registry.index_of_name("position"), Some(2));
+    assert_eq!(registry.register_name("velocity"), Ok(5));
+    assert_eq!(registry.register_name("health"), Ok(0));
+    assert_eq!(registry.register_name("armor"), Ok(1));
+    assert_eq!(registry.register_name("mana"), Ok(3));
+}
+
+#[test]
+fn test_snapshot_parse_errors() {
+    let duplicate_index = "0 position\n0 velocity\n".parse::<RegistrySnapshot>();
+    let duplicate_name = "0 position\n1 position\n".parse::<RegistrySnapshot>();
+    let out_of_range = "128 position\n".parse::<RegistrySnapshot>();
+    let missing_name = "3\n".parse::<RegistrySnapshot>();
+    let leading_whitespace = "3  position\n".parse::<RegistrySnapshot>();
+
+    assert_eq!(duplicate_index, Err(RegistryError::InvalidSnapshot { line: 2 }));
+    assert_eq!(duplicate_name, Err(RegistryError::InvalidSnapshot { line: 2 }));
+    assert_eq!(out_of_range, Err(RegistryError::InvalidSnapshot { line: 1 }));
+    assert_eq!(missing_name, Err(RegistryError::InvalidSnapshot { line: 1 }));
+    assert_eq!(leading_whitespace, Err(RegistryError::InvalidSnapshot { line: 1 }));
+}
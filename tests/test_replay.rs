@@ -0,0 +1,124 @@
+extern crate bitset;
+
+
+use bitset::{
+    BitSet,
+    BitVec,
+    ReplayStatus,
+    ReplayWindow,
+};
+use std::collections::BTreeSet;
+
+
+/// Check a window against a model that remembers every sequence number.
+fn check_against_model<S>(mut window: ReplayWindow<S>, sequence: &[u64]) where S: bitset::WindowStorage {
+    let size = window.size() as u64;
+    let mut seen = BTreeSet::new();
+    for &seq in sequence.iter() {
+        let highest = seen.iter().next_back().copied();
+        let expected = match highest {
+            Some(highest) if seq + size <= highest => ReplayStatus::TooOld,
+            _ if seen.contains(&seq) => ReplayStatus::Duplicate,
+            _ => ReplayStatus::Accepted,
+        };
+
+        assert_eq!(window.check_and_update(seq), expected, "sequence number {}", seq);
+        if expected == ReplayStatus::Accepted {
+            seen.insert(seq);
+        }
+    }
+}
+
+fn scrambled_sequence(length: u64, spread: u64) -> Vec<u64> {
+    let mut state: u64 = 0x853C_49E6_748F_EA9B;
+    (0..length)
+        .map(|i| {
+            state = state.wrapping_mul(6_364_136_223_846_793_005).wrapping_add(1_442_695_040_888_963_407);
+            (i + (state >> 33) % spread).saturating_sub(spread / 2)
+        })
+        .collect()
+}
+
+#[test]
+fn test_window_sizes() {
+    assert!(ReplayWindow::<BitSet>::new(0).is_none());
+    assert!(ReplayWindow::<BitSet>::new(64).is_some());
+    assert!(ReplayWindow::<BitSet>::new(128).is_some());
+    assert!(ReplayWindow::<BitSet>::new(129).is_none());
+    assert!(ReplayWindow::<BitVec>::new(0).is_none());
+    assert_eq!(ReplayWindow::<BitVec>::new(1024).unwrap().size(), 1024);
+}
+
+#[test]
+fn test_first_sequence_number_is_accepted() {
+    let mut window = ReplayWindow::<BitSet>::new(64).unwrap();
+
+    assert_eq!(window.highest(), None);
+    assert_eq!(window.check_and_update(0), ReplayStatus::Accepted);
+    assert_eq!(window.check_and_update(0), ReplayStatus::Duplicate);
+    assert_eq!(window.highest(), Some(0));
+}
+
+#[test]
+fn test_window_edges() {
+    let mut window = ReplayWindow::<BitSet>::new(128).unwrap();
+    window.check_and_update(1000);
+
+    assert_eq!(window.check(873), ReplayStatus::Accepted);
+    assert_eq!(window.check(872), ReplayStatus::TooOld);
+
+    // A jump of exactly the window size forgets every earlier number.
+    window.check_and_update(1128);
+    assert_eq!(window.check(1001), ReplayStatus::Accepted);
+    assert_eq!(window.check(1000), ReplayStatus::TooOld);
+}
+
+#[test]
+fn test_large_jumps() {
+    let mut window = ReplayWindow::<BitSet>::new(64).unwrap();
+    window.check_and_update(5);
+    window.check_and_update(4);
+
+    assert_eq!(window.check_and_update(u64::MAX), ReplayStatus::Accepted);
+    assert_eq!(window.check_and_update(u64::MAX - 1), ReplayStatus::Accepted);
+    assert_eq!(window.check_and_update(u64::MAX - 1), ReplayStatus::Duplicate);
+    assert_eq!(window.check_and_update(5), ReplayStatus::TooOld);
+}
+
+#[test]
+fn test_check_does_not_record() {
+    let mut window = ReplayWindow::<BitVec>::new(256).unwrap();
+    window.check_and_update(10);
+
+    assert_eq!(window.check(9), ReplayStatus::Accepted);
+    assert_eq!(window.check(9), ReplayStatus::Accepted);
+
+    window.update(9);
+    assert_eq!(window.check(9), ReplayStatus::Duplicate);
+}
+
+#[test]
+fn test_reset() {
+    let mut window = ReplayWindow::<BitSet>::new(32).unwrap();
+    window.check_and_update(100);
+    window.reset();
+
+    assert_eq!(window.highest(), None);
+    assert_eq!(window.check_and_update(1), ReplayStatus::Accepted);
+}
+
+#[test]
+fn test_bitset_windows_match_model() {
+    let sequence = scrambled_sequence(2000, 150);
+    for &size in [1, 32, 64, 100, 128].iter() {
+        check_against_model(ReplayWindow::<BitSet>::new(size).unwrap(), &sequence);
+    }
+}
+
+#[test]
+fn test_bitvec_windows_match_model() {
+    let sequence = scrambled_sequence(3000, 700);
+    for &size in [1, 64, 128, 129, 300, 1024].iter() {
+        check_against_model(ReplayWindow::<BitVec>::new(size).unwrap(), &sequence);
+    }
+}
@@ -0,0 +1,113 @@
+extern crate bitset;
+
+
+use bitset::{
+    BitSet,
+    BitVec,
+};
+use std::ops::Range;
+
+
+/// Collect the maximal runs of slots with the value `value` by testing every
+/// slot.
+fn naive_runs(bits: &[bool], value: bool) -> Vec<Range<usize>> {
+    let mut runs = Vec::new();
+    let mut start = 0;
+    while start < bits.len() {
+        if bits[start] != value {
+            start += 1;
+            continue;
+        }
+        let end = (start..bits.len()).find(|&i| bits[i] != value).unwrap_or(bits.len());
+        runs.push(start..end);
+        start = end;
+    }
+
+    runs
+}
+
+fn patterns() -> Vec<u128> {
+    vec![
+        0,
+        u128::MAX,
+        1,
+        1 << 127,
+        0b1110_0110_0001,
+        0xFFFF_0000_FFFF_00F0_0F0F_1111_0000_0001,
+        0xAAAA_AAAA_AAAA_AAAA_AAAA_AAAA_AAAA_AAAA,
+        0x7FFF_FFFF_FFFF_FFFF_FFFF_FFFF_FFFF_FFFE,
+    ]
+}
+
+fn sample_bitvecs() -> Vec<BitVec> {
+    let mut samples = vec![BitVec::new(), BitVec::zeros(300), BitVec::ones(300)];
+    for &length in [1, 127, 128, 129, 256, 300, 513].iter() {
+        samples.push((0..length).map(|i: usize| (i * 7 + i / 13) % 5 == 2).collect());
+        samples.push((0..length).map(|i: usize| (i / 64) % 3 != 1).collect());
+    }
+
+    samples
+}
+
+#[test]
+fn test_bitset_runs_match_naive() {
+    for &pattern in patterns().iter() {
+        let bitset = BitSet::from_u128(pattern);
+        let bits: Vec<bool> = (0..128).map(|i| bitset.test(i)).collect();
+        let ones = naive_runs(&bits, true);
+        let zeros = naive_runs(&bits, false);
+
+        assert_eq!(bitset.runs().collect::<Vec<_>>(), ones, "{:#X}", pattern);
+        assert_eq!(bitset.zero_runs().collect::<Vec<_>>(), zeros, "{:#X}", pattern);
+        assert_eq!(bitset.run_count(), ones.len());
+        assert_eq!(bitset.runs().len(), ones.len());
+    }
+}
+
+#[test]
+fn test_bitset_runs_reversed() {
+    for &pattern in patterns().iter() {
+        let bitset = BitSet::from_u128(pattern);
+        let mut forward: Vec<_> = bitset.runs().collect();
+        forward.reverse();
+
+        assert_eq!(bitset.runs().rev().collect::<Vec<_>>(), forward, "{:#X}", pattern);
+    }
+}
+
+#[test]
+fn test_bitset_runs_from_both_ends() {
+    let bitset = BitSet::from_u64(0b1011_0111);
+    let mut runs = bitset.runs();
+
+    assert_eq!(runs.next(), Some(0..3));
+    assert_eq!(runs.next_back(), Some(7..8));
+    assert_eq!(runs.next(), Some(4..6));
+    assert_eq!(runs.next_back(), None);
+    assert_eq!(runs.next(), None);
+}
+
+#[test]
+fn test_bitvec_runs_match_naive() {
+    for bitvec in sample_bitvecs().iter() {
+        let bits: Vec<bool> = (0..bitvec.len()).map(|i| bitvec.test(i)).collect();
+        let ones = naive_runs(&bits, true);
+
+        assert_eq!(bitvec.runs().collect::<Vec<_>>(), ones, "length {}", bitvec.len());
+        assert_eq!(bitvec.zero_runs().collect::<Vec<_>>(), naive_runs(&bits, false), "length {}", bitvec.len());
+        assert_eq!(bitvec.run_count(), ones.len());
+    }
+}
+
+#[test]
+fn test_bitvec_run_across_blocks() {
+    let mut bitvec = BitVec::zeros(400);
+    bitvec.allocate_run_aligned(200, 100);
+
+    assert_eq!(bitvec.runs().collect::<Vec<_>>(), vec![0..200]);
+    assert_eq!(bitvec.run_count(), 1);
+
+    bitvec.release_run(0, 100);
+    assert_eq!(bitvec.runs().collect::<Vec<_>>(), vec![100..200]);
+    assert_eq!(bitvec.zero_runs().collect::<Vec<_>>(), vec![0..100, 200..400]);
+}
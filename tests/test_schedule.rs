@@ -0,0 +1,140 @@
+extern crate bitset;
+
+
+use bitset::{
+    BitSet,
+    Conflict,
+    ConflictKind,
+    ScheduleError,
+    Scheduler,
+};
+
+
+#[test]
+fn test_empty_scheduler() {
+    let scheduler = Scheduler::new();
+    let schedule = scheduler.build().unwrap();
+
+    assert!(schedule.stages().is_empty());
+    assert!(schedule.conflicts().is_empty());
+}
+
+#[test]
+fn test_readers_share_a_stage() {
+    let mut scheduler = Scheduler::new();
+    let a = scheduler.add_system("a", BitSet::from_u64(0b11), BitSet::new());
+    let b = scheduler.add_system("b", BitSet::from_u64(0b01), BitSet::new());
+    let schedule = scheduler.build().unwrap();
+
+    assert_eq!(schedule.stages(), &[vec![a, b]]);
+}
+
+#[test]
+fn test_write_write_conflict() {
+    let mut scheduler = Scheduler::new();
+    let a = scheduler.add_system("a", BitSet::new(), BitSet::from_u64(0b110));
+    let b = scheduler.add_system("b", BitSet::new(), BitSet::from_u64(0b011));
+    let schedule = scheduler.build().unwrap();
+    let expected = Conflict {
+        first: a,
+        second: b,
+        kind: ConflictKind::WriteWrite,
+        components: BitSet::from_u64(0b010),
+    };
+
+    assert_eq!(schedule.stages(), &[vec![a], vec![b]]);
+    assert_eq!(schedule.conflicts(), &[expected]);
+}
+
+#[test]
+fn test_read_write_conflicts() {
+    let mut scheduler = Scheduler::new();
+    let a = scheduler.add_system("a", BitSet::from_u64(0b01), BitSet::new());
+    let b = scheduler.add_system("b", BitSet::new(), BitSet::from_u64(0b01));
+    let c = scheduler.add_system("c", BitSet::from_u64(0b01), BitSet::new());
+    let schedule = scheduler.build().unwrap();
+    let kinds: Vec<ConflictKind> = schedule.conflicts().iter().map(|c| c.kind).collect();
+
+    assert_eq!(schedule.stages(), &[vec![a], vec![b], vec![c]]);
+    assert_eq!(kinds, vec![ConflictKind::ReadWrite, ConflictKind::WriteRead]);
+}
+
+#[test]
+fn test_stages_are_conflict_free() {
+    let mut scheduler = Scheduler::new();
+    for i in 0..20 {
+        let reads = BitSet::from_u64(1 << (i % 7));
+        let writes = BitSet::from_u64(1 << ((i * 3) % 11));
+        scheduler.add_system(&format!("system{}", i), reads, writes);
+    }
+    let schedule = scheduler.build().unwrap();
+
+    for stage in schedule.stages() {
+        for &first in stage.iter() {
+            for &second in stage.iter().filter(|&&second| second != first) {
+                let first = scheduler.system(first).unwrap();
+                let second = scheduler.system(second).unwrap();
+                assert!((first.writes() & second.writes()).none());
+                assert!((first.reads() & second.writes()).none());
+            }
+        }
+    }
+}
+
+#[test]
+fn test_explicit_ordering() {
+    let mut scheduler = Scheduler::new();
+    let a = scheduler.add_system("a", BitSet::new(), BitSet::from_u64(0b01));
+    let b = scheduler.add_system("b", BitSet::new(), BitSet::from_u64(0b10));
+    scheduler.add_ordering(b, a).unwrap();
+    let schedule = scheduler.build().unwrap();
+
+    assert_eq!(schedule.stages(), &[vec![b], vec![a]]);
+    assert_eq!(schedule.stage_of(a), Some(1));
+}
+
+#[test]
+fn test_ordering_against_conflict_is_a_cycle() {
+    let mut scheduler = Scheduler::new();
+    let a = scheduler.add_system("a", BitSet::new(), BitSet::from_u64(0b01));
+    let b = scheduler.add_system("b", BitSet::new(), BitSet::from_u64(0b01));
+    scheduler.add_system("c", BitSet::new(), BitSet::from_u64(0b10));
+    scheduler.add_ordering(b, a).unwrap();
+    let result = scheduler.build();
+
+    assert_eq!(result, Err(ScheduleError::Cycle { systems: vec![a, b] }));
+}
+
+#[test]
+fn test_add_ordering_unknown_system() {
+    let mut other = Scheduler::new();
+    other.add_system("x", BitSet::new(), BitSet::new());
+    let unknown = other.add_system("y", BitSet::new(), BitSet::new());
+    let mut scheduler = Scheduler::new();
+    let a = scheduler.add_system("a", BitSet::new(), BitSet::new());
+
+    assert_eq!(scheduler.add_ordering(a, unknown), None);
+}
+
+#[test]
+fn test_conflict_display() {
+    let mut scheduler = Scheduler::new();
+    scheduler.add_system("a", BitSet::from_u64(0b1010), BitSet::new());
+    scheduler.add_system("b", BitSet::new(), BitSet::from_u64(0b1110));
+    let conflicts = scheduler.conflicts();
+
+    assert_eq!(conflicts[0].to_string(), "system 0 reads components [1, 3] that system 1 writes");
+}
+
+#[test]
+fn test_explain_uses_system_names() {
+    let mut scheduler = Scheduler::new();
+    scheduler.add_system("physics", BitSet::new(), BitSet::from_u64(0b101));
+    scheduler.add_system("collide", BitSet::new(), BitSet::from_u64(0b100));
+    let conflicts = scheduler.conflicts();
+
+    assert_eq!(
+        scheduler.explain(&conflicts[0]),
+        "`physics` and `collide` both write components [2]"
+    );
+}
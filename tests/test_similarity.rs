@@ -0,0 +1,92 @@
+extern crate bitset;
+
+
+use bitset::{
+    BitSet,
+    BitVec,
+};
+
+
+fn samples() -> Vec<BitSet> {
+    vec![
+        BitSet::new(),
+        BitSet::from_u128(u128::MAX),
+        BitSet::from_u64(0b1011_0110),
+        BitSet::from_u128(0xFFFF << 100),
+        BitSet::from_u128(0x0123_4567_89AB_CDEF_FEDC_BA98_7654_3210),
+    ]
+}
+
+#[test]
+fn test_counts_match_materialized_sets() {
+    for a in samples() {
+        for b in samples() {
+            assert_eq!(a.and_count(&b), (a & b).count());
+            assert_eq!(a.or_count(&b), (a | b).count());
+            assert_eq!(a.xor_count(&b), (a ^ b).count());
+            assert_eq!(a.andnot_count(&b), (a & !b).count());
+            assert_eq!(a.hamming_distance(&b), a.xor_count(&b));
+        }
+    }
+}
+
+#[test]
+fn test_similarities_of_identical_sets() {
+    for a in samples() {
+        assert_eq!(a.hamming_distance(&a), 0);
+        assert_eq!(a.jaccard_similarity(&a), 1.0);
+        assert_eq!(a.dice(&a), 1.0);
+        assert_eq!(a.cosine(&a), 1.0);
+        assert_eq!(a.overlap_coefficient(&a), 1.0);
+    }
+}
+
+#[test]
+fn test_similarities() {
+    let a = BitSet::from_u64(0b0000_1111);
+    let b = BitSet::from_u64(0b0011_1100);
+
+    assert_eq!(a.jaccard_similarity(&b), 2.0 / 6.0);
+    assert_eq!(a.dice(&b), 0.5);
+    assert_eq!(a.cosine(&b), 0.5);
+    assert_eq!(a.overlap_coefficient(&b), 0.5);
+    assert_eq!(a.overlap_coefficient(&BitSet::from_u64(0b0011)), 1.0);
+}
+
+#[test]
+fn test_similarities_with_an_empty_set() {
+    let a = BitSet::from_u64(0b0110);
+    let empty = BitSet::new();
+
+    assert_eq!(a.jaccard_similarity(&empty), 0.0);
+    assert_eq!(a.dice(&empty), 0.0);
+    assert_eq!(empty.cosine(&a), 0.0);
+    assert_eq!(empty.overlap_coefficient(&a), 0.0);
+    assert_eq!(a.hamming_distance(&empty), 2);
+}
+
+#[test]
+fn test_similarities_are_symmetric() {
+    for a in samples() {
+        for b in samples() {
+            assert_eq!(a.jaccard_similarity(&b), b.jaccard_similarity(&a));
+            assert_eq!(a.dice(&b), b.dice(&a));
+            assert_eq!(a.cosine(&b), b.cosine(&a));
+            assert_eq!(a.overlap_coefficient(&b), b.overlap_coefficient(&a));
+        }
+    }
+}
+
+#[test]
+fn test_bitvec_counts_of_different_lengths() {
+    let a: BitVec = (0..300).map(|i| i % 2 == 0).collect();
+    let b: BitVec = (0..130).map(|i| i % 4 == 0).collect();
+
+    assert_eq!(a.and_count(&b), (a.clone() & b.clone()).count());
+    assert_eq!(a.or_count(&b), (a.clone() | b.clone()).count());
+    assert_eq!(a.xor_count(&b), (a.clone() ^ b.clone()).count());
+    assert_eq!(b.andnot_count(&a), 0);
+    assert_eq!(a.andnot_count(&b), 150 - 33);
+    assert_eq!(a.jaccard_similarity(&b), 33.0 / 150.0);
+    assert_eq!(BitVec::zeros(10).jaccard_similarity(&BitVec::new()), 1.0);
+}
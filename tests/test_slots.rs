@@ -0,0 +1,172 @@
+extern crate bitset;
+
+
+use bitset::{
+    BitSet,
+    BitVec,
+};
+use std::ops::Range;
+
+
+/// Find the first aligned run of free slots by testing every slot.
+fn naive_find(occupied: &[bool], len: usize, align: usize) -> Option<usize> {
+    (0..=occupied.len())
+        .step_by(align)
+        .find(|&start| start + len <= occupied.len() && occupied[start..start + len].iter().all(|&slot| !slot))
+}
+
+fn naive_longest(occupied: &[bool]) -> Option<Range<usize>> {
+    let mut longest: Option<Range<usize>> = None;
+    let mut start = 0;
+    while start < occupied.len() {
+        if occupied[start] {
+            start += 1;
+            continue;
+        }
+        let end = (start..occupied.len()).find(|&i| occupied[i]).unwrap_or(occupied.len());
+        let longer = match longest.as_ref() {
+            Some(longest) => end - start > longest.len(),
+            None => true,
+        };
+        if longer {
+            longest = Some(start..end);
+        }
+        start = end;
+    }
+
+    longest
+}
+
+fn patterns() -> Vec<u128> {
+    vec![
+        0,
+        u128::MAX,
+        0b1100_0110_0001,
+        0xFFFF_0000_FFFF_00F0_0F0F_1111_0000_0001,
+        0x8000_0000_0000_0000_0000_0000_0000_0000,
+        0x0000_0000_0000_FFFF_FFFF_0000_0000_0000,
+        0xAAAA_AAAA_AAAA_AAAA_AAAA_AAAA_AAAA_AAAA,
+        0x0123_4567_89AB_CDEF_FEDC_BA98_7654_3210,
+    ]
+}
+
+fn bitset_slots(bitset: &BitSet) -> Vec<bool> {
+    (0..bitset.capacity()).map(|i| bitset.test(i)).collect()
+}
+
+fn bitvec_slots(bitvec: &BitVec) -> Vec<bool> {
+    (0..bitvec.len()).map(|i| bitvec.test(i)).collect()
+}
+
+fn sample_bitvecs() -> Vec<BitVec> {
+    let mut samples = vec![BitVec::new(), BitVec::zeros(300), BitVec::ones(300)];
+    for &length in [5, 127, 128, 129, 300, 513].iter() {
+        samples.push((0..length).map(|i: usize| (i * 7 + i / 13) % 5 == 2).collect());
+        samples.push((0..length).map(|i: usize| (i / 40) % 3 == 1).collect());
+    }
+
+    samples
+}
+
+#[test]
+fn test_bitset_find_free_run_matches_naive() {
+    for &pattern in patterns().iter() {
+        let bitset = BitSet::from_u128(pattern);
+        let slots = bitset_slots(&bitset);
+        for len in 0..=130 {
+            assert_eq!(bitset.find_free_run(len), naive_find(&slots, len, 1), "{:#X} len {}", pattern, len);
+            for &align in [1, 2, 3, 8, 64].iter() {
+                assert_eq!(bitset.find_free_run_aligned(len, align), naive_find(&slots, len, align));
+            }
+        }
+    }
+}
+
+#[test]
+fn test_bitset_longest_free_run_matches_naive() {
+    for &pattern in patterns().iter() {
+        let bitset = BitSet::from_u128(pattern);
+
+        assert_eq!(bitset.longest_free_run(), naive_longest(&bitset_slots(&bitset)), "{:#X}", pattern);
+    }
+}
+
+#[test]
+fn test_bitset_allocate_and_release() {
+    let mut calendar = BitSet::new();
+
+    assert_eq!(calendar.allocate_run(100), Some(0));
+    assert_eq!(calendar.allocate_run(29), None);
+    assert_eq!(calendar.allocate_run_aligned(8, 16), Some(112));
+    assert_eq!(calendar.release_run(10, 20), Some(()));
+    assert!(calendar.is_free_run(10, 20));
+    assert!(!calendar.is_free_run(9, 20));
+    assert_eq!(calendar.allocate_run(20), Some(10));
+    assert_eq!(calendar.release_run(120, 9), None);
+    assert_eq!(calendar.release_run(0, 128), Some(()));
+    assert!(calendar.none());
+    assert_eq!(calendar.allocate_run(128), Some(0));
+    assert!(calendar.all());
+}
+
+#[test]
+fn test_bitset_aligned_rejects_zero_alignment() {
+    assert_eq!(BitSet::new().find_free_run_aligned(1, 0), None);
+}
+
+#[test]
+fn test_bitset_next_one_and_zero() {
+    let bitset = BitSet::from_u128(0b1011 | (1 << 127));
+
+    assert_eq!(bitset.next_one(2), Some(3));
+    assert_eq!(bitset.next_one(4), Some(127));
+    assert_eq!(bitset.next_one(128), None);
+    assert_eq!(bitset.next_zero(0), Some(2));
+    assert_eq!(bitset.next_zero(127), None);
+}
+
+#[test]
+fn test_bitvec_find_free_run_matches_naive() {
+    for bitvec in sample_bitvecs().iter() {
+        let slots = bitvec_slots(bitvec);
+        for &len in [0, 1, 2, 3, 39, 40, 41, 80, 128, 129, 300].iter() {
+            assert_eq!(bitvec.find_free_run(len), naive_find(&slots, len, 1), "length {} run {}", bitvec.len(), len);
+            for &align in [1, 3, 16, 128].iter() {
+                assert_eq!(bitvec.find_free_run_aligned(len, align), naive_find(&slots, len, align));
+            }
+        }
+    }
+}
+
+#[test]
+fn test_bitvec_longest_free_run_matches_naive() {
+    for bitvec in sample_bitvecs().iter() {
+        assert_eq!(bitvec.longest_free_run(), naive_longest(&bitvec_slots(bitvec)));
+    }
+}
+
+#[test]
+fn test_bitvec_next_one_and_zero() {
+    let mut bitvec = BitVec::ones(200);
+    bitvec.set(150, false);
+
+    assert_eq!(bitvec.next_zero(0), Some(150));
+    assert_eq!(bitvec.next_zero(151), None);
+    assert_eq!(bitvec.next_one(150), Some(151));
+    assert_eq!(BitVec::zeros(200).next_one(0), None);
+}
+
+#[test]
+fn test_bitvec_allocate_and_release() {
+    let mut pages = BitVec::zeros(1000);
+
+    assert_eq!(pages.allocate_run(300), Some(0));
+    assert_eq!(pages.allocate_run_aligned(10, 256), Some(512));
+    assert_eq!(pages.release_run(50, 200), Some(()));
+    assert!(pages.is_free_run(50, 200));
+    assert_eq!(pages.count(), 110);
+    assert_eq!(pages.allocate_run(200), Some(50));
+    assert_eq!(pages.release_run(990, 11), None);
+    assert_eq!(pages.allocate_run(479), None);
+    assert_eq!(pages.allocate_run(478), Some(522));
+}
@@ -0,0 +1,171 @@
+extern crate bitset;
+
+
+use bitset::{
+    BitSet,
+    BitVec,
+};
+
+
+fn binomial(n: u64, k: u64) -> u64 {
+    (0..k).fold(1, |result, i| result * (n - i) / (i + 1))
+}
+
+#[test]
+fn test_subsets_are_all_submasks_in_decreasing_order() {
+    let mask = BitSet::from_u128(0b1011_0100 | (1 << 100) | (1 << 127));
+    let subsets: Vec<BitSet> = mask.subsets().collect();
+
+    assert_eq!(subsets.len(), 1 << mask.count());
+    assert!(subsets.iter().all(|subset| (subset & !mask).none()));
+    assert!(subsets.windows(2).all(|pair| pair[0].to_u128() > pair[1].to_u128()));
+    assert_eq!(subsets.first(), Some(&mask));
+    assert_eq!(subsets.last(), Some(&BitSet::new()));
+}
+
+#[test]
+fn test_subsets_reversed() {
+    let mask = BitSet::from_u64(0b1101_0010);
+    let mut forward: Vec<BitSet> = mask.subsets().collect();
+    forward.reverse();
+
+    assert_eq!(mask.subsets().rev().collect::<Vec<_>>(), forward);
+}
+
+#[test]
+fn test_subsets_from_both_ends_meet() {
+    let mask = BitSet::from_u64(0b111);
+    for split in 0..=8 {
+        let mut subsets = mask.subsets();
+        let mut front: Vec<BitSet> = subsets.by_ref().take(split).collect();
+        let back: Vec<BitSet> = subsets.rev().collect();
+        front.extend(back.into_iter().rev());
+
+        assert_eq!(front, mask.subsets().collect::<Vec<_>>());
+    }
+}
+
+#[test]
+fn test_subsets_of_empty_set() {
+    assert_eq!(BitSet::new().subsets().collect::<Vec<_>>(), vec![BitSet::new()]);
+}
+
+#[test]
+fn test_combinations_counts() {
+    for n in 0..=10 {
+        for k in 0..=n {
+            let combinations: Vec<BitSet> = BitSet::combinations(n, k).collect();
+
+            assert_eq!(combinations.len() as u64, binomial(n as u64, k as u64), "n {} k {}", n, k);
+            assert!(combinations.iter().all(|c| c.count() == k && c.iter().all(|i| i < n)));
+            assert!(combinations.windows(2).all(|pair| pair[0].to_u128() < pair[1].to_u128()));
+        }
+    }
+}
+
+#[test]
+fn test_combinations_at_capacity() {
+    assert_eq!(BitSet::combinations(128, 128).collect::<Vec<_>>(), vec![!BitSet::new()]);
+    assert_eq!(BitSet::combinations(128, 0).collect::<Vec<_>>(), vec![BitSet::new()]);
+    assert_eq!(BitSet::combinations(128, 127).count(), 128);
+    assert_eq!(BitSet::combinations(128, 1).next_back(), Some(BitSet::from_u128(1 << 127)));
+    assert_eq!(BitSet::combinations(129, 1).count(), 0);
+    assert_eq!(BitSet::combinations(3, 4).count(), 0);
+}
+
+#[test]
+fn test_combinations_reversed() {
+    let mut forward: Vec<BitSet> = BitSet::combinations(9, 4).collect();
+    forward.reverse();
+
+    assert_eq!(BitSet::combinations(9, 4).rev().collect::<Vec<_>>(), forward);
+}
+
+#[test]
+fn test_next_same_popcount() {
+    assert_eq!(BitSet::new().next_same_popcount(), None);
+    assert_eq!(BitSet::from_u64(1).next_same_popcount(), Some(BitSet::from_u64(2)));
+    assert_eq!(BitSet::from_u64(0b1011).next_same_popcount(), Some(BitSet::from_u64(0b1101)));
+    assert_eq!(BitSet::from_u128(1 << 127).next_same_popcount(), None);
+    assert_eq!(
+        BitSet::from_u128(0xFFFF_FFFF_FFFF_FFFF).next_same_popcount(),
+        Some(BitSet::from_u128(0x1_7FFF_FFFF_FFFF_FFFF))
+    );
+}
+
+#[test]
+fn test_bitvec_combinations_match_bitset() {
+    for n in 0..=9 {
+        for k in 0..=n {
+            let expected: Vec<Vec<usize>> = BitSet::combinations(n, k).map(|c| c.iter().collect()).collect();
+            let result: Vec<Vec<usize>> = BitVec::combinations(n, k).map(|c| c.iter().collect()).collect();
+            let reversed: Vec<Vec<usize>> = BitVec::combinations(n, k).rev().map(|c| c.iter().collect()).collect();
+
+            assert_eq!(result, expected, "n {} k {}", n, k);
+            assert_eq!(reversed, expected.into_iter().rev().collect::<Vec<_>>());
+        }
+    }
+}
+
+#[test]
+fn test_bitvec_combinations_across_blocks() {
+    let combinations: Vec<BitVec> = BitVec::combinations(200, 2).collect();
+
+    assert_eq!(combinations.len(), 200 * 199 / 2);
+    assert!(combinations.iter().all(|c| c.len() == 200 && c.count() == 2));
+    assert_eq!(combinations[127 * 126 / 2].iter().collect::<Vec<_>>(), vec![0, 127]);
+    assert_eq!(combinations[128 * 127 / 2].iter().collect::<Vec<_>>(), vec![0, 128]);
+    assert_eq!(BitVec::combinations(3, 4).count(), 0);
+}
+
+#[test]
+fn test_bitvec_next_same_popcount() {
+    let mut bitvec = BitVec::zeros(130);
+    bitvec.set(129, true);
+
+    assert_eq!(bitvec.next_same_popcount(), None);
+    assert_eq!(BitVec::zeros(10).next_same_popcount(), None);
+}
+
+#[test]
+fn test_bitvec_subsets_across_blocks() {
+    let positions = [1, 126, 127, 128, 129, 255, 300];
+    let mut mask = BitVec::zeros(320);
+    for &position in positions.iter() {
+        mask.set(position, true);
+    }
+    // Subset `i` in decreasing order has the positions of the bits of
+    // `2^7 - 1 - i`, counting the positions from the lowest one up.
+    let expected: Vec<Vec<usize>> = (0..1_usize << positions.len())
+        .rev()
+        .map(|bits| (0..positions.len()).filter(|&i| bits & (1 << i) != 0).map(|i| positions[i]).collect())
+        .collect();
+    let subsets: Vec<BitVec> = mask.subsets().collect();
+    let reversed: Vec<Vec<usize>> = mask.subsets().rev().map(|subset| subset.iter().collect()).collect();
+
+    assert!(subsets.iter().all(|subset| subset.len() == 320));
+    assert_eq!(subsets.iter().map(|subset| subset.iter().collect()).collect::<Vec<Vec<usize>>>(), expected);
+    assert_eq!(reversed, expected.into_iter().rev().collect::<Vec<_>>());
+}
+
+#[test]
+fn test_bitvec_subsets_from_both_ends_meet() {
+    let mut mask = BitVec::zeros(256);
+    for &position in [0, 127, 128, 200].iter() {
+        mask.set(position, true);
+    }
+    for split in 0..=16 {
+        let mut subsets = mask.subsets();
+        let mut front: Vec<BitVec> = subsets.by_ref().take(split).collect();
+        let back: Vec<BitVec> = subsets.rev().collect();
+        front.extend(back.into_iter().rev());
+
+        assert_eq!(front, mask.subsets().collect::<Vec<_>>());
+    }
+}
+
+#[test]
+fn test_bitvec_subsets_of_empty_set() {
+    assert_eq!(BitVec::zeros(130).subsets().collect::<Vec<_>>(), vec![BitVec::zeros(130)]);
+    assert_eq!(BitVec::new().subsets().collect::<Vec<_>>(), vec![BitVec::new()]);
+}
@@ -0,0 +1,143 @@
+extern crate bitset;
+
+
+use bitset::{
+    BitSet,
+    Classifier,
+    ParseTernaryError,
+    TernaryBitSet,
+};
+
+
+fn ternary(string: &str) -> TernaryBitSet {
+    string.parse().unwrap()
+}
+
+#[test]
+fn test_parse_and_format_round_trip() {
+    for string in ["", "0", "1", "x", "10x1xx", "xxxx0000111100001111xxxx"].iter() {
+        let pattern = ternary(string);
+
+        assert_eq!(pattern.as_string_width(string.len()), *string);
+        assert_eq!(pattern.as_string().parse::<TernaryBitSet>(), Ok(pattern));
+    }
+}
+
+#[test]
+fn test_parse_digits() {
+    let pattern = ternary("1*X0");
+
+    assert_eq!(pattern.value(), BitSet::from_u64(0b1000));
+    assert_eq!(pattern.care(), BitSet::from_u64(0b1001));
+    assert_eq!(pattern.digit(3), Some(true));
+    assert_eq!(pattern.digit(2), None);
+    assert_eq!(pattern.digit(0), Some(false));
+    assert_eq!(pattern.digit(100), None);
+}
+
+#[test]
+fn test_parse_errors() {
+    assert_eq!(
+        "10a1".parse::<TernaryBitSet>(),
+        Err(ParseTernaryError::InvalidCharacter { position: 2, character: 'a' })
+    );
+    let long = "x".repeat(129);
+
+    assert_eq!(long.parse::<TernaryBitSet>(), Err(ParseTernaryError::TooLong { length: 129 }));
+    assert!("x".repeat(128).parse::<TernaryBitSet>().is_ok());
+}
+
+#[test]
+fn test_matches() {
+    let pattern = ternary("1x0");
+    let matching: Vec<u64> = (0..8).filter(|&key| pattern.matches(&BitSet::from_u64(key))).collect();
+
+    assert_eq!(matching, vec![0b100, 0b110]);
+    assert!(TernaryBitSet::any().matches(&BitSet::from_u64(0xDEAD_BEEF)));
+    assert!(TernaryBitSet::exact(BitSet::from_u64(5)).matches(&BitSet::from_u64(5)));
+    assert!(!TernaryBitSet::exact(BitSet::from_u64(5)).matches(&BitSet::from_u64(4)));
+}
+
+#[test]
+fn test_intersect_matches_both() {
+    let patterns = ["1xx0", "x1x0", "xxx1", "0xxx", "1010", "xxxx"];
+    for left in patterns.iter() {
+        for right in patterns.iter() {
+            let (left, right) = (ternary(left), ternary(right));
+            let both: Vec<u64> = (0..16)
+                .filter(|&key| left.matches(&BitSet::from_u64(key)) && right.matches(&BitSet::from_u64(key)))
+                .collect();
+            match left.intersect(&right) {
+                Some(intersection) => {
+                    let matched: Vec<u64> = (0..16)
+                        .filter(|&key| intersection.matches(&BitSet::from_u64(key)))
+                        .collect();
+
+                    assert!(left.overlaps(&right));
+                    assert_eq!(matched, both);
+                }
+                None => {
+                    assert!(!left.overlaps(&right));
+                    assert!(both.is_empty());
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_subsumes() {
+    assert!(ternary("1xxx").subsumes(&ternary("10x1")));
+    assert!(!ternary("10x1").subsumes(&ternary("1xxx")));
+    assert!(!ternary("0xxx").subsumes(&ternary("10x1")));
+    assert!(TernaryBitSet::any().subsumes(&ternary("0101")));
+    assert!(ternary("0101").subsumes(&ternary("0101")));
+}
+
+#[test]
+fn test_set_digit() {
+    let mut pattern = TernaryBitSet::any();
+    pattern.set_digit(2, Some(true));
+    pattern.set_digit(0, Some(false));
+
+    assert_eq!(pattern.as_string_width(3), "1x0");
+    assert_eq!(pattern.dont_care_count(), 126);
+    assert_eq!(pattern.set_digit(128, None), None);
+}
+
+#[test]
+fn test_classifier_priorities() {
+    let mut classifier = Classifier::new();
+    classifier.insert(ternary("xxxx"), 0, 0);
+    classifier.insert(ternary("1xxx"), 5, 1);
+    classifier.insert(ternary("x1xx"), 5, 2);
+    classifier.insert(ternary("11x1"), 9, 3);
+
+    assert_eq!(classifier.len(), 4);
+    assert_eq!(classifier.classify(&BitSet::from_u64(0b1101)), Some(&3));
+    // Equal priorities keep the order of insertion.
+    assert_eq!(classifier.classify(&BitSet::from_u64(0b1100)), Some(&1));
+    assert_eq!(classifier.classify(&BitSet::from_u64(0b0100)), Some(&2));
+    assert_eq!(classifier.classify(&BitSet::from_u64(0b0000)), Some(&0));
+    assert_eq!(classifier.lookup(&BitSet::from_u64(0b0100)).unwrap().priority(), 5);
+}
+
+#[test]
+fn test_classifier_without_match() {
+    let mut classifier = Classifier::new();
+    classifier.insert(ternary("1x"), 1, "high");
+
+    assert_eq!(classifier.classify(&BitSet::from_u64(0b01)), None);
+    assert!(Classifier::<()>::new().is_empty());
+}
+
+#[test]
+fn test_classifier_shadowed() {
+    let mut classifier = Classifier::new();
+    classifier.insert(ternary("1xxx"), 5, 'a');
+    classifier.insert(ternary("10x1"), 1, 'b');
+    classifier.insert(ternary("0xx1"), 1, 'c');
+    let shadowed: Vec<char> = classifier.shadowed().iter().map(|rule| *rule.action()).collect();
+
+    assert_eq!(shadowed, vec!['b']);
+}
@@ -0,0 +1,86 @@
+extern crate bitset;
+
+
+use bitset::{
+    BitSet,
+    transpose_128,
+    transpose_64,
+    transpose_8,
+};
+
+
+/// Generate a pseudo-random word with a linear congruential generator.
+fn next_word(state: &mut u64) -> u64 {
+    *state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+
+    *state ^ (*state >> 29)
+}
+
+#[test]
+fn test_transpose_128_matches_naive() {
+    let mut state = 1;
+    let mut block = [BitSet::new(); 128];
+    for row in block.iter_mut() {
+        let high = next_word(&mut state) as u128;
+        *row = BitSet::from_u128((high << 64) | next_word(&mut state) as u128);
+    }
+    let original = block;
+    transpose_128(&mut block);
+
+    for (i, row) in original.iter().enumerate() {
+        for (j, column) in block.iter().enumerate() {
+            assert_eq!(column.test(i), row.test(j));
+        }
+    }
+    transpose_128(&mut block);
+    assert_eq!(block[..], original[..]);
+}
+
+#[test]
+fn test_transpose_128_identity_and_row() {
+    let mut block = [BitSet::new(); 128];
+    for (i, row) in block.iter_mut().enumerate() {
+        row.set(i, true);
+    }
+    let identity = block;
+    transpose_128(&mut block);
+
+    assert_eq!(block[..], identity[..]);
+    let mut block = [BitSet::new(); 128];
+    block[5] = BitSet::from_u128(u128::MAX);
+    transpose_128(&mut block);
+    assert!(block.iter().all(|row| *row == BitSet::from_u128(1 << 5)));
+}
+
+#[test]
+fn test_transpose_64_matches_naive() {
+    let mut state = 2;
+    let mut block = [0_u64; 64];
+    for row in block.iter_mut() {
+        *row = next_word(&mut state);
+    }
+    let original = block;
+    transpose_64(&mut block);
+
+    for (i, row) in original.iter().enumerate() {
+        for (j, column) in block.iter().enumerate() {
+            assert_eq!((column >> i) & 1, (row >> j) & 1);
+        }
+    }
+}
+
+#[test]
+fn test_transpose_8_matches_naive() {
+    let mut state = 3;
+    for _ in 0..100 {
+        let original = next_word(&mut state).to_le_bytes();
+        let mut block = original;
+        transpose_8(&mut block);
+
+        for (i, row) in original.iter().enumerate() {
+            for (j, column) in block.iter().enumerate() {
+                assert_eq!((column >> i) & 1, (row >> j) & 1);
+            }
+        }
+    }
+}
@@ -0,0 +1,183 @@
+extern crate bitset;
+
+
+use bitset::{
+    BitSet,
+    TruthTable,
+};
+
+
+/// Evaluate a truth table entry by entry after renaming its variables.
+fn naive_permute(function: &TruthTable, permutation: &[usize]) -> TruthTable {
+    let vars = function.vars();
+    let mut table = BitSet::new();
+    for source in 0..(1 << vars) {
+        let target = (0..vars)
+            .filter(|&var| source & (1 << var) != 0)
+            .fold(0, |target, var| target | (1 << permutation[var]));
+        table.set(target, function.evaluate(source));
+    }
+
+    TruthTable::new(vars, table).unwrap()
+}
+
+#[test]
+fn test_var_evaluates_to_its_input() {
+    for vars in 1..=TruthTable::MAX_VARS {
+        for var in 0..vars {
+            let projection = TruthTable::var(vars, var).unwrap();
+            for assignment in 0..(1 << vars) {
+                assert_eq!(projection.evaluate(assignment), assignment & (1 << var) != 0);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_var_out_of_range() {
+    assert!(TruthTable::var(3, 3).is_none());
+    assert!(TruthTable::var(8, 0).is_none());
+    assert!(TruthTable::new(8, BitSet::new()).is_none());
+}
+
+#[test]
+fn test_new_discards_unused_entries() {
+    let function = TruthTable::new(2, BitSet::from_u64(0xFF)).unwrap();
+
+    assert_eq!(function.table(), BitSet::from_u64(0xF));
+    assert_eq!(function, TruthTable::constant(2, true).unwrap());
+}
+
+#[test]
+fn test_not_stays_within_entries() {
+    let a = TruthTable::var(3, 0).unwrap();
+
+    assert_eq!((!a).table(), BitSet::from_u64(0x55));
+    assert_eq!(!!a, a);
+}
+
+#[test]
+fn test_operators_match_evaluation() {
+    let a = TruthTable::var(3, 0).unwrap();
+    let b = TruthTable::var(3, 1).unwrap();
+    let c = TruthTable::var(3, 2).unwrap();
+    let function = (a & !b) ^ (b | c);
+    for assignment in 0..8 {
+        let x = [assignment & 1 != 0, assignment & 2 != 0, assignment & 4 != 0];
+        let expected = (x[0] && !x[1]) ^ (x[1] || x[2]);
+
+        assert_eq!(function.evaluate(assignment), expected);
+    }
+}
+
+#[test]
+#[should_panic]
+fn test_operators_reject_mismatched_vars() {
+    let _ = TruthTable::var(2, 0).unwrap() & TruthTable::var(3, 0).unwrap();
+}
+
+#[test]
+fn test_shannon_expansion() {
+    let function = TruthTable::new(7, BitSet::from_u128(0x0123_4567_89AB_CDEF_FEDC_BA98_7654_3210)).unwrap();
+    for var in 0..7 {
+        let x = TruthTable::var(7, var).unwrap();
+        let low = function.cofactor(var, false).unwrap();
+        let high = function.cofactor(var, true).unwrap();
+
+        assert!(!low.depends_on(var));
+        assert!(!high.depends_on(var));
+        assert_eq!((x & high) | (!x & low), function);
+    }
+}
+
+#[test]
+fn test_support() {
+    let a = TruthTable::var(5, 0).unwrap();
+    let d = TruthTable::var(5, 3).unwrap();
+    let e = TruthTable::var(5, 4).unwrap();
+    let function = (a & d) | (e & !e);
+
+    assert_eq!(function.support(), BitSet::from_u64(0b01001));
+    assert!(function.depends_on(3));
+    assert!(!function.depends_on(4));
+    assert!(TruthTable::constant(5, true).unwrap().support().none());
+}
+
+#[test]
+fn test_swap_matches_permute() {
+    let function = TruthTable::new(6, BitSet::from_u64(0x1234_5678_9ABC_DEF0)).unwrap();
+    for i in 0..6 {
+        for j in 0..6 {
+            let mut permutation: Vec<usize> = (0..6).collect();
+            permutation.swap(i, j);
+
+            assert_eq!(function.swap(i, j), function.permute(&permutation));
+        }
+    }
+}
+
+#[test]
+fn test_permute_matches_naive() {
+    let function = TruthTable::new(4, BitSet::from_u64(0xB5C3)).unwrap();
+    let permutation = [2, 0, 3, 1];
+
+    assert_eq!(function.permute(&permutation), Some(naive_permute(&function, &permutation)));
+}
+
+#[test]
+fn test_permute_rejects_invalid_permutations() {
+    let function = TruthTable::var(3, 0).unwrap();
+
+    assert!(function.permute(&[0, 1]).is_none());
+    assert!(function.permute(&[0, 1, 1]).is_none());
+    assert!(function.permute(&[0, 1, 3]).is_none());
+}
+
+#[test]
+fn test_negate_input() {
+    let a = TruthTable::var(3, 0).unwrap();
+    let b = TruthTable::var(3, 1).unwrap();
+    let function = a & b;
+
+    assert_eq!(function.negate_input(1), Some(a & !b));
+    assert!(function.negate_input(3).is_none());
+}
+
+#[test]
+fn test_npn_canonical_is_invariant() {
+    let function = TruthTable::new(4, BitSet::from_u64(0x6A3C)).unwrap();
+    let (canonical, transform) = function.npn_canonical();
+
+    assert_eq!(transform.apply(&function), Some(canonical));
+
+    let equivalent = !function.negate_input(2).unwrap().swap(0, 3).unwrap().swap(1, 2).unwrap();
+    let (other, other_transform) = equivalent.npn_canonical();
+
+    assert_eq!(other, canonical);
+    assert_eq!(other_transform.apply(&equivalent), Some(canonical));
+}
+
+#[test]
+fn test_npn_classes_of_two_variables() {
+    let classes: BitSet = (0..16)
+        .map(|table| {
+            let function = TruthTable::new(2, BitSet::from_u64(table)).unwrap();
+            let (canonical, _) = function.npn_canonical();
+
+            canonical.table().to_u64().unwrap() as usize
+        })
+        .collect();
+
+    // Constants, projections, AND-like functions, and XOR-like functions.
+    assert_eq!(classes.count(), 4);
+}
+
+#[test]
+fn test_display() {
+    let a = TruthTable::var(2, 0).unwrap();
+    let b = TruthTable::var(2, 1).unwrap();
+
+    assert_eq!((a & b).to_string(), "8");
+    assert_eq!(TruthTable::var(1, 0).unwrap().to_string(), "2");
+    assert_eq!(TruthTable::var(4, 3).unwrap().to_string(), "FF00");
+}